@@ -0,0 +1,50 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use plasmid::prelude::*;
+
+/// A circular 10 kb plasmid with pseudo-random nucleotides.
+fn plasmid() -> DnaSequence {
+    let mut state = 42u64;
+    let seq = (0..10_000)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ['A', 'C', 'G', 'T'][(state >> 33) as usize % 4]
+        })
+        .collect::<String>();
+    let mut seq = DnaSequence::from_str(seq).unwrap();
+    seq.set_topology(Topology::Circular);
+    seq
+}
+
+/// Compare the single-pass scanner to searching for each enzyme separately,
+/// run with `cargo bench`.
+fn bench_restriction_scan(c: &mut Criterion) {
+    let seq = plasmid();
+    let mut group = c.benchmark_group("restriction scan 10 kb");
+    group.bench_function("one search per enzyme", |b| {
+        b.iter(|| {
+            RestrictionEnzymes
+                .iter()
+                .map(|enzyme| {
+                    let mut found = seq.find_pattern(&enzyme.recognition_site()).len();
+                    if !enzyme.is_palindromic() {
+                        found += seq.find_pattern(&enzyme.reverse_complement_site()).len();
+                    }
+                    found
+                })
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("single pass", |b| {
+        b.iter(|| {
+            let mut seq = seq.clone();
+            seq.annotate_restriction_enzymes();
+            seq.as_annotations().len()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_restriction_scan);
+criterion_main!(benches);
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
-    eaa::Eaa,
+    eaa::{Eaa, GeneticCode},
     rna::{RnaCodon, RnaNucleotide},
     traits::*,
 };
@@ -29,10 +29,32 @@ impl DnaCodon {
     pub fn translate(&self) -> Eaa {
         Eaa::from(&self.transcribe())
     }
+
+    /// Translate the codon using an alternative genetic code.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    /// use plasmid::traits::TryFromStr;
+    ///
+    /// // Mycoplasma read TGA as tryptophan
+    /// let codon = DnaCodon::try_from_str("TGA").unwrap();
+    /// assert_eq!(codon.translate(), Eaa::Ter);
+    /// assert_eq!(codon.translate_with(GeneticCode::from_id(4).unwrap()), Eaa::Trp);
+    /// ```
+    pub fn translate_with(&self, code: &GeneticCode) -> Eaa {
+        code.translate(&self.transcribe())
+    }
 }
 
 impl_codon_traits!(DnaNucleotide => DnaCodon);
 
+impl From<DnaCodon> for RnaCodon {
+    fn from(codon: DnaCodon) -> RnaCodon {
+        codon.transcribe()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::DnaCodon;
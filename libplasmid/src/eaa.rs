@@ -1,5 +1,8 @@
+#[allow(clippy::module_inception)]
 pub mod eaa;
+pub mod genetic_code;
 pub mod qualified_eaa;
 
 pub use self::eaa::*;
+pub use self::genetic_code::*;
 pub use self::qualified_eaa::*;
@@ -3,7 +3,7 @@ use crate::rna::RnaCodon;
 use crate::traits::*;
 
 /// Essential Amino Acid
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Eaa {
     /// Any
     Any,
@@ -57,7 +57,7 @@ pub enum Eaa {
 
 impl Eaa {
     pub(crate) fn all_as_str() -> &'static str {
-        "-*ABCDEFGHIKLMNPQRSVWXY"
+        "-*ABCDEFGHIKLMNPQRSTVWXY"
     }
 
     pub fn is_eaa(c: &char) -> bool {
@@ -130,10 +130,10 @@ impl ToLetter for Eaa {
     }
 }
 
-impl ToString for Eaa {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Eaa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use self::Eaa::*;
-        match self {
+        let name = match self {
             Ala => "Alanine",
             Arg => "Arginine",
             Asn => "Asparagine",
@@ -158,8 +158,8 @@ impl ToString for Eaa {
             Tyr => "Tyrosine",
             Val => "Valine",
             Any => "(any)",
-        }
-        .to_string()
+        };
+        write!(f, "{}", name)
     }
 }
 
@@ -0,0 +1,363 @@
+use anyhow::bail;
+
+use super::Eaa;
+use crate::{
+    rna::{RnaCodon, RnaNucleotide},
+    traits::*,
+};
+
+/// A genetic code (translation table) as published by the NCBI.
+///
+/// Amino acids and start codons are stored as 64 letter strings in the NCBI
+/// codon order (`TCAG` for each of the three bases). `M` marks a start codon
+/// in the start codon string.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GeneticCode {
+    id: u8,
+    name: &'static str,
+    amino_acids: &'static str,
+    starts: &'static str,
+}
+
+macro_rules! genetic_code {
+    ($id: expr, $name: expr, $amino_acids: expr, $starts: expr) => {
+        GeneticCode {
+            id: $id,
+            name: $name,
+            amino_acids: $amino_acids,
+            starts: $starts,
+        }
+    };
+}
+
+static GENETIC_CODES: [GeneticCode; 27] = [
+    genetic_code!(
+        1,
+        "Standard",
+        "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "---M------**--*----M---------------M----------------------------"
+    ),
+    genetic_code!(
+        2,
+        "Vertebrate Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+        "----------**--------------------MMMM----------**---M------------"
+    ),
+    genetic_code!(
+        3,
+        "Yeast Mitochondrial",
+        "FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------**----------------------MM---------------M------------"
+    ),
+    genetic_code!(
+        4,
+        "Mold, Protozoan, and Coelenterate Mitochondrial and Mycoplasma/Spiroplasma",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "--MM------**-------M------------MMMM---------------M------------"
+    ),
+    genetic_code!(
+        5,
+        "Invertebrate Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+        "---M------**--------------------MMMM---------------M------------"
+    ),
+    genetic_code!(
+        6,
+        "Ciliate, Dasycladacean and Hexamita Nuclear",
+        "FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "--------------*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        9,
+        "Echinoderm and Flatworm Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        "----------**-----------------------M---------------M------------"
+    ),
+    genetic_code!(
+        10,
+        "Euplotid Nuclear",
+        "FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------**-----------------------M----------------------------"
+    ),
+    genetic_code!(
+        11,
+        "Bacterial, Archaeal and Plant Plastid",
+        "FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "---M------**--*----M------------MMMM---------------M------------"
+    ),
+    genetic_code!(
+        12,
+        "Alternative Yeast Nuclear",
+        "FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------**--*----M---------------M----------------------------"
+    ),
+    genetic_code!(
+        13,
+        "Ascidian Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG",
+        "---M------**----------------------MM---------------M------------"
+    ),
+    genetic_code!(
+        14,
+        "Alternative Flatworm Mitochondrial",
+        "FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        "-----------*-----------------------M----------------------------"
+    ),
+    genetic_code!(
+        15,
+        "Blepharisma Macronuclear",
+        "FFLLSSSSYY*QCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------*---*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        16,
+        "Chlorophycean Mitochondrial",
+        "FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------*---*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        21,
+        "Trematode Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        "----------**-----------------------M---------------M------------"
+    ),
+    genetic_code!(
+        22,
+        "Scenedesmus obliquus Mitochondrial",
+        "FFLLSS*SYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "------*---*---*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        23,
+        "Thraustochytrium Mitochondrial",
+        "FF*LSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "--*-------**--*-----------------M--M---------------M------------"
+    ),
+    genetic_code!(
+        24,
+        "Rhabdopleuridae Mitochondrial",
+        "FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        "---M------**-------M---------------M---------------M------------"
+    ),
+    genetic_code!(
+        25,
+        "Candidate Division SR1 and Gracilibacteria",
+        "FFLLSSSSYY**CCGWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "---M------**-----------------------M---------------M------------"
+    ),
+    genetic_code!(
+        26,
+        "Pachysolen tannophilus Nuclear",
+        "FFLLSSSSYY**CC*WLLLAPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------**--*----M---------------M----------------------------"
+    ),
+    genetic_code!(
+        27,
+        "Karyorelict Nuclear",
+        "FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "--------------*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        28,
+        "Condylostoma Nuclear",
+        "FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------**--*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        29,
+        "Mesodinium Nuclear",
+        "FFLLSSSSYYYYCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "--------------*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        30,
+        "Peritrich Nuclear",
+        "FFLLSSSSYYEECC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "--------------*--------------------M----------------------------"
+    ),
+    genetic_code!(
+        31,
+        "Blastocrithidia Nuclear",
+        "FFLLSSSSYYEECCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "----------**-----------------------M----------------------------"
+    ),
+    genetic_code!(
+        32,
+        "Balanophoraceae Plastid",
+        "FFLLSSSSYY*WCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        "---M------*---*----M------------MMMM---------------M------------"
+    ),
+    genetic_code!(
+        33,
+        "Cephalodiscidae Mitochondrial",
+        "FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        "---M-------*-------M---------------M---------------M------------"
+    ),
+];
+
+impl GeneticCode {
+    /// The standard genetic code (NCBI table 1).
+    pub fn standard() -> &'static GeneticCode {
+        &GENETIC_CODES[0]
+    }
+
+    /// All genetic codes, ordered by their NCBI table id.
+    pub fn all() -> &'static [GeneticCode] {
+        &GENETIC_CODES
+    }
+
+    /// Look up a genetic code by its NCBI table id (`transl_table`).
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let code = GeneticCode::from_id(2).unwrap();
+    /// assert_eq!(code.name(), "Vertebrate Mitochondrial");
+    /// assert!(GeneticCode::from_id(7).is_err());
+    /// ```
+    pub fn from_id(id: u8) -> anyhow::Result<&'static GeneticCode> {
+        match GENETIC_CODES.iter().find(|code| code.id == id) {
+            Some(code) => Ok(code),
+            None => bail!("Unknown genetic code: {}", id),
+        }
+    }
+
+    /// The NCBI table id.
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// The NCBI name of the genetic code.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Translate a codon to its amino acid.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    /// use plasmid::traits::TryFromStr;
+    ///
+    /// let codon = RnaCodon::try_from_str("UGA").unwrap();
+    /// assert_eq!(GeneticCode::standard().translate(&codon), Eaa::Ter);
+    /// assert_eq!(GeneticCode::from_id(4).unwrap().translate(&codon), Eaa::Trp);
+    /// ```
+    pub fn translate(&self, codon: &RnaCodon) -> Eaa {
+        let letter = self.amino_acids.as_bytes()[Self::index(codon)] as char;
+        Eaa::try_from_letter(letter).expect("genetic code tables only contain amino acids")
+    }
+
+    /// Whether the codon can start translation.
+    pub fn is_start(&self, codon: &RnaCodon) -> bool {
+        self.starts.as_bytes()[Self::index(codon)] == b'M'
+    }
+
+    /// Whether the codon terminates translation.
+    pub fn is_stop(&self, codon: &RnaCodon) -> bool {
+        self.translate(codon) == Eaa::Ter
+    }
+
+    /// All codons that can start translation.
+    pub fn start_codons(&self) -> Vec<RnaCodon> {
+        Self::codons()
+            .filter(|codon| self.is_start(codon))
+            .collect()
+    }
+
+    /// All codons that terminate translation.
+    pub fn stop_codons(&self) -> Vec<RnaCodon> {
+        Self::codons().filter(|codon| self.is_stop(codon)).collect()
+    }
+
+    /// All 64 codons in the NCBI codon order.
+    fn codons() -> impl Iterator<Item = RnaCodon> {
+        use RnaNucleotide::*;
+        let bases = [U, C, A, G];
+        (0..64).map(move |i| {
+            RnaCodon::from_triplet_arr([bases[i / 16], bases[i / 4 % 4], bases[i % 4]])
+        })
+    }
+
+    /// The position of a codon in the NCBI codon order.
+    fn index(codon: &RnaCodon) -> usize {
+        use RnaNucleotide::*;
+        codon.to_triplet_arr().iter().fold(0, |index, base| {
+            index * 4
+                + match base {
+                    U => 0,
+                    C => 1,
+                    A => 2,
+                    G => 3,
+                }
+        })
+    }
+}
+
+impl Default for GeneticCode {
+    fn default() -> Self {
+        *GeneticCode::standard()
+    }
+}
+
+impl std::fmt::Display for GeneticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GeneticCode;
+    use crate::{eaa::Eaa, rna::RnaCodon, traits::*};
+
+    #[test]
+    fn test_genetic_code_tables() {
+        for code in GeneticCode::all() {
+            assert_eq!(code.amino_acids.len(), 64, "{}", code);
+            assert_eq!(code.starts.len(), 64, "{}", code);
+            assert!(
+                code.amino_acids.chars().all(|c| Eaa::is_eaa(&c)),
+                "{}",
+                code
+            );
+            assert!(!code.start_codons().is_empty(), "{}", code);
+        }
+        let ids = GeneticCode::all()
+            .iter()
+            .map(|code| code.id())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                1, 2, 3, 4, 5, 6, 9, 10, 11, 12, 13, 14, 15, 16, 21, 22, 23, 24, 25, 26, 27, 28,
+                29, 30, 31, 32, 33
+            ]
+        );
+    }
+
+    #[test]
+    fn test_genetic_code_standard_matches_eaa() {
+        let code = GeneticCode::standard();
+        for codon in GeneticCode::codons() {
+            assert_eq!(code.translate(&codon), Eaa::from(&codon), "{}", codon);
+        }
+        let starts = code
+            .start_codons()
+            .iter()
+            .map(|codon| codon.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(starts, ["UUG", "CUG", "AUG"]);
+    }
+
+    #[test]
+    fn test_genetic_code_vertebrate_mitochondrial() -> anyhow::Result<()> {
+        let code = GeneticCode::from_id(2)?;
+        assert_eq!(code.translate(&RnaCodon::try_from_str("AUA")?), Eaa::Met);
+        assert_eq!(code.translate(&RnaCodon::try_from_str("UGA")?), Eaa::Trp);
+        assert_eq!(code.translate(&RnaCodon::try_from_str("AGA")?), Eaa::Ter);
+        assert_eq!(code.stop_codons().len(), 4);
+        Ok(())
+    }
+}
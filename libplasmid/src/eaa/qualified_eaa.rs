@@ -20,15 +20,15 @@ impl Deref for QualifiedEaa {
     }
 }
 
-impl Into<Eaa> for QualifiedEaa {
-    fn into(self) -> Eaa {
-        self.eaa
+impl From<QualifiedEaa> for Eaa {
+    fn from(qualified: QualifiedEaa) -> Eaa {
+        qualified.eaa
     }
 }
 
-impl Into<RnaCodon> for QualifiedEaa {
-    fn into(self) -> RnaCodon {
-        self.codon
+impl From<QualifiedEaa> for RnaCodon {
+    fn from(qualified: QualifiedEaa) -> RnaCodon {
+        qualified.codon
     }
 }
 
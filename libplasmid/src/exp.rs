@@ -1,5 +1,7 @@
 pub mod export;
+pub mod gel;
 pub mod svg;
 
 pub use self::export::*;
+pub use self::gel::*;
 pub use self::svg::*;
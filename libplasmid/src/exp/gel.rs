@@ -0,0 +1,384 @@
+use crate::sim::Fragment;
+
+use super::Export;
+
+/// A DNA size standard run next to the samples.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GelLadder {
+    /// 1 kb ladder, 500 bp to 10 kb
+    Kb1,
+    /// 100 bp ladder, 100 bp to 1.5 kb
+    Bp100,
+    /// Lambda DNA digested with HindIII, 125 bp to 23 kb
+    LambdaHindIII,
+    /// Bands of the given size in bp and mass in ng
+    Custom(Vec<GelBand>),
+}
+
+impl GelLadder {
+    /// The bands of the ladder, with the masses of a typical 0.5 µg load.
+    pub fn bands(&self) -> Vec<GelBand> {
+        let bands = |bands: &[(usize, f32)]| {
+            bands
+                .iter()
+                .map(|&(size, mass)| GelBand { size, mass })
+                .collect()
+        };
+        match self {
+            GelLadder::Kb1 => bands(&[
+                (10000, 42.0),
+                (8000, 42.0),
+                (6000, 50.0),
+                (5000, 42.0),
+                (4000, 33.0),
+                (3000, 125.0),
+                (2000, 48.0),
+                (1500, 36.0),
+                (1000, 42.0),
+                (500, 40.0),
+            ]),
+            GelLadder::Bp100 => bands(&[
+                (1517, 22.5),
+                (1200, 17.5),
+                (1000, 47.5),
+                (900, 13.5),
+                (800, 12.0),
+                (700, 10.5),
+                (600, 9.0),
+                (500, 48.5),
+                (400, 19.0),
+                (300, 14.5),
+                (200, 12.5),
+                (100, 24.0),
+            ]),
+            GelLadder::LambdaHindIII => {
+                let sizes = [23130, 9416, 6557, 4361, 2322, 2027, 564, 125];
+                GelLane::equimolar("", sizes, 500.0).bands
+            }
+            GelLadder::Custom(bands) => bands.clone(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            GelLadder::Kb1 => "1 kb",
+            GelLadder::Bp100 => "100 bp",
+            GelLadder::LambdaHindIII => "λ HindIII",
+            GelLadder::Custom(_) => "Ladder",
+        }
+    }
+}
+
+/// A band of DNA molecules of the same size.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GelBand {
+    /// Size in bp
+    pub size: usize,
+    /// Mass in ng
+    pub mass: f32,
+}
+
+/// A lane of a gel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GelLane {
+    pub label: String,
+    pub bands: Vec<GelBand>,
+}
+
+impl GelLane {
+    pub fn new(label: &str, bands: Vec<GelBand>) -> Self {
+        Self {
+            label: label.to_string(),
+            bands,
+        }
+    }
+
+    /// A lane of equimolar molecules of the given sizes, e.g. the fragments of
+    /// a digest, loaded with `mass` ng of DNA in total.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let lane = GelLane::equimolar("EcoRI", [3000, 1000], 400.0);
+    /// assert_eq!(lane.bands[0].mass, 300.0);
+    /// ```
+    pub fn equimolar<I>(label: &str, sizes: I, mass: f32) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let sizes = sizes.into_iter().collect::<Vec<_>>();
+        let total = sizes.iter().sum::<usize>().max(1) as f32;
+        let bands = sizes
+            .into_iter()
+            .map(|size| GelBand {
+                size,
+                mass: mass * size as f32 / total,
+            })
+            .collect();
+        Self::new(label, bands)
+    }
+
+    /// A lane of the fragments of a digest, loaded with `mass` ng of DNA in total.
+    pub fn from_fragments(label: &str, fragments: &[Fragment], mass: f32) -> Self {
+        Self::equimolar(label, fragments.iter().map(|f| f.len()), mass)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GelExportConfig {
+    /// Agarose concentration in percent (w/v)
+    pub agarose: f32,
+    /// Ladder run in the first lane
+    pub ladder: Option<GelLadder>,
+    /// Height of the gel below the wells in px
+    pub height: f32,
+    pub lane_width: f32,
+    /// Thickness of a band in px, bands closer than this co-migrate
+    pub band_thickness: f32,
+    /// Mass in ng at which a band is fully saturated
+    pub saturation: f32,
+}
+
+impl Default for GelExportConfig {
+    fn default() -> Self {
+        Self {
+            agarose: 1.0,
+            ladder: Some(GelLadder::Kb1),
+            height: 400.0,
+            lane_width: 60.0,
+            band_thickness: 4.0,
+            saturation: 100.0,
+        }
+    }
+}
+
+impl GelExportConfig {
+    pub fn new(agarose: f32, ladder: Option<GelLadder>) -> Self {
+        Self {
+            agarose,
+            ladder,
+            ..Default::default()
+        }
+    }
+
+    /// The range of sizes in bp separated by the gel, shifting to smaller
+    /// fragments with higher agarose concentrations.
+    pub fn resolution(&self) -> (f32, f32) {
+        let factor = self.agarose * self.agarose;
+        (500.0 / factor, 10000.0 / factor)
+    }
+
+    /// Migration distance of a band of `size` bp, relative to the length of the gel.
+    ///
+    /// The distance decreases linearly with the logarithm of the size, molecules
+    /// outside of the `resolution` pile up at the wells or at the end of the gel.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let one_percent = GelExportConfig::new(1.0, None);
+    /// let two_percent = GelExportConfig::new(2.0, None);
+    /// assert!(one_percent.migration(1000) > one_percent.migration(3000));
+    /// assert!(one_percent.migration(1000) > two_percent.migration(1000));
+    /// ```
+    pub fn migration(&self, size: usize) -> f32 {
+        let (min, max) = self.resolution();
+        let size = (size.max(1) as f32).log10();
+        let distance = (max.log10() - size) / (max.log10() - min.log10());
+        // Keep a margin below the wells and above the end of the gel
+        0.05 + 0.9 * distance.clamp(0.0, 1.0)
+    }
+}
+
+/// Bands of a lane that migrate to the same position.
+#[derive(Debug, Clone, PartialEq)]
+struct RenderedBand {
+    /// Position below the wells in px
+    position: f32,
+    sizes: Vec<usize>,
+    mass: f32,
+}
+
+/// Renders lanes of DNA fragments as a virtual agarose gel in SVG.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+///
+/// let config = GelExportConfig::new(1.0, Some(GelLadder::Kb1));
+/// let lanes = vec![GelLane::equimolar("EcoRI", [3000, 1200, 800], 500.0)];
+/// let svg = GelExport::new(config, lanes).export();
+/// assert!(svg.starts_with("<svg"));
+/// ```
+pub struct GelExport {
+    pub config: GelExportConfig,
+    pub lanes: Vec<GelLane>,
+}
+
+impl GelExport {
+    pub fn new(config: GelExportConfig, lanes: Vec<GelLane>) -> Self {
+        GelExport { config, lanes }
+    }
+
+    /// All lanes including the ladder.
+    fn all_lanes(&self) -> Vec<GelLane> {
+        let ladder = self
+            .config
+            .ladder
+            .as_ref()
+            .map(|ladder| GelLane::new(ladder.name(), ladder.bands()));
+        ladder.into_iter().chain(self.lanes.clone()).collect()
+    }
+
+    /// The bands of a lane, merging co-migrating bands.
+    fn render_bands(&self, lane: &GelLane) -> Vec<RenderedBand> {
+        let mut bands = lane
+            .bands
+            .iter()
+            .map(|band| RenderedBand {
+                position: self.config.migration(band.size) * self.config.height,
+                sizes: vec![band.size],
+                mass: band.mass,
+            })
+            .collect::<Vec<_>>();
+        bands.sort_by(|a, b| a.position.total_cmp(&b.position));
+        let mut merged: Vec<RenderedBand> = Vec::new();
+        for band in bands {
+            match merged.last_mut() {
+                Some(last) if band.position - last.position < self.config.band_thickness => {
+                    // Weight the position of the merged band by mass
+                    let mass = last.mass + band.mass;
+                    if mass > 0.0 {
+                        last.position =
+                            (last.position * last.mass + band.position * band.mass) / mass;
+                    }
+                    last.mass = mass;
+                    last.sizes.extend(band.sizes);
+                }
+                _ => merged.push(band),
+            }
+        }
+        merged
+    }
+}
+
+impl Export for GelExport {
+    type Output = String;
+
+    fn export(&self) -> String {
+        let config = &self.config;
+        let lanes = self.all_lanes();
+        let (margin_left, margin_top) = (50_f32, 40_f32);
+        let well_height = 6_f32;
+        let w = margin_left + config.lane_width * lanes.len().max(1) as f32 + 10.0;
+        let h = margin_top + config.height + 10.0;
+
+        let mut elements = vec![format!(
+            r###"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="hsla(0, 0%, 10%, 1)" />"###,
+            x = margin_left - 5.0,
+            y = margin_top - well_height - 5.0,
+            width = w - margin_left,
+            height = config.height + well_height + 10.0,
+        )];
+        for (i, lane) in lanes.iter().enumerate() {
+            let x = margin_left + config.lane_width * i as f32;
+            let band_width = config.lane_width * 0.7;
+            let band_x = x + (config.lane_width - band_width) / 2.0;
+            elements.push(format!(
+                r###"<text x="{x}" y="{y}" font-size="10" text-anchor="middle">{label}</text>"###,
+                x = x + config.lane_width / 2.0,
+                y = margin_top - well_height - 10.0,
+                label = lane.label,
+            ));
+            elements.push(format!(
+                r###"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="none" stroke="hsla(0, 0%, 60%, 1)" />"###,
+                x = band_x,
+                y = margin_top - well_height,
+                width = band_width,
+                height = well_height,
+            ));
+            for band in self.render_bands(lane) {
+                let y = margin_top + band.position;
+                let opacity = (band.mass / config.saturation).clamp(0.05, 1.0);
+                let sizes = band
+                    .sizes
+                    .iter()
+                    .map(|size| format!("{} bp", size))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                elements.push(format!(
+                    r###"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="white" fill-opacity="{opacity}"><title>{sizes}</title></rect>"###,
+                    x = band_x,
+                    y = y - config.band_thickness / 2.0,
+                    width = band_width,
+                    height = config.band_thickness,
+                    opacity = opacity,
+                    sizes = sizes,
+                ));
+                if i == 0 && config.ladder.is_some() {
+                    elements.push(format!(
+                        r###"<text x="{x}" y="{y}" font-size="8" text-anchor="end">{size}</text>"###,
+                        x = margin_left - 8.0,
+                        y = y + 3.0,
+                        size = band.sizes[0],
+                    ));
+                }
+            }
+        }
+
+        format!(
+            r###"<svg width="{width}" height="{height}" viewBox="0 0 {width} {height}">{elements}</svg>"###,
+            width = w,
+            height = h,
+            elements = elements.join("")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{GelBand, GelLadder};
+
+    #[test]
+    fn test_gel_merges_co_migrating_bands() {
+        let config = GelExportConfig::new(1.0, None);
+        let gel = GelExport::new(
+            config,
+            vec![GelLane::equimolar("Digest", [4000, 4050, 1000], 500.0)],
+        );
+        let bands = gel.render_bands(&gel.lanes[0]);
+        assert_eq!(bands.len(), 2);
+        assert_eq!(bands[0].sizes, [4050, 4000]);
+        assert!((bands[0].mass - 500.0 * 8050.0 / 9050.0).abs() < 0.01);
+        assert_eq!(bands[1].sizes, [1000]);
+    }
+
+    #[test]
+    fn test_gel_ladders() {
+        let lambda = GelLadder::LambdaHindIII.bands();
+        assert_eq!(lambda.len(), 8);
+        // Equimolar, so the 23 kb band holds almost half of the mass
+        assert!(lambda[0].mass > 200.0);
+        let custom = GelLadder::Custom(vec![GelBand {
+            size: 100,
+            mass: 10.0,
+        }]);
+        assert_eq!(custom.bands().len(), 1);
+
+        let config = GelExportConfig::new(2.0, Some(GelLadder::Bp100));
+        let svg = GelExport::new(config, Vec::new()).export();
+        assert_eq!(svg.matches("<title>").count(), 12);
+    }
+
+    #[test]
+    fn test_gel_migration_clamped() {
+        let config = GelExportConfig::new(1.0, None);
+        assert_eq!(config.migration(50000), config.migration(100000));
+        assert_eq!(config.migration(10), config.migration(1));
+        assert!(config.migration(10) <= 1.0);
+    }
+}
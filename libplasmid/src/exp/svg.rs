@@ -81,8 +81,8 @@ impl SvgExport {
             for n in self.sequence.iter() {
                 let is_same = last_nuc == Some(*n);
                 let new_chain_size = if is_same { curr_chain_size + 1 } else { 1 };
-                if !is_same && last_nuc.is_some() {
-                    buf.push((last_nuc.unwrap() /* safe */, curr_chain_size));
+                if let (false, Some(last)) = (is_same, last_nuc) {
+                    buf.push((last, curr_chain_size));
                 }
                 curr_chain_size = new_chain_size;
                 last_nuc = Some(*n);
@@ -114,7 +114,7 @@ impl SvgExport {
                 let color = colors.get(&n).unwrap_or(&default_color);
                 let stroke_width = 20_f32;
                 let step = step * len as f32;
-                let arc = arc(last_deg, last_deg + step as f32, stroke_width);
+                let arc = arc(last_deg, last_deg + step, stroke_width);
                 buf.push(format!(
                     r###"<path d="{d}" fill="none" stroke="{color}" stroke-width="{stroke_width}" />"###,
                     d = arc, color = color, stroke_width = stroke_width,
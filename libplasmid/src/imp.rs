@@ -1,5 +1,7 @@
 pub mod fasta;
 pub mod import;
+pub mod rebase;
 
 pub use self::fasta::*;
 pub use self::import::*;
+pub use self::rebase::*;
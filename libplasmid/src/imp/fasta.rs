@@ -119,9 +119,7 @@ impl FastaFile {
     }
 
     pub fn is_iupac_sequence(&self) -> bool {
-        self.sequence
-            .chars()
-            .all(|c| IupacNucleotide::all_as_str().contains(c))
+        self.sequence.chars().all(|c| IupacNucleotide::is_iupac(&c))
     }
 
     pub fn is_dna_sequence(&self) -> bool {
@@ -0,0 +1,339 @@
+use super::Import;
+use crate::{
+    traits::TryFromLetter,
+    uni::{CutPositions, IupacNucleotide, RestrictionEnzyme},
+};
+
+/// Supplier and origin of a restriction enzyme, as listed by REBASE.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnzymeReference {
+    pub name: String,
+    /// The first enzyme found with the same recognition site and cut positions
+    pub prototype: Option<String>,
+    pub organism: String,
+    /// The methylation sites of the enzyme in REBASE notation
+    pub methylation: String,
+    /// REBASE codes of the commercial suppliers of the enzyme, e.g. `N` for NEB
+    pub suppliers: String,
+}
+
+impl EnzymeReference {
+    /// Whether the enzyme can be bought from any supplier.
+    pub fn is_commercially_available(&self) -> bool {
+        !self.suppliers.is_empty()
+    }
+}
+
+/// A REBASE enzyme file in EMBOSS format (`emboss_e.###`).
+///
+/// Cut positions are given as the number of nucleotides before the cut, counted
+/// from the first nucleotide of the site. Cuts before the site are negative and
+/// EMBOSS skips zero, so `-1` is the cut just before the site. Enzymes without
+/// known cut positions are skipped.
+pub struct EmbossEnzymeFile {
+    pub enzymes: Vec<RestrictionEnzyme>,
+}
+
+/// A REBASE reference file in EMBOSS format (`emboss_r.###`).
+pub struct EmbossReferenceFile {
+    pub references: Vec<EnzymeReference>,
+}
+
+/// A REBASE file in Bairoch format (`bairoch.###`).
+///
+/// Both strands are listed for non-palindromic sites. Enzymes without known cut
+/// positions are skipped.
+pub struct BairochFile {
+    pub enzymes: Vec<RestrictionEnzyme>,
+    /// The references of `enzymes`, in the same order
+    pub references: Vec<EnzymeReference>,
+}
+
+fn parse_site(site: &str) -> anyhow::Result<Vec<IupacNucleotide>> {
+    site.trim()
+        .chars()
+        .map(IupacNucleotide::try_from_letter)
+        .collect()
+}
+
+/// Split a REBASE file into its records, separated by `//` lines.
+fn records(s: &str) -> impl Iterator<Item = Vec<&str>> {
+    let mut records = vec![Vec::new()];
+    for line in s.lines().filter(|line| !line.starts_with('#')) {
+        if line.trim() == "//" {
+            records.push(Vec::new());
+        } else {
+            records.last_mut().unwrap().push(line);
+        }
+    }
+    records.into_iter().filter_map(|record| {
+        let first = record.iter().position(|line| !line.trim().is_empty())?;
+        Some(record[first..].to_vec())
+    })
+}
+
+impl Import for EmbossEnzymeFile {
+    type Output = Self;
+
+    /// Import an EMBOSS enzyme file from a string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let file = EmbossEnzymeFile::import(
+    ///     "# REBASE version 101\nBsaI\tGGTCTC\t6\t2\t0\t7\t11\t0\t0\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(file.enzymes[0].name, "BsaI");
+    /// assert_eq!(file.enzymes[0].cut, CutPositions::new(7, 11));
+    /// ```
+    fn import<S>(s: S) -> anyhow::Result<Self::Output>
+    where
+        S: AsRef<str>,
+    {
+        // EMBOSS has no cut position 0
+        let position = |field: &str| -> anyhow::Result<isize> {
+            let pos = field.trim().parse::<isize>()?;
+            Ok(if pos < 0 { pos + 1 } else { pos })
+        };
+        let mut enzymes = Vec::new();
+        for line in s.as_ref().lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            if fields.len() < 9 {
+                bail!("Invalid EMBOSS enzyme line: {}", line);
+            }
+            let site = parse_site(fields[1])?;
+            let cuts = (
+                position(fields[5])?,
+                position(fields[6])?,
+                position(fields[7])?,
+                position(fields[8])?,
+            );
+            let enzyme = match fields[3] {
+                "0" => continue,
+                "2" => {
+                    RestrictionEnzyme::from_cut(fields[0], &site, CutPositions::new(cuts.0, cuts.1))
+                }
+                "4" => {
+                    RestrictionEnzyme::from_cut(fields[0], &site, CutPositions::new(cuts.2, cuts.3))
+                        .with_upstream_cut(CutPositions::new(cuts.0, cuts.1))
+                }
+                n => bail!("Invalid number of cuts in EMBOSS enzyme line: {}", n),
+            };
+            enzymes.push(enzyme);
+        }
+        Ok(Self { enzymes })
+    }
+}
+
+impl Import for EmbossReferenceFile {
+    type Output = Self;
+
+    /// Import an EMBOSS reference file from a string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let file = EmbossReferenceFile::import(
+    ///     "BsaI\nEco31I\nBacillus stearothermophilus 6-55\nATCC\n\nN\n0\n//\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(file.references[0].prototype.as_deref(), Some("Eco31I"));
+    /// assert_eq!(file.references[0].suppliers, "N");
+    /// ```
+    fn import<S>(s: S) -> anyhow::Result<Self::Output>
+    where
+        S: AsRef<str>,
+    {
+        let references = records(s.as_ref())
+            .map(|record| {
+                let field = |i: usize| record.get(i).map_or("", |f| f.trim()).to_string();
+                EnzymeReference {
+                    name: field(0),
+                    prototype: Some(field(1)).filter(|p| !p.is_empty()),
+                    organism: field(2),
+                    methylation: field(4),
+                    suppliers: field(5),
+                }
+            })
+            .collect();
+        Ok(Self { references })
+    }
+}
+
+impl Import for BairochFile {
+    type Output = Self;
+
+    /// Import a Bairoch file from a string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let file = BairochFile::import(
+    ///     "ID   BsaI\nPT   Eco31I\nRS   GGTCTC, 7; GAGACC, -5;\nCR   N.\n//\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(file.enzymes[0].cut, CutPositions::new(7, 11));
+    /// assert_eq!(file.references[0].suppliers, "N");
+    /// ```
+    fn import<S>(s: S) -> anyhow::Result<Self::Output>
+    where
+        S: AsRef<str>,
+    {
+        let mut enzymes = Vec::new();
+        let mut references = Vec::new();
+        for record in records(s.as_ref()) {
+            let mut reference = EnzymeReference::default();
+            let mut sites = Vec::new();
+            for line in record {
+                let (code, value) = (line.get(..2).unwrap_or(""), line.get(2..).unwrap_or(""));
+                let value = value.trim();
+                match code {
+                    "ID" => reference.name = value.to_string(),
+                    "PT" => reference.prototype = Some(value.to_string()),
+                    "OS" => reference.organism = value.to_string(),
+                    "MS" => reference.methylation = value.to_string(),
+                    "CR" => reference
+                        .suppliers
+                        .extend(value.chars().filter(|c| c.is_ascii_alphanumeric())),
+                    "RS" => {
+                        for entry in value.split(';').filter(|e| !e.trim().is_empty()) {
+                            let (site, cut) = entry
+                                .split_once(',')
+                                .ok_or_else(|| anyhow!("Invalid Bairoch site: {}", entry))?;
+                            sites.push((parse_site(site)?, cut.trim().parse::<isize>().ok()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let cut = match sites[..] {
+                [(ref site, Some(top))] => {
+                    Some((site, CutPositions::new(top, site.len() as isize - top)))
+                }
+                [(ref site, Some(top)), (_, Some(bottom)), ..] => {
+                    Some((site, CutPositions::new(top, site.len() as isize - bottom)))
+                }
+                _ => None,
+            };
+            if let Some((site, cut)) = cut {
+                enzymes.push(RestrictionEnzyme::from_cut(&reference.name, site, cut));
+                references.push(reference);
+            }
+        }
+        Ok(Self {
+            enzymes,
+            references,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    const EMBOSS_E: &str = "\
+# REBASE version 101                                              emboss_e.101
+#
+AanI\tTTATAA\t6\t2\t1\t3\t3\t0\t0
+AcsI\tRAATTY\t6\t2\t0\t1\t5\t0\t0
+BaeI\tACNNNNGTAYC\t11\t4\t0\t-11\t-16\t23\t18
+BsaI\tGGTCTC\t6\t2\t0\t7\t11\t0\t0
+M.AbaI\tGATC\t4\t0\t0\t0\t0\t0\t0
+";
+
+    const EMBOSS_R: &str = "\
+# REBASE version 101                                              emboss_r.101
+#
+AanI
+PsiI
+Aquifex aeolicus
+DSM 6858
+
+BF
+0
+//
+AcsI
+ApoI
+Arthrobacter citreus
+P.A. Bloch
+
+
+0
+//
+";
+
+    #[test]
+    fn test_emboss_enzyme_file_import() -> anyhow::Result<()> {
+        let file = EmbossEnzymeFile::import(EMBOSS_E)?;
+        let names = file
+            .enzymes
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["AanI", "AcsI", "BaeI", "BsaI"]);
+        assert_eq!(file.enzymes[0].overhang_type(), Some(OverhangType::Blunt));
+        assert_eq!(file.enzymes[1].before, [IupacNucleotide::R]);
+        assert_eq!(
+            file.enzymes[2].upstream_cut,
+            Some(CutPositions::new(-10, -15))
+        );
+        assert_eq!(file.enzymes[2].cut, CutPositions::new(23, 18));
+        Ok(())
+    }
+
+    #[test]
+    fn test_emboss_enzyme_file_import_invalid() {
+        assert!(EmbossEnzymeFile::import("BsaI\tGGTCTC\t6\t2\n").is_err());
+        assert!(EmbossEnzymeFile::import("BsaI\tGGTXTC\t6\t2\t0\t7\t11\t0\t0\n").is_err());
+    }
+
+    #[test]
+    fn test_emboss_reference_file_import() -> anyhow::Result<()> {
+        let file = EmbossReferenceFile::import(EMBOSS_R)?;
+        assert_eq!(file.references.len(), 2);
+        assert_eq!(file.references[0].name, "AanI");
+        assert_eq!(file.references[0].organism, "Aquifex aeolicus");
+        assert!(file.references[0].is_commercially_available());
+        assert_eq!(file.references[1].prototype.as_deref(), Some("ApoI"));
+        assert!(!file.references[1].is_commercially_available());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bairoch_file_import() -> anyhow::Result<()> {
+        let file = BairochFile::import(
+            "\
+ID   EcoRI
+ET   R2
+OS   Escherichia coli RY13
+RS   GAATTC, 1;
+MS   3(6)
+CR   BCFJKMNOQRSVX.
+//
+ID   PstI
+RS   CTGCAG, 5;
+CR   ABCFIJKMNOQRSVX.
+//
+ID   AbaBGI
+RS   ACCAC, ?;
+//
+",
+        )?;
+        assert_eq!(file.enzymes.len(), 2);
+        assert_eq!(file.enzymes[0].cut, CutPositions::new(1, 5));
+        assert_eq!(file.references[0].organism, "Escherichia coli RY13");
+        assert_eq!(file.references[0].methylation, "3(6)");
+        assert_eq!(
+            file.enzymes[1].overhang_type(),
+            Some(OverhangType::ThreePrime)
+        );
+        Ok(())
+    }
+}
@@ -14,5 +14,6 @@ pub mod imp;
 pub mod prelude;
 pub mod rna;
 pub mod seq;
+pub mod sim;
 pub mod uni;
 pub mod vis;
@@ -1,8 +1,28 @@
 pub use crate::dna::{DnaCodon, DnaNucleotide};
-pub use crate::eaa::{Eaa, QualifiedEaa};
-pub use crate::exp::{Export, SvgExport, SvgExportConfig, SvgRenderMode};
-pub use crate::imp::{FastaEaaFile, FastaFile, FastaIupacFile, Import, TypedFastaFile};
+pub use crate::eaa::{Eaa, GeneticCode, QualifiedEaa};
+pub use crate::exp::{
+    Export, GelBand, GelExport, GelExportConfig, GelLadder, GelLane, SvgExport, SvgExportConfig,
+    SvgRenderMode,
+};
+pub use crate::imp::{
+    BairochFile, EmbossEnzymeFile, EmbossReferenceFile, EnzymeReference, FastaEaaFile, FastaFile,
+    FastaIupacFile, Import, TypedFastaFile,
+};
 pub use crate::rna::{RnaCodon, RnaNucleotide};
-pub use crate::seq::{Annotation, DnaSequence, RnaSequence};
+pub use crate::seq::{
+    Annotation, BasePair, DnaEnd, DnaSequence, DoubleStrandedDna, Orf, OrfOptions, OverhangType,
+    PackedDnaSequence, PackedIupacSequence, PositionedEaa, ReadingFrame, RnaSequence,
+    SequenceStorage, Strand, Topology, TranslationOptions,
+};
+pub use crate::sim::{
+    Amplicon, AssemblyPrimers, CloningOptions, CloningPair, Domestication, ForbiddenSite, Fragment,
+    GoldenGateAssembly, HomologyAssembly, HomologyOptions, Junction, Ligation, LigationPart,
+    Overlap, PcrOptions, Primer, PrimerPair, PrimerSite, SynonymousMutation, TmOptions,
+};
 pub use crate::traits::{Codon, Nucleotide, NucleotideSequence};
-pub use crate::uni::{IupacNucleotide, RestrictionEnzymes};
+pub use crate::uni::{
+    CutPositions, EnzymeDatabase, EnzymeQuery, IupacCodon, IupacNucleotide, Methylation,
+    MethylationProfile, PatternMatch, PatternScanner, ReactionBuffer, RestrictionEnzyme,
+    RestrictionEnzymes, RestrictionMap, RestrictionOptions, RestrictionSite,
+    RestrictionSiteScanner,
+};
@@ -1,4 +1,7 @@
-use crate::{eaa::Eaa, traits::*};
+use crate::{
+    eaa::{Eaa, GeneticCode},
+    traits::*,
+};
 
 use super::RnaNucleotide;
 
@@ -11,6 +14,21 @@ impl RnaCodon {
     pub fn translate(&self) -> Eaa {
         Eaa::from(self)
     }
+
+    /// Translate the codon using an alternative genetic code.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    /// use plasmid::traits::TryFromStr;
+    ///
+    /// let codon = RnaCodon::try_from_str("AUA").unwrap();
+    /// assert_eq!(codon.translate(), Eaa::Ile);
+    /// assert_eq!(codon.translate_with(GeneticCode::from_id(2).unwrap()), Eaa::Met);
+    /// ```
+    pub fn translate_with(&self, code: &GeneticCode) -> Eaa {
+        code.translate(self)
+    }
 }
 
 impl_codon_traits!(RnaNucleotide => RnaCodon);
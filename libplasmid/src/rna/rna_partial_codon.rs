@@ -30,9 +30,9 @@ impl Default for RnaPartialCodon {
 impl RnaPartialCodon {
     pub fn push(&mut self, item: RnaNucleotide) -> anyhow::Result<()> {
         if self.nucleobase_count() == 3 {
-            return Err(anyhow!(
+            Err(anyhow!(
                 "Cannot push more than two bases into a partial codon"
-            ));
+            ))
         } else {
             self.bases.push(item);
             Ok(())
@@ -49,7 +49,7 @@ impl RnaPartialCodon {
 
     pub fn to_codon(&self) -> Option<RnaCodon> {
         let arr = [
-            *self.bases.get(0)?,
+            *self.bases.first()?,
             *self.bases.get(1)?,
             *self.bases.get(2)?,
         ];
@@ -1,9 +1,31 @@
 pub mod annotation;
+pub mod base_pair;
 pub mod dna;
+pub mod double_stranded_dna;
+pub mod edit_history;
 pub mod genetic_sequence;
+pub mod orf;
+pub mod packed_sequence;
+pub mod reading_frame;
 pub mod rna;
+pub mod sequence_storage;
+pub mod sequence_view;
+pub mod strand;
+pub mod topology;
+pub mod translation;
 
 pub use self::annotation::*;
+pub use self::base_pair::*;
 pub use self::dna::*;
+pub use self::double_stranded_dna::*;
+pub use self::edit_history::*;
 pub use self::genetic_sequence::*;
+pub use self::orf::*;
+pub use self::packed_sequence::*;
+pub use self::reading_frame::*;
 pub use self::rna::*;
+pub use self::sequence_storage::*;
+pub use self::sequence_view::*;
+pub use self::strand::*;
+pub use self::topology::*;
+pub use self::translation::*;
@@ -2,12 +2,27 @@ use std::borrow::Borrow;
 
 use crate::uni::RestrictionEnzyme;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+use super::Strand;
+
+/// An annotated region of a genetic sequence.
+///
+/// New fields may be added, so annotations can't be built from a struct
+/// literal outside of this crate. Use `Annotation::new` and the `with_*`
+/// methods instead, e.g. `Annotation::new(0, 2, None, "lacZ").with_strand(Strand::Reverse)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub struct Annotation {
     pub start: usize,
+    /// A position inside of the annotation, e.g. the cut on the top strand,
+    /// as the position of the first nucleotide after the cut
     pub needle: Option<usize>,
+    /// The cut on the bottom strand, as the top strand position of the first
+    /// nucleotide after the cut
+    pub bottom_needle: Option<usize>,
     pub end: usize,
     pub text: String,
+    /// The strand the annotated feature is located on, if it is stranded
+    pub strand: Option<Strand>,
 }
 
 impl Annotation {
@@ -19,10 +34,41 @@ impl Annotation {
             start,
             end,
             needle,
+            bottom_needle: None,
             text: text.as_ref().to_string(),
+            strand: None,
         }
     }
 
+    /// Set the strand of the annotated feature.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let ann = Annotation::new(0, 2, None, "lacZ").with_strand(Strand::Reverse);
+    /// assert_eq!(ann.strand, Some(Strand::Reverse));
+    /// ```
+    pub fn with_strand(mut self, strand: Strand) -> Annotation {
+        self.strand = Some(strand);
+        self
+    }
+
+    /// Set the cut position on the bottom strand.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// // EcoRI: G/AATTC on the top strand, CTTAA/G on the bottom strand
+    /// let ann = Annotation::new(0, 5, Some(1), "EcoRI").with_bottom_needle(5);
+    /// assert_eq!(ann.bottom_needle, Some(5));
+    /// ```
+    pub fn with_bottom_needle(mut self, needle: usize) -> Annotation {
+        self.bottom_needle = Some(needle);
+        self
+    }
+
     pub fn new_from_restriction_enzyme<T>(
         start: usize,
         end: usize,
@@ -34,11 +80,201 @@ impl Annotation {
     {
         Annotation::new(start, end, needle, &enzyme.borrow().name)
     }
+
+    /// Whether the annotation wraps around the origin of a circular sequence,
+    /// i.e. it covers `start..len` and `0..=end`.
+    pub fn is_wrapping(&self) -> bool {
+        self.start > self.end
+    }
+
+    /// Number of nucleotides covered by the annotation on a sequence of length `len`.
+    pub fn len_in(&self, len: usize) -> usize {
+        if self.is_wrapping() {
+            len - self.start + self.end + 1
+        } else {
+            self.end - self.start + 1
+        }
+    }
+
+    /// Whether the annotation covers the nucleotide at position `pos`.
+    pub fn contains(&self, pos: usize) -> bool {
+        if self.is_wrapping() {
+            pos >= self.start || pos <= self.end
+        } else {
+            (self.start..=self.end).contains(&pos)
+        }
+    }
+
+    /// Map both needles with `f`, needles for which `f` returns `None` are dropped.
+    pub(crate) fn map_needles<F>(&self, f: F) -> Annotation
+    where
+        F: Fn(usize) -> Option<usize>,
+    {
+        Annotation {
+            needle: self.needle.and_then(&f),
+            bottom_needle: self.bottom_needle.and_then(&f),
+            ..self.clone()
+        }
+    }
+
+    /// Split a wrapping annotation at the origin of a sequence of length `len`.
+    pub(crate) fn unwrap_origin(&self, len: usize) -> Vec<Annotation> {
+        if !self.is_wrapping() {
+            return vec![self.clone()];
+        }
+        let needles_in = |start: usize, end: usize| {
+            self.map_needles(|n| (start..=end + 1).contains(&n).then_some(n))
+        };
+        vec![
+            Annotation {
+                end: len - 1,
+                ..needles_in(self.start, len - 1)
+            },
+            Annotation {
+                start: 0,
+                ..needles_in(0, self.end)
+            },
+        ]
+    }
+
+    /// Rotate the annotation onto a circular sequence of length `len` whose origin
+    /// has been moved to position `origin`.
+    pub(crate) fn rotate(&self, len: usize, origin: usize) -> Annotation {
+        let rotated = |i: usize| {
+            if i >= origin {
+                i - origin
+            } else {
+                i + len - origin
+            }
+        };
+        Annotation {
+            start: rotated(self.start),
+            end: rotated(self.end),
+            ..self.map_needles(|n| Some(rotated(n)))
+        }
+    }
+
+    /// Mirror the annotation onto the reverse complement of a sequence of length `len`.
+    ///
+    /// The strands are swapped, so the needle of the top strand becomes the needle
+    /// of the bottom strand and vice versa.
+    pub(crate) fn reverse(&self, len: usize) -> Annotation {
+        Annotation {
+            start: len - 1 - self.end,
+            end: len - 1 - self.start,
+            needle: self.bottom_needle.map(|n| len - n),
+            bottom_needle: self.needle.map(|n| len - n),
+            strand: self.strand.map(|strand| strand.opposite()),
+            ..self.clone()
+        }
+    }
+
+    /// Project the annotation onto the region of `view_len` nucleotides starting at
+    /// position `start` of a sequence of length `len`.
+    ///
+    /// The annotation is clipped to the region and its positions are re-based to the
+    /// start of the region. An annotation may be split in two if it wraps around the
+    /// origin, and it is dropped if it does not overlap the region at all.
+    pub(crate) fn project(&self, len: usize, start: usize, view_len: usize) -> Vec<Annotation> {
+        self.rotate(len, start)
+            .unwrap_origin(len)
+            .into_iter()
+            .filter(|part| part.start < view_len)
+            .map(|part| {
+                let end = part.end.min(view_len - 1);
+                Annotation {
+                    end,
+                    ..part.map_needles(|n| (n <= end + 1).then_some(n))
+                }
+            })
+            .collect()
+    }
+
+    /// Like `rebase`, but for an annotation on a circular sequence of length `len`,
+    /// which may wrap around the origin.
+    pub(crate) fn rebase_circular(
+        &self,
+        len: usize,
+        pos: usize,
+        removed: usize,
+        inserted: usize,
+    ) -> Vec<Annotation> {
+        if !self.is_wrapping() {
+            return self.rebase(pos, removed, inserted);
+        }
+        if removed == 0 {
+            // The origin lies inside the annotation, so insertions at either end of the
+            // linear representation grow it.
+            let moved = |i: usize| if i >= pos { i + inserted } else { i };
+            return vec![Annotation {
+                start: moved(self.start),
+                end: moved(self.end),
+                ..self.map_needles(|n| Some(moved(n)))
+            }];
+        }
+        let new_len = len - removed + inserted;
+        let mut parts = self
+            .unwrap_origin(len)
+            .iter()
+            .flat_map(|part| part.rebase(pos, removed, inserted))
+            .collect::<Vec<_>>();
+        let tail = parts.iter().position(|part| part.end + 1 == new_len);
+        let head = parts.iter().position(|part| part.start == 0);
+        if let (Some(tail), Some(head)) = (tail, head) {
+            if tail != head {
+                let tail = if head < tail { tail - 1 } else { tail };
+                let head = parts.remove(head);
+                let tail = &mut parts[tail];
+                tail.end = head.end;
+                tail.needle = tail.needle.or(head.needle);
+                tail.bottom_needle = tail.bottom_needle.or(head.bottom_needle);
+            }
+        }
+        parts
+    }
+
+    /// Rebase the annotation onto a sequence in which `removed` nucleotides at
+    /// position `pos` have been replaced by `inserted` nucleotides.
+    ///
+    /// Annotations after the edit are shifted, annotations overlapping it are
+    /// shrunk, and annotations that are fully removed are dropped. A pure insertion
+    /// inside an annotation grows it, while a replacement inside an annotation splits
+    /// it into the parts before and after the replaced nucleotides.
+    pub(crate) fn rebase(&self, pos: usize, removed: usize, inserted: usize) -> Vec<Annotation> {
+        let edit_end = pos + removed;
+        let moved = |i: usize| {
+            if i >= edit_end {
+                i - removed + inserted
+            } else {
+                i
+            }
+        };
+        let part = |start: usize, end: usize| Annotation {
+            start: moved(start),
+            end: moved(end),
+            ..self.map_needles(|n| {
+                ((start..=end + 1).contains(&n) && !(pos..edit_end).contains(&n)).then(|| moved(n))
+            })
+        };
+
+        let before = (self.start < pos).then(|| (self.start, self.end.min(pos - 1)));
+        let after = (self.end >= edit_end).then(|| (self.start.max(edit_end), self.end));
+        match (before, after) {
+            (Some(_), Some(_)) if removed == 0 || inserted == 0 => {
+                vec![part(self.start, self.end)]
+            }
+            (before, after) => before
+                .into_iter()
+                .chain(after)
+                .map(|(start, end)| part(start, end))
+                .collect(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::prelude::RestrictionEnzymes;
+    use crate::prelude::{RestrictionEnzyme, Strand};
 
     use super::Annotation;
 
@@ -53,14 +289,141 @@ mod tests {
 
     #[test]
     pub fn test_annotation_new_from_restriction_enzyme() {
-        let enzyme = RestrictionEnzymes
-            .iter()
-            .find(|r| r.name == "NdeI")
-            .unwrap();
+        let enzyme = RestrictionEnzyme::by_name("NdeI").unwrap();
         let ann = Annotation::new_from_restriction_enzyme(0, 5, Some(3), enzyme);
         assert_eq!(ann.start, 0);
         assert_eq!(ann.needle, Some(3));
         assert_eq!(ann.end, 5);
         assert_eq!(ann.text, "NdeI");
     }
+
+    #[test]
+    pub fn test_annotation_reverse() {
+        let ann = Annotation::new(2, 5, Some(4), "foo");
+        let reversed = ann.reverse(10);
+        assert_eq!((reversed.start, reversed.end), (4, 7));
+        assert_eq!((reversed.needle, reversed.bottom_needle), (None, Some(6)));
+        assert_eq!(ann.reverse(10).reverse(10), ann);
+
+        let ann = Annotation::new(2, 7, Some(3), "EcoRI").with_bottom_needle(7);
+        let reversed = ann.reverse(10);
+        assert_eq!(
+            (reversed.needle, reversed.bottom_needle),
+            (Some(3), Some(7))
+        );
+
+        let ann = ann.with_strand(Strand::Forward);
+        assert_eq!(ann.reverse(10).strand, Some(Strand::Reverse));
+    }
+
+    #[test]
+    pub fn test_annotation_rebase_insertion() {
+        let ann = Annotation::new(4, 8, Some(6), "foo");
+        assert_eq!(
+            ann.rebase(2, 0, 3),
+            [Annotation::new(7, 11, Some(9), "foo")]
+        );
+        assert_eq!(
+            ann.rebase(4, 0, 3),
+            [Annotation::new(7, 11, Some(9), "foo")]
+        );
+        assert_eq!(
+            ann.rebase(6, 0, 3),
+            [Annotation::new(4, 11, Some(9), "foo")]
+        );
+        assert_eq!(ann.rebase(9, 0, 3), [Annotation::new(4, 8, Some(6), "foo")]);
+    }
+
+    #[test]
+    pub fn test_annotation_rebase_removal() {
+        let ann = Annotation::new(4, 8, Some(6), "foo");
+        assert_eq!(ann.rebase(0, 2, 0), [Annotation::new(2, 6, Some(4), "foo")]);
+        assert_eq!(ann.rebase(2, 4, 0), [Annotation::new(2, 4, Some(2), "foo")]);
+        assert_eq!(ann.rebase(5, 3, 0), [Annotation::new(4, 5, None, "foo")]);
+        assert_eq!(ann.rebase(7, 4, 0), [Annotation::new(4, 6, Some(6), "foo")]);
+        assert_eq!(ann.rebase(9, 4, 0), [Annotation::new(4, 8, Some(6), "foo")]);
+        assert!(ann.rebase(4, 5, 0).is_empty());
+        assert!(ann.rebase(2, 10, 0).is_empty());
+    }
+
+    #[test]
+    pub fn test_annotation_rebase_replacement() {
+        let ann = Annotation::new(4, 8, Some(7), "foo");
+        assert_eq!(
+            ann.rebase(5, 2, 4),
+            [
+                Annotation::new(4, 4, None, "foo"),
+                Annotation::new(9, 10, Some(9), "foo")
+            ]
+        );
+        assert_eq!(ann.rebase(2, 4, 1), [Annotation::new(3, 5, Some(4), "foo")]);
+        assert_eq!(ann.rebase(7, 4, 1), [Annotation::new(4, 6, None, "foo")]);
+        assert!(ann.rebase(4, 5, 2).is_empty());
+    }
+
+    #[test]
+    pub fn test_annotation_wrapping() {
+        let ann = Annotation::new(8, 1, None, "origin");
+        assert!(ann.is_wrapping());
+        assert_eq!(ann.len_in(10), 4);
+        assert!(ann.contains(9) && ann.contains(0) && !ann.contains(2));
+        assert_eq!(
+            ann.unwrap_origin(10),
+            [
+                Annotation::new(8, 9, None, "origin"),
+                Annotation::new(0, 1, None, "origin")
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_annotation_rotate() {
+        let ann = Annotation::new(2, 4, Some(3), "foo");
+        assert_eq!(ann.rotate(10, 3), Annotation::new(9, 1, Some(0), "foo"));
+        assert_eq!(ann.rotate(10, 2), Annotation::new(0, 2, Some(1), "foo"));
+    }
+
+    #[test]
+    pub fn test_annotation_rebase_circular() {
+        let ann = Annotation::new(8, 1, None, "origin");
+        // Insertions at the origin grow the annotation
+        assert_eq!(
+            ann.rebase_circular(10, 0, 0, 2),
+            [Annotation::new(10, 3, None, "origin")]
+        );
+        assert_eq!(
+            ann.rebase_circular(10, 10, 0, 2),
+            [Annotation::new(8, 1, None, "origin")]
+        );
+        // Removals on either side of the origin shrink it
+        assert_eq!(
+            ann.rebase_circular(10, 9, 1, 0),
+            [Annotation::new(8, 1, None, "origin")]
+        );
+        assert_eq!(
+            ann.rebase_circular(10, 0, 1, 0),
+            [Annotation::new(7, 0, None, "origin")]
+        );
+        assert_eq!(
+            ann.rebase_circular(10, 4, 2, 0),
+            [Annotation::new(6, 1, None, "origin")]
+        );
+        // Replacing the end of the sequence splits it
+        assert_eq!(
+            ann.rebase_circular(10, 9, 1, 1),
+            [
+                Annotation::new(8, 8, None, "origin"),
+                Annotation::new(0, 1, None, "origin")
+            ]
+        );
+        // Removing one side leaves the other
+        assert_eq!(
+            ann.rebase_circular(10, 0, 2, 0),
+            [Annotation::new(6, 7, None, "origin")]
+        );
+        assert_eq!(
+            ann.rebase_circular(10, 8, 2, 0),
+            [Annotation::new(0, 1, None, "origin")]
+        );
+    }
 }
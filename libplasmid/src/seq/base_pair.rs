@@ -0,0 +1,57 @@
+use crate::traits::Nucleotide;
+
+/// A column of a double-stranded sequence.
+///
+/// `top` is the nucleotide of the top strand and `bottom` the nucleotide of the
+/// bottom strand facing it, either is `None` where that strand is missing,
+/// e.g. in overhangs or single-stranded gaps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BasePair<B> {
+    /// Column in top strand coordinates, negative if the bottom strand
+    /// protrudes beyond the 5' end of the top strand.
+    pub position: isize,
+    pub top: Option<B>,
+    pub bottom: Option<B>,
+}
+
+impl<B> BasePair<B>
+where
+    B: Nucleotide + PartialEq + Copy,
+{
+    /// Whether both strands are present and complementary.
+    pub fn is_paired(&self) -> bool {
+        matches!((self.top, self.bottom), (Some(t), Some(b)) if t.complement() == b)
+    }
+
+    /// Whether both strands are present but not complementary.
+    pub fn is_mismatch(&self) -> bool {
+        matches!((self.top, self.bottom), (Some(t), Some(b)) if t.complement() != b)
+    }
+
+    /// Whether only one of the strands is present.
+    pub fn is_single_stranded(&self) -> bool {
+        self.top.is_some() != self.bottom.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dna::DnaNucleotide::*;
+
+    use super::BasePair;
+
+    #[test]
+    fn test_base_pair_kind() {
+        let pair = |top, bottom| BasePair {
+            position: 0,
+            top,
+            bottom,
+        };
+        assert!(pair(Some(A), Some(T)).is_paired());
+        assert!(pair(Some(G), Some(T)).is_mismatch());
+        assert!(!pair(Some(G), Some(T)).is_paired());
+        assert!(pair(None, Some(T)).is_single_stranded());
+        assert!(pair(Some(C), None).is_single_stranded());
+        assert!(!pair(Some(C), None).is_mismatch());
+    }
+}
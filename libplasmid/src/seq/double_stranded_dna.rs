@@ -0,0 +1,700 @@
+use std::fmt::Display;
+
+use anyhow::bail;
+
+use crate::{dna::DnaNucleotide, traits::*};
+
+use super::{Annotation, BasePair, DnaSequence, Topology};
+
+/// The shape of an end of a double-stranded DNA molecule.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OverhangType {
+    /// Both strands end at the same position.
+    Blunt,
+    /// The 5' end of one strand protrudes (sticky end).
+    FivePrime,
+    /// The 3' end of one strand protrudes (sticky end).
+    ThreePrime,
+}
+
+impl Display for OverhangType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverhangType::Blunt => write!(f, "blunt"),
+            OverhangType::FivePrime => write!(f, "5'"),
+            OverhangType::ThreePrime => write!(f, "3'"),
+        }
+    }
+}
+
+/// An end of a linear double-stranded DNA molecule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DnaEnd {
+    pub overhang_type: OverhangType,
+    /// The single-stranded nucleotides, read 5' to 3' along the top strand.
+    ///
+    /// For overhangs on the bottom strand this is the complement of the
+    /// single-stranded nucleotides, i.e. the top strand after a fill-in.
+    /// In this form, ends which anneal have the same overhang, see `is_compatible_with`.
+    pub overhang: Vec<DnaNucleotide>,
+}
+
+impl DnaEnd {
+    pub fn blunt() -> Self {
+        Self {
+            overhang_type: OverhangType::Blunt,
+            overhang: Vec::new(),
+        }
+    }
+
+    /// Whether the end is blunt.
+    pub fn is_blunt(&self) -> bool {
+        self.overhang_type == OverhangType::Blunt
+    }
+
+    /// Number of single-stranded nucleotides at this end.
+    pub fn len(&self) -> usize {
+        self.overhang.len()
+    }
+
+    /// Whether the end has no single-stranded nucleotides.
+    pub fn is_empty(&self) -> bool {
+        self.overhang.is_empty()
+    }
+
+    /// The single-stranded nucleotides column by column, on the strand carrying
+    /// them, for the right end of a molecule if `right` is set.
+    fn single_strand(&self, right: bool) -> Vec<DnaNucleotide> {
+        // 5' overhangs of right ends and 3' overhangs of left ends are on the
+        // bottom strand, their top strand fill-in is stored
+        let on_bottom = match self.overhang_type {
+            OverhangType::FivePrime => right,
+            OverhangType::ThreePrime => !right,
+            OverhangType::Blunt => false,
+        };
+        if on_bottom {
+            self.overhang.iter().map(|n| n.complement()).collect()
+        } else {
+            self.overhang.clone()
+        }
+    }
+
+    /// Whether this right end of a molecule can be ligated to the left end of
+    /// another one, i.e. both are blunt or their overhangs anneal.
+    ///
+    /// Overhangs anneal if they are of the same type and length and the
+    /// single-stranded nucleotides of one end are the reverse complement of
+    /// those of the other end, so that they pair column by column. Cohesive
+    /// ends left by different enzymes are compatible if they anneal.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// // BamHI (G/GATCC) and BglII (A/GATCT) both leave 5' GATC overhangs
+    /// let bamhi = DoubleStrandedDna::with_overhangs(
+    ///     DnaSequence::from_str("AAAAGATC").unwrap(),
+    ///     (OverhangType::Blunt, 0),
+    ///     (OverhangType::FivePrime, 4),
+    /// )
+    /// .unwrap();
+    /// let bglii = DoubleStrandedDna::with_overhangs(
+    ///     DnaSequence::from_str("GATCTTTT").unwrap(),
+    ///     (OverhangType::FivePrime, 4),
+    ///     (OverhangType::Blunt, 0),
+    /// )
+    /// .unwrap();
+    /// let (right, left) = (bamhi.right_end().unwrap(), bglii.left_end().unwrap());
+    /// assert!(right.is_compatible_with(&left));
+    /// assert!(!right.is_compatible_with(&DnaEnd::blunt()));
+    /// ```
+    pub fn is_compatible_with(&self, left: &DnaEnd) -> bool {
+        self.overhang_type == left.overhang_type
+            && self.len() == left.len()
+            && self
+                .single_strand(true)
+                .iter()
+                .zip(left.single_strand(false))
+                .all(|(a, b)| a.complement() == b)
+    }
+}
+
+/// A DNA molecule made of two annealed strands.
+///
+/// The top strand is a regular `DnaSequence` and carries the annotations and the
+/// topology of the molecule. The bottom strand is stored 5' to 3', and its 3' end
+/// is located at column `offset` of the top strand. Either strand may extend past
+/// the other one on both sides, which gives the molecule sticky ends.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+///
+/// // An EcoRI fragment: 5' AATTCGTAG    3'
+/// //                    3'     GCATCTTAA 5'
+/// let seq = DnaSequence::from_str("AATTCGTAGAATT").unwrap();
+/// let dsdna = DoubleStrandedDna::with_overhangs(
+///     seq,
+///     (OverhangType::FivePrime, 4),
+///     (OverhangType::FivePrime, 4),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(dsdna.top().to_nucleotide_string(), "AATTCGTAG");
+/// assert_eq!(dsdna.left_end().unwrap().overhang_type, OverhangType::FivePrime);
+/// assert_eq!(dsdna.left_end().unwrap().overhang, dsdna.right_end().unwrap().overhang);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DoubleStrandedDna {
+    top: DnaSequence,
+    bottom: Vec<DnaNucleotide>,
+    offset: isize,
+}
+
+impl DoubleStrandedDna {
+    /// Create a blunt, fully paired molecule from its top strand.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let dsdna = DoubleStrandedDna::new(DnaSequence::from_str("ATGC").unwrap());
+    /// assert_eq!(dsdna.bottom(), DnaSequence::from_str("GCAT").unwrap().as_nucleotides());
+    /// ```
+    pub fn new(top: DnaSequence) -> Self {
+        let bottom = top.as_reverse_strand();
+        Self {
+            top,
+            bottom,
+            offset: 0,
+        }
+    }
+
+    /// Create a molecule from two strands, both read 5' to 3'.
+    ///
+    /// `offset` is the column of the top strand at which the 3' end of the bottom
+    /// strand is located, it is negative if the bottom strand protrudes on the left.
+    /// The strands are not required to be complementary, but they have to overlap.
+    /// On circular molecules both strands have to cover the whole circle.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// // 5'   ATGCA 3'
+    /// // 3' GCTACG  5'
+    /// let top = DnaSequence::from_str("ATGCA").unwrap();
+    /// let bottom = DnaSequence::from_str("GCATCG").unwrap();
+    /// let dsdna = DoubleStrandedDna::from_strands(top, bottom.as_nucleotides(), -2).unwrap();
+    ///
+    /// assert_eq!(dsdna.len(), 7);
+    /// assert_eq!(dsdna.left_end().unwrap().overhang_type, OverhangType::ThreePrime);
+    /// assert_eq!(dsdna.right_end().unwrap().overhang_type, OverhangType::ThreePrime);
+    /// ```
+    pub fn from_strands<I, T>(top: DnaSequence, bottom: I, offset: isize) -> anyhow::Result<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: std::borrow::Borrow<DnaNucleotide>,
+    {
+        let bottom = bottom.into_iter().map(|b| *b.borrow()).collect::<Vec<_>>();
+        if top.is_circular() && (offset != 0 || bottom.len() != top.len()) {
+            bail!("Both strands of a circular molecule have to cover the whole circle");
+        }
+        let overlap = (top.len() as isize).min(offset + bottom.len() as isize) - offset.max(0);
+        if overlap <= 0 {
+            bail!("The strands of a double-stranded molecule have to overlap");
+        }
+        Ok(Self {
+            top,
+            bottom,
+            offset,
+        })
+    }
+
+    /// Create a molecule from the sequence of all of its columns, read 5' to 3'
+    /// along the top strand, leaving single-stranded overhangs of the given type
+    /// and length at the left and right ends.
+    ///
+    /// Annotations are kept on the top strand, clipped to the nucleotides it keeps.
+    pub fn with_overhangs(
+        sequence: DnaSequence,
+        left: (OverhangType, usize),
+        right: (OverhangType, usize),
+    ) -> anyhow::Result<Self> {
+        let overhang_len = |(overhang_type, len): (OverhangType, usize)| match overhang_type {
+            OverhangType::Blunt if len > 0 => bail!("Blunt ends can't have an overhang"),
+            _ => Ok(len),
+        };
+        let (left_len, right_len) = (overhang_len(left)?, overhang_len(right)?);
+        if sequence.is_circular() && left_len + right_len > 0 {
+            bail!("Circular molecules can't have overhangs");
+        }
+        let len = sequence.len();
+        if left_len + right_len >= len {
+            bail!("The overhangs have to leave at least one paired nucleotide");
+        }
+        // Columns of the top and bottom strand
+        let (mut top_start, mut top_end) = (0, len);
+        let (mut bottom_start, mut bottom_end) = (0, len);
+        match left.0 {
+            OverhangType::FivePrime => bottom_start = left_len,
+            OverhangType::ThreePrime => top_start = left_len,
+            OverhangType::Blunt => {}
+        }
+        match right.0 {
+            OverhangType::FivePrime => top_end = len - right_len,
+            OverhangType::ThreePrime => bottom_end = len - right_len,
+            OverhangType::Blunt => {}
+        }
+        let bottom = sequence.as_nucleotides()[bottom_start..bottom_end]
+            .iter()
+            .rev()
+            .map(|b| b.complement())
+            .collect::<Vec<_>>();
+        let mut top = sequence;
+        top.remove_range(top_end..);
+        top.remove_range(..top_start);
+        Ok(Self {
+            top,
+            bottom,
+            offset: bottom_start as isize - top_start as isize,
+        })
+    }
+
+    /// The top strand, read 5' to 3'.
+    pub fn top(&self) -> &DnaSequence {
+        &self.top
+    }
+
+    /// Mutable access to the annotations of the top strand. The strands
+    /// themselves can't be changed, since the bottom strand has to stay paired with them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut dna = DoubleStrandedDna::new(DnaSequence::from_str("ATGC").unwrap());
+    /// dna.top_annotations_mut().push(Annotation::new(0, 2, None, "Start Codon"));
+    /// assert_eq!(dna.top().as_annotations().len(), 1);
+    /// ```
+    pub fn top_annotations_mut(&mut self) -> &mut Vec<Annotation> {
+        self.top.as_mut_annotations()
+    }
+
+    /// The bottom strand, read 5' to 3'.
+    pub fn bottom(&self) -> &[DnaNucleotide] {
+        &self.bottom
+    }
+
+    /// The column of the top strand at which the 3' end of the bottom strand is located.
+    pub fn offset(&self) -> isize {
+        self.offset
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.top.topology()
+    }
+
+    pub fn is_circular(&self) -> bool {
+        self.top.is_circular()
+    }
+
+    /// The number of columns spanned by the molecule, including overhangs.
+    pub fn len(&self) -> usize {
+        (self.last_column() - self.first_column()) as usize
+    }
+
+    /// Whether the molecule contains no nucleotides.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The left end of the molecule (5' end of the top strand),
+    /// or `None` for circular molecules.
+    pub fn left_end(&self) -> Option<DnaEnd> {
+        if self.is_circular() {
+            return None;
+        }
+        let end = match self.offset {
+            0 => DnaEnd::blunt(),
+            offset if offset > 0 => DnaEnd {
+                overhang_type: OverhangType::FivePrime,
+                overhang: self.top_columns(0..offset),
+            },
+            offset => DnaEnd {
+                overhang_type: OverhangType::ThreePrime,
+                overhang: self.bottom_columns(offset..0),
+            },
+        };
+        Some(end)
+    }
+
+    /// The right end of the molecule (3' end of the top strand),
+    /// or `None` for circular molecules.
+    pub fn right_end(&self) -> Option<DnaEnd> {
+        if self.is_circular() {
+            return None;
+        }
+        let top_end = self.top.len() as isize;
+        let bottom_end = self.offset + self.bottom.len() as isize;
+        let end = match bottom_end - top_end {
+            0 => DnaEnd::blunt(),
+            diff if diff > 0 => DnaEnd {
+                overhang_type: OverhangType::FivePrime,
+                overhang: self.bottom_columns(top_end..bottom_end),
+            },
+            _ => DnaEnd {
+                overhang_type: OverhangType::ThreePrime,
+                overhang: self.top_columns(bottom_end..top_end),
+            },
+        };
+        Some(end)
+    }
+
+    /// Flip the molecule, so that the bottom strand becomes the top strand.
+    ///
+    /// Annotations are mirrored onto the new top strand and clipped to it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("GATCCAAT").unwrap();
+    /// let dsdna = DoubleStrandedDna::with_overhangs(
+    ///     seq,
+    ///     (OverhangType::FivePrime, 4),
+    ///     (OverhangType::Blunt, 0),
+    /// )
+    /// .unwrap();
+    ///
+    /// let flipped = dsdna.reverse_complement();
+    /// assert_eq!(flipped.top().to_nucleotide_string(), "ATTG");
+    /// assert_eq!(flipped.right_end().unwrap().overhang_type, OverhangType::FivePrime);
+    /// ```
+    pub fn reverse_complement(&self) -> DoubleStrandedDna {
+        if self.is_circular() {
+            return Self {
+                top: self.top.reverse_complement(),
+                bottom: self.top.as_nucleotides().to_vec(),
+                offset: 0,
+            };
+        }
+        let bottom_len = self.bottom.len() as isize;
+        // Column of the new top strand's 5' end in the old coordinates
+        let pivot = self.offset + bottom_len - 1;
+        let mut top = self.bottom.iter().copied().collect::<DnaSequence>();
+        top.as_mut_annotations()
+            .extend(self.top.annotation_iter().filter_map(|ann| {
+                let start = (pivot - ann.end as isize).max(0);
+                let end = (pivot - ann.start as isize).min(bottom_len - 1);
+                let mirrored = |n: Option<usize>| {
+                    n.map(|n| pivot + 1 - n as isize)
+                        .filter(|n| (start..=end + 1).contains(n))
+                        .map(|n| n as usize)
+                };
+                (start <= end).then(|| Annotation {
+                    start: start as usize,
+                    end: end as usize,
+                    needle: mirrored(ann.bottom_needle),
+                    bottom_needle: mirrored(ann.needle),
+                    strand: ann.strand.map(|strand| strand.opposite()),
+                    ..ann.clone()
+                })
+            }));
+        Self {
+            top,
+            bottom: self.top.as_nucleotides().to_vec(),
+            offset: self.offset + bottom_len - self.top.len() as isize,
+        }
+    }
+
+    /// The base pair at `column` of the top strand, `None` outside of the molecule.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let dsdna = DoubleStrandedDna::new(DnaSequence::from_str("ATGC").unwrap());
+    /// let pair = dsdna.base_pair_at(2).unwrap();
+    ///
+    /// assert_eq!((pair.top, pair.bottom), (Some(G), Some(C)));
+    /// assert!(dsdna.base_pair_at(4).is_none());
+    /// ```
+    pub fn base_pair_at(&self, column: isize) -> Option<BasePair<DnaNucleotide>> {
+        (self.first_column()..self.last_column())
+            .contains(&column)
+            .then(|| BasePair {
+                position: column,
+                top: self.top_at(column),
+                bottom: self.bottom_at(column),
+            })
+    }
+
+    /// Iterate over all columns of the molecule from left to right.
+    ///
+    /// Overhangs yield single-stranded pairs, and strands that are not
+    /// complementary, e.g. annealed oligos with a mismatch, yield mismatched pairs.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// // 5'   ATGCA 3'
+    /// // 3' GCTTCG  5'
+    /// let top = DnaSequence::from_str("ATGCA").unwrap();
+    /// let bottom = DnaSequence::from_str("GCTTCG").unwrap();
+    /// let dsdna = DoubleStrandedDna::from_strands(top, bottom.as_nucleotides(), -2).unwrap();
+    /// let pairs = dsdna.base_pair_iter().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(pairs.len(), 7);
+    /// assert_eq!(pairs[0].position, -2);
+    /// assert!(pairs[0].is_single_stranded());
+    /// assert!(pairs[3].is_mismatch());
+    /// assert_eq!(pairs.iter().filter(|pair| pair.is_paired()).count(), 3);
+    /// ```
+    pub fn base_pair_iter(&self) -> impl DoubleEndedIterator<Item = BasePair<DnaNucleotide>> + '_ {
+        (self.first_column()..self.last_column()).map(|column| BasePair {
+            position: column,
+            top: self.top_at(column),
+            bottom: self.bottom_at(column),
+        })
+    }
+
+    fn first_column(&self) -> isize {
+        self.offset.min(0)
+    }
+
+    fn last_column(&self) -> isize {
+        (self.top.len() as isize).max(self.offset + self.bottom.len() as isize)
+    }
+
+    /// The top strand nucleotide at `column`, if any.
+    fn top_at(&self, column: isize) -> Option<DnaNucleotide> {
+        usize::try_from(column)
+            .ok()
+            .and_then(|i| self.top.as_nucleotides().get(i))
+            .copied()
+    }
+
+    /// The bottom strand nucleotide at `column`, if any.
+    fn bottom_at(&self, column: isize) -> Option<DnaNucleotide> {
+        usize::try_from(self.offset + self.bottom.len() as isize - 1 - column)
+            .ok()
+            .and_then(|i| self.bottom.get(i))
+            .copied()
+    }
+
+    fn top_columns(&self, columns: std::ops::Range<isize>) -> Vec<DnaNucleotide> {
+        columns.filter_map(|c| self.top_at(c)).collect()
+    }
+
+    /// The complement of the bottom strand at `columns`, read along the top strand.
+    fn bottom_columns(&self, columns: std::ops::Range<isize>) -> Vec<DnaNucleotide> {
+        columns
+            .filter_map(|c| self.bottom_at(c))
+            .map(|b| b.complement())
+            .collect()
+    }
+}
+
+impl Display for DoubleStrandedDna {
+    /// Print both strands on top of each other, the bottom strand is read 3' to 5'.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let row = |base_at: &dyn Fn(isize) -> Option<DnaNucleotide>| {
+            (self.first_column()..self.last_column())
+                .map(|c| base_at(c).map_or(' ', |b| b.to_letter()))
+                .collect::<String>()
+        };
+        writeln!(f, "5' {} 3'", row(&|c| self.top_at(c)))?;
+        write!(f, "3' {} 5'", row(&|c| self.bottom_at(c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{DnaEnd, DoubleStrandedDna, OverhangType};
+
+    fn dna(s: &str) -> DnaSequence {
+        DnaSequence::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_double_stranded_dna_blunt() {
+        let dsdna = DoubleStrandedDna::new(dna("ATGCC"));
+        assert_eq!(dsdna.len(), 5);
+        assert_eq!(dsdna.offset(), 0);
+        assert_eq!(dsdna.left_end(), Some(DnaEnd::blunt()));
+        assert_eq!(dsdna.right_end(), Some(DnaEnd::blunt()));
+        assert_eq!(dsdna.to_string(), "5' ATGCC 3'\n3' TACGG 5'");
+    }
+
+    #[test]
+    fn test_double_stranded_dna_with_overhangs() {
+        // PstI leaves 3' overhangs: CTGCA/G
+        let dsdna = DoubleStrandedDna::with_overhangs(
+            dna("TGCAGAATTCTGCA"),
+            (OverhangType::ThreePrime, 4),
+            (OverhangType::ThreePrime, 4),
+        )
+        .unwrap();
+        assert_eq!(
+            dsdna.to_string(),
+            "5'     GAATTCTGCA 3'\n3' ACGTCTTAAG     5'"
+        );
+        assert_eq!(dsdna.len(), 14);
+        let left = dsdna.left_end().unwrap();
+        let right = dsdna.right_end().unwrap();
+        assert_eq!(left.overhang_type, OverhangType::ThreePrime);
+        assert_eq!(left, right);
+        assert_eq!(dna("TGCA").as_nucleotides(), left.overhang);
+    }
+
+    #[test]
+    fn test_double_stranded_dna_with_overhangs_annotations() {
+        let mut seq = dna("AATTCGTAGAATT");
+        seq.as_mut_annotations()
+            .push(Annotation::new(2, 6, None, "foo"));
+        let dsdna = DoubleStrandedDna::with_overhangs(
+            seq,
+            (OverhangType::ThreePrime, 4),
+            (OverhangType::FivePrime, 4),
+        )
+        .unwrap();
+        assert_eq!(dsdna.top().to_nucleotide_string(), "CGTAG");
+        assert_eq!(
+            dsdna.top().as_annotations(),
+            [Annotation::new(0, 2, None, "foo")]
+        );
+        assert_eq!(dsdna.offset(), -4);
+    }
+
+    #[test]
+    fn test_double_stranded_dna_invalid() {
+        assert!(DoubleStrandedDna::with_overhangs(
+            dna("AATT"),
+            (OverhangType::FivePrime, 2),
+            (OverhangType::ThreePrime, 2),
+        )
+        .is_err());
+        assert!(DoubleStrandedDna::with_overhangs(
+            dna("AATT"),
+            (OverhangType::Blunt, 1),
+            (OverhangType::Blunt, 0),
+        )
+        .is_err());
+        assert!(
+            DoubleStrandedDna::from_strands(dna("AATT"), dna("AATT").as_nucleotides(), 4).is_err()
+        );
+
+        let mut circular = dna("AATT");
+        circular.set_topology(Topology::Circular);
+        assert!(DoubleStrandedDna::from_strands(circular, dna("AAT").as_nucleotides(), 0).is_err());
+    }
+
+    #[test]
+    fn test_dna_end_compatibility() {
+        let end = |seq: &str, overhang_type: OverhangType, right: bool| {
+            let ends = match right {
+                true => ((OverhangType::Blunt, 0), (overhang_type, 4)),
+                false => ((overhang_type, 4), (OverhangType::Blunt, 0)),
+            };
+            let dsdna = DoubleStrandedDna::with_overhangs(dna(seq), ends.0, ends.1).unwrap();
+            match right {
+                true => dsdna.right_end().unwrap(),
+                false => dsdna.left_end().unwrap(),
+            }
+        };
+        // EcoRI 5' AATT overhangs anneal
+        let right = end("CCGAATT", OverhangType::FivePrime, true);
+        assert!(right.is_compatible_with(&end("AATTCGG", OverhangType::FivePrime, false)));
+        // Same length but not complementary
+        assert!(!right.is_compatible_with(&end("GATCCGG", OverhangType::FivePrime, false)));
+        assert!(!right.is_compatible_with(&end("CCGGCGG", OverhangType::FivePrime, false)));
+        // Same nucleotides, but a 3' overhang
+        assert!(!right.is_compatible_with(&end("AATTCGG", OverhangType::ThreePrime, false)));
+        // PstI 3' TGCA overhangs anneal
+        let right = end("CCCTGCA", OverhangType::ThreePrime, true);
+        assert!(right.is_compatible_with(&end("TGCAGGG", OverhangType::ThreePrime, false)));
+        assert!(!right.is_compatible_with(&end("ACGTGGG", OverhangType::ThreePrime, false)));
+        assert!(DnaEnd::blunt().is_compatible_with(&DnaEnd::blunt()));
+    }
+
+    #[test]
+    fn test_double_stranded_dna_reverse_complement() {
+        let mut seq = dna("AATTCGTAGG");
+        seq.as_mut_annotations()
+            .push(Annotation::new(0, 5, Some(4), "foo").with_bottom_needle(5));
+        let dsdna = DoubleStrandedDna::with_overhangs(
+            seq,
+            (OverhangType::FivePrime, 4),
+            (OverhangType::ThreePrime, 1),
+        )
+        .unwrap();
+        let flipped = dsdna.reverse_complement();
+        assert_eq!(flipped.to_string(), "5'  CTACG     3'\n3' GGATGCTTAA 5'");
+        assert_eq!(
+            flipped.left_end().unwrap().overhang_type,
+            OverhangType::ThreePrime
+        );
+        assert_eq!(
+            flipped.right_end().unwrap().overhang_type,
+            OverhangType::FivePrime
+        );
+        // Columns 4..=5 of the old top strand are the last two columns of the new one
+        assert_eq!(
+            flipped.top().as_annotations(),
+            [Annotation::new(3, 4, Some(4), "foo").with_bottom_needle(5)]
+        );
+        assert_eq!(flipped.reverse_complement().to_string(), dsdna.to_string());
+    }
+
+    #[test]
+    fn test_double_stranded_dna_circular() {
+        let mut seq = dna("GATTACA");
+        seq.set_topology(Topology::Circular);
+        let dsdna = DoubleStrandedDna::new(seq);
+        assert!(dsdna.is_circular());
+        assert_eq!(dsdna.left_end(), None);
+        assert!(dsdna.reverse_complement().is_circular());
+    }
+
+    #[test]
+    fn test_double_stranded_dna_base_pair_iter() {
+        // 5' ATGCATT   3'
+        // 3'   CGAAACG 5'
+        let bottom = dna("GCAAAGC");
+        let dsdna =
+            DoubleStrandedDna::from_strands(dna("ATGCATT"), bottom.as_nucleotides(), 2).unwrap();
+        let pairs = dsdna.base_pair_iter().collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 9);
+        assert_eq!(
+            pairs.iter().map(|pair| pair.position).collect::<Vec<_>>(),
+            (0..9).collect::<Vec<_>>()
+        );
+        let kinds = pairs
+            .iter()
+            .map(|pair| match pair {
+                p if p.is_paired() => '|',
+                p if p.is_mismatch() => 'x',
+                _ => ' ',
+            })
+            .collect::<String>();
+        assert_eq!(kinds, "  ||x||  ");
+        assert_eq!(dsdna.base_pair_at(-1), None);
+        assert_eq!(
+            dsdna.base_pair_at(8).unwrap().bottom,
+            Some(DnaNucleotide::G)
+        );
+
+        let reversed = dsdna.reverse_complement();
+        assert_eq!(
+            reversed
+                .base_pair_iter()
+                .filter(|pair| pair.is_mismatch())
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+};
+
+use super::{Annotation, Topology};
+
+/// An invertible operation on a genetic sequence
+#[derive(Debug, Clone)]
+pub(crate) enum Edit<B> {
+    /// `removed` nucleotides at `pos` were replaced by `inserted` nucleotides.
+    /// Edits that remove nucleotides keep the annotations from before the edit,
+    /// since they can't be restored by rebasing.
+    Splice {
+        pos: usize,
+        removed: Vec<B>,
+        inserted: Vec<B>,
+        annotations: Option<Vec<Annotation>>,
+    },
+    /// The nucleotide at `pos` was swapped with `base`.
+    Substitute { pos: usize, base: B },
+    /// The annotations were swapped with these annotations.
+    Annotations(Vec<Annotation>),
+    /// The topology and annotations were swapped with these.
+    Topology(Topology, Vec<Annotation>),
+    /// The origin was moved to this position.
+    Rotate(usize),
+}
+
+/// Bounded journal of the edits made to a genetic sequence.
+///
+/// Edits are grouped into transactions, undoing or redoing a transaction
+/// reverts or reapplies all of its edits at once.
+#[derive(Debug, Clone)]
+pub struct EditHistory<B> {
+    undo_stack: VecDeque<Vec<Edit<B>>>,
+    redo_stack: Vec<Vec<Edit<B>>>,
+    transaction: Vec<Edit<B>>,
+    transaction_depth: usize,
+    pending_annotations: Option<Vec<Annotation>>,
+    pending_base: Option<(usize, B)>,
+    limit: usize,
+}
+
+impl<B> EditHistory<B> {
+    /// Create an empty history which keeps at most `limit` transactions.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            transaction: Vec::new(),
+            transaction_depth: 0,
+            pending_annotations: None,
+            pending_base: None,
+            limit,
+        }
+    }
+
+    /// The maximum number of transactions kept in the history.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// The number of transactions that can be undone.
+    pub fn undo_len(&self) -> usize {
+        self.undo_stack.len() + usize::from(!self.transaction.is_empty())
+    }
+
+    /// The number of transactions that can be redone.
+    pub fn redo_len(&self) -> usize {
+        self.redo_stack.len()
+    }
+
+    /// Whether a transaction is currently open.
+    pub fn in_transaction(&self) -> bool {
+        self.transaction_depth > 0
+    }
+
+    pub(crate) fn record(&mut self, edit: Edit<B>) {
+        self.redo_stack.clear();
+        if self.in_transaction() {
+            self.transaction.push(edit);
+        } else {
+            self.push_undo(vec![edit]);
+        }
+    }
+
+    pub(crate) fn begin_transaction(&mut self) {
+        self.transaction_depth += 1;
+    }
+
+    pub(crate) fn commit_transaction(&mut self) {
+        self.transaction_depth = self.transaction_depth.saturating_sub(1);
+        if self.transaction_depth == 0 {
+            self.close_transaction();
+        }
+    }
+
+    /// Close all open transactions.
+    pub(crate) fn close_transaction(&mut self) {
+        self.transaction_depth = 0;
+        if !self.transaction.is_empty() {
+            let edits = std::mem::take(&mut self.transaction);
+            self.push_undo(edits);
+        }
+    }
+
+    pub(crate) fn pop_undo(&mut self) -> Option<Vec<Edit<B>>> {
+        self.undo_stack.pop_back()
+    }
+
+    pub(crate) fn pop_redo(&mut self) -> Option<Vec<Edit<B>>> {
+        self.redo_stack.pop()
+    }
+
+    pub(crate) fn push_redo(&mut self, edits: Vec<Edit<B>>) {
+        self.redo_stack.push(edits);
+    }
+
+    pub(crate) fn push_undo(&mut self, edits: Vec<Edit<B>>) {
+        self.undo_stack.push_back(edits);
+        while self.undo_stack.len() > self.limit {
+            self.undo_stack.pop_front();
+        }
+    }
+
+    /// Keep a copy of `annotations` before they are changed through
+    /// `as_mut_annotations` or `annotations_mut`, recording earlier changes first. Within a
+    /// transaction the first copy is kept until another edit is recorded.
+    pub(crate) fn snapshot_annotations(&mut self, annotations: &[Annotation]) {
+        if self.in_transaction() && self.pending_annotations.is_some() {
+            return;
+        }
+        self.flush_annotations(annotations);
+        self.pending_annotations = Some(annotations.to_vec());
+    }
+
+    /// Record the changes made to `annotations` since the last snapshot.
+    pub(crate) fn flush_annotations(&mut self, annotations: &[Annotation]) {
+        if let Some(pending) = self.pending_annotations.take() {
+            if pending != annotations {
+                self.record(Edit::Annotations(pending));
+            }
+        }
+    }
+
+    pub(crate) fn pending_annotations(&self) -> Option<&Vec<Annotation>> {
+        self.pending_annotations.as_ref()
+    }
+
+    /// Keep the nucleotide at `pos` before it is changed through indexing.
+    /// Pending changes have to be recorded first.
+    pub(crate) fn snapshot_base(&mut self, pos: usize, base: B) {
+        self.pending_base = Some((pos, base));
+    }
+
+    pub(crate) fn take_pending_base(&mut self) -> Option<(usize, B)> {
+        self.pending_base.take()
+    }
+
+    pub(crate) fn pending_base(&self) -> Option<&(usize, B)> {
+        self.pending_base.as_ref()
+    }
+}
+
+/// Mutable access to the annotations of a genetic sequence, see
+/// `GeneticSequence::annotations_mut`.
+///
+/// The annotations are only copied into the edit history once they are
+/// accessed mutably.
+pub struct AnnotationsMut<'a, B> {
+    annotations: &'a mut Vec<Annotation>,
+    history: Option<&'a mut EditHistory<B>>,
+}
+
+impl<'a, B> AnnotationsMut<'a, B> {
+    pub(crate) fn new(
+        annotations: &'a mut Vec<Annotation>,
+        history: Option<&'a mut EditHistory<B>>,
+    ) -> Self {
+        Self {
+            annotations,
+            history,
+        }
+    }
+
+    fn snapshot(&mut self) {
+        if let Some(history) = self.history.take() {
+            history.snapshot_annotations(self.annotations);
+        }
+    }
+}
+
+impl<B> Deref for AnnotationsMut<'_, B> {
+    type Target = Vec<Annotation>;
+
+    fn deref(&self) -> &Self::Target {
+        self.annotations
+    }
+}
+
+impl<B> DerefMut for AnnotationsMut<'_, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.snapshot();
+        self.annotations
+    }
+}
+
+impl<'a, B> IntoIterator for AnnotationsMut<'a, B> {
+    type Item = &'a mut Annotation;
+    type IntoIter = std::slice::IterMut<'a, Annotation>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.snapshot();
+        self.annotations.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edit, EditHistory};
+
+    #[test]
+    fn test_edit_history_limit() {
+        let mut history = EditHistory::<u8>::new(2);
+        for pos in 0..3 {
+            history.record(Edit::Rotate(pos));
+        }
+        assert_eq!(history.undo_len(), 2);
+        assert!(matches!(history.pop_undo().unwrap()[..], [Edit::Rotate(2)]));
+        assert!(matches!(history.pop_undo().unwrap()[..], [Edit::Rotate(1)]));
+        assert!(history.pop_undo().is_none());
+    }
+
+    #[test]
+    fn test_edit_history_transaction() {
+        let mut history = EditHistory::<u8>::new(10);
+        history.begin_transaction();
+        history.record(Edit::Rotate(1));
+        history.begin_transaction();
+        history.record(Edit::Rotate(2));
+        history.commit_transaction();
+        assert!(history.in_transaction());
+        history.commit_transaction();
+        assert!(!history.in_transaction());
+        assert_eq!(history.undo_len(), 1);
+        assert_eq!(history.pop_undo().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_edit_history_record_clears_redo() {
+        let mut history = EditHistory::<u8>::new(10);
+        history.push_redo(vec![Edit::Rotate(1)]);
+        assert_eq!(history.redo_len(), 1);
+        history.record(Edit::Rotate(2));
+        assert_eq!(history.redo_len(), 0);
+    }
+}
@@ -1,64 +1,84 @@
 use std::{
     borrow::Borrow,
-    collections::VecDeque,
+    collections::HashMap,
     marker::PhantomData,
-    ops::{Index, IndexMut},
+    ops::{Bound, Index, IndexMut, Range, RangeBounds},
 };
 
 use crate::{
-    prelude::{IupacNucleotide, RestrictionEnzymes},
+    eaa::{Eaa, GeneticCode, QualifiedEaa},
+    prelude::{CutPositions, IupacNucleotide, RestrictionEnzyme, RestrictionOptions},
+    rna::RnaCodon,
     traits::*,
+    uni::{BuiltinEnzymeDatabase, EnzymeDatabase, RestrictionMap},
 };
 
-use super::Annotation;
+use super::{
+    edit_history::Edit, Annotation, AnnotationsMut, BasePair, EditHistory, Orf, OrfOptions,
+    PositionedEaa, ReadingFrame, SequenceStorage, SequenceView, Strand, Topology,
+    TranslationOptions,
+};
 
-pub struct GeneticSequence<B, C>
+/// A genetic sequence with annotations and an optional edit history.
+///
+/// The nucleotides are kept in the storage `S`, a `Vec<B>` by default. See
+/// `PackedDnaSequence` for DNA packed into two bits per nucleotide.
+#[derive(Debug, Clone)]
+pub struct GeneticSequence<B, C, S = Vec<B>>
 where
     B: Nucleotide,
     C: Codon<B>,
 {
-    sequence: Vec<B>,
+    sequence: S,
     annotations: Vec<Annotation>,
+    topology: Topology,
+    history: Option<EditHistory<B>>,
     phantom: PhantomData<C>,
 }
 
-impl<B, C> GeneticSequence<B, C>
+impl<B, C, S> GeneticSequence<B, C, S>
 where
     B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
     C: Codon<B>,
+    S: SequenceStorage<B>,
 {
     pub fn new() -> Self {
         Self {
-            sequence: Vec::new(),
+            sequence: S::default(),
             annotations: Vec::new(),
+            topology: Topology::Linear,
+            history: None,
             phantom: PhantomData,
         }
     }
 
-    pub fn from_str<T>(s: T) -> anyhow::Result<GeneticSequence<B, C>>
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str<T>(s: T) -> anyhow::Result<Self>
     where
         T: AsRef<str>,
     {
-        let mut builder = GeneticSequence::<B, C>::new();
+        let mut builder = Self::new();
         builder.push_base_str(s)?;
         Ok(builder)
     }
 }
 
-impl<B, C> Default for GeneticSequence<B, C>
+impl<B, C, S> Default for GeneticSequence<B, C, S>
 where
     B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
     C: Codon<B>,
+    S: SequenceStorage<B>,
 {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<B, C> GeneticSequence<B, C>
+impl<B, C, S> GeneticSequence<B, C, S>
 where
     B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
     C: Codon<B> + Sized,
+    S: SequenceStorage<B>,
 {
     /// Append a nucleobase to the end of the sequence.
     ///
@@ -75,7 +95,8 @@ where
     where
         T: Borrow<B>,
     {
-        self.sequence.push(*base.borrow())
+        let len = self.sequence.len();
+        self.splice(len..len, [base]);
     }
 
     /// Append a nucleobase string to the end of the sequence.
@@ -93,10 +114,13 @@ where
     where
         T: AsRef<str>,
     {
-        for c in s.as_ref().chars() {
-            let base = B::try_from_letter(c)?;
-            self.push_base(base)
-        }
+        let bases = s
+            .as_ref()
+            .chars()
+            .map(B::try_from_letter)
+            .collect::<anyhow::Result<Vec<B>>>()?;
+        let len = self.sequence.len();
+        self.splice(len..len, bases);
         Ok(())
     }
 
@@ -116,11 +140,14 @@ where
     where
         T: Borrow<C>,
     {
-        self.sequence.extend(codon.borrow().to_triplet_arr())
+        let len = self.sequence.len();
+        self.splice(len..len, codon.borrow().to_triplet_arr());
     }
 
     /// Remove the last nucleobase from the sequence and return it, or `None` if it is empty.
     ///
+    /// Annotations covering the removed nucleobase are shrunk or dropped.
+    ///
     /// # Examples
     /// ```rust
     /// use plasmid::prelude::{*, DnaNucleotide::*};
@@ -131,306 +158,1410 @@ where
     /// assert_eq!(base, T);
     /// ```
     pub fn pop_base(&mut self) -> Option<B> {
-        self.sequence.pop()
+        let len = self.sequence.len();
+        if len == 0 {
+            return None;
+        }
+        self.splice(len - 1..len, [] as [B; 0]).pop()
     }
 
-    /// Return the last codon from the sequence, or `None` if there are none.
-    /// The function will return the last proper codon in the sequence.
+    /// Replace the nucleobase at position `pos` and return the previous one.
     ///
-    /// Use `#last_codon_unsafe` if you need a codon from the last three nucleobases.
+    /// The substitution is recorded in the edit history, unless the nucleobase
+    /// stays the same.
+    ///
+    /// # Panics
+    /// Panics if `pos` is out of bounds.
     ///
     /// # Examples
     /// ```rust
     /// use plasmid::prelude::{*, DnaNucleotide::*};
     ///
-    /// let mut seq1 = DnaSequence::from_str("AGTAA").unwrap();
-    /// let seq1_codon = seq1.last_codon().unwrap(); // AGT
-    ///
-    /// assert_eq!(seq1_codon, [A, G, T].into());
-    ///
-    /// let mut seq2 = DnaSequence::from_str("AA").unwrap();
-    /// let seq2_codon = seq2.last_codon(); // None
+    /// let mut seq = DnaSequence::from_str("ATG").unwrap();
+    /// seq.enable_history(10);
+    /// assert_eq!(seq.set_base(2, G), G);
+    /// assert!(!seq.can_undo());
     ///
-    /// assert!(seq2_codon.is_none());
+    /// assert_eq!(seq.set_base(0, T), A);
+    /// assert_eq!(seq.to_string(), "TTG");
+    /// seq.undo();
+    /// assert_eq!(seq.to_string(), "ATG");
     /// ```
-    pub fn last_codon(&self) -> Option<C> {
-        self.codon_iter().last()
+    pub fn set_base(&mut self, pos: usize, base: B) -> B
+    where
+        B: PartialEq,
+    {
+        let previous = self.sequence.replace(pos, base);
+        if previous != base {
+            self.flush_pending_edits();
+            if let Some(history) = &mut self.history {
+                history.record(Edit::Substitute {
+                    pos,
+                    base: previous,
+                });
+            }
+        }
+        previous
     }
 
-    /// Return the last codon from the sequence, or `None` if there are none.
-    /// This function will build a codon from the last nucleotide triplet.
+    /// Insert nucleobases before the nucleobase at position `pos`.
+    ///
+    /// Annotations after `pos` are shifted, annotations containing `pos` grow.
+    ///
+    /// # Panics
+    /// Panics if `pos` is greater than the length of the sequence.
     ///
     /// # Examples
     /// ```rust
     /// use plasmid::prelude::{*, DnaNucleotide::*};
     ///
-    /// let mut seq1 = DnaSequence::from_str("AGTAA").unwrap();
-    /// let seq1_codon = seq1.last_codon_unsafe().unwrap(); // TAA
-    ///
-    /// assert_eq!(seq1_codon, [T, A, A].into());
-    ///
-    /// let mut seq2 = DnaSequence::from_str("AA").unwrap();
-    /// let seq2_codon = seq2.last_codon_unsafe(); // None
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.as_mut_annotations().push(Annotation::new(3, 5, None, "Phe"));
+    /// seq.insert_bases(3, [G, C, C]);
     ///
-    /// assert!(seq2_codon.is_none());
+    /// assert_eq!(seq.to_string(), "ATGGCCTTC");
+    /// assert_eq!(seq.as_annotations()[0].start, 6);
+    /// assert_eq!(seq.as_annotations()[0].end, 8);
     /// ```
-    pub fn last_codon_unsafe(&self) -> Option<C> {
-        let seq: [B; 3] = self
-            .sequence
-            .iter()
-            .rev()
-            .take(3)
-            .rev()
-            .cloned()
-            .collect::<Vec<B>>()
-            .try_into()
-            .ok()?;
-        Some(C::from_triplet_arr(seq))
+    pub fn insert_bases<I, T>(&mut self, pos: usize, bases: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Borrow<B>,
+    {
+        self.splice(pos..pos, bases);
     }
 
-    /// An iterator over the nucleotides of a genetic sequence.
+    /// Remove a range of nucleobases from the sequence and return them.
+    ///
+    /// Annotations after the range are shifted, annotations overlapping it are
+    /// shrunk and annotations inside of it are dropped.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
     ///
     /// # Examples
-    /// ```
+    /// ```rust
     /// use plasmid::prelude::{*, DnaNucleotide::*};
     ///
-    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
-    /// let nucleotides = seq.nucleotide_iter().map(|&x| x).collect::<Vec<_>>();
+    /// let mut seq = DnaSequence::from_str("ATGGCCTTC").unwrap();
+    /// seq.as_mut_annotations().push(Annotation::new(0, 5, None, "Met-Ala"));
+    /// let removed = seq.remove_range(3..6);
     ///
-    /// assert_eq!(nucleotides, [T, G, A, T, C, C])
+    /// assert_eq!(removed, [G, C, C]);
+    /// assert_eq!(seq.to_string(), "ATGTTC");
+    /// assert_eq!(seq.as_annotations()[0].end, 2);
     /// ```
-    pub fn nucleotide_iter(&self) -> std::slice::Iter<B> {
-        self.sequence.iter()
+    pub fn remove_range<R>(&mut self, range: R) -> Vec<B>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = self.to_range(range);
+        self.splice(range, [] as [B; 0])
     }
 
-    /// An iterator over the codons of a genetic sequence.
+    /// Replace a range of nucleobases with other nucleobases and return the replaced ones.
+    ///
+    /// The replacement is treated as new sequence: annotations overlapping the range
+    /// lose the replaced nucleobases, annotations spanning the whole range are split
+    /// around it and annotations inside of it are dropped.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
     ///
     /// # Examples
     /// ```rust
-    /// use plasmid::prelude::*;
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
     ///
-    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
-    /// for codon in seq.codon_iter() {
-    ///     println!("{:?}", codon);
-    /// }
+    /// let mut seq = DnaSequence::from_str("ATGGCCTTC").unwrap();
+    /// seq.as_mut_annotations().push(Annotation::new(0, 8, None, "ORF"));
+    /// seq.replace_range(3..6, [T, A]);
+    ///
+    /// assert_eq!(seq.to_string(), "ATGTATTC");
+    /// let parts = seq.as_annotations();
+    /// assert_eq!((parts[0].start, parts[0].end), (0, 2));
+    /// assert_eq!((parts[1].start, parts[1].end), (5, 7));
     /// ```
-    pub fn codon_iter(&self) -> impl Iterator<Item = C> + '_ {
-        self.sequence
-            .chunks_exact(3)
-            .map(|chunk| C::from_triplet_arr(chunk.try_into().unwrap()))
+    pub fn replace_range<R, I, T>(&mut self, range: R, bases: I) -> Vec<B>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+        T: Borrow<B>,
+    {
+        let range = self.to_range(range);
+        self.splice(range, bases)
     }
 
-    /// An iterator over the IUPAC sequence.
+    fn to_range<R>(&self, range: R) -> Range<usize>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => self.sequence.len(),
+        };
+        start..end
+    }
+
+    fn splice<I, T>(&mut self, range: Range<usize>, bases: I) -> Vec<B>
+    where
+        I: IntoIterator<Item = T>,
+        T: Borrow<B>,
+    {
+        self.flush_pending_edits();
+        let pos = range.start;
+        let inserted = bases.into_iter().map(|b| *b.borrow()).collect::<Vec<B>>();
+        let annotations = match self.history {
+            Some(_) if !range.is_empty() => Some(self.annotations.clone()),
+            _ => None,
+        };
+        let removed = self.apply_splice(range, &inserted);
+        if let Some(history) = &mut self.history {
+            history.record(Edit::Splice {
+                pos,
+                removed: removed.clone(),
+                inserted,
+                annotations,
+            });
+        }
+        removed
+    }
+
+    fn apply_splice(&mut self, range: Range<usize>, bases: &[B]) -> Vec<B> {
+        let pos = range.start;
+        let removed = self.sequence.splice(range, bases);
+        self.rebase_annotations(pos, removed.len(), bases.len());
+        removed
+    }
+
+    fn rebase_annotations(&mut self, pos: usize, removed: usize, inserted: usize) {
+        let len = self.sequence.len() + removed - inserted;
+        self.annotations = match self.topology {
+            Topology::Linear => self
+                .annotations
+                .iter()
+                .flat_map(|ann| ann.rebase(pos, removed, inserted))
+                .collect(),
+            Topology::Circular => self
+                .annotations
+                .iter()
+                .flat_map(|ann| ann.rebase_circular(len, pos, removed, inserted))
+                .collect(),
+        };
+    }
+
+    /// The number of nucleotides in the sequence.
     ///
     /// # Examples
     /// ```rust
-    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    /// use plasmid::prelude::*;
     ///
-    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
-    /// assert_eq!(&seq.iupac_iter().collect::<Vec<_>>(), &[T, G, A, T, C, C])
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// assert_eq!(seq.len(), 6);
     /// ```
-    pub fn iupac_iter(&self) -> impl Iterator<Item = IupacNucleotide> + '_ {
-        self.sequence.iter().map(|n| n.to_iupac())
+    pub fn len(&self) -> usize {
+        self.sequence.len()
     }
 
-    /// An iterator over the associated annotations.
+    /// Whether the sequence contains no nucleotides.
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+
+    /// A borrowed view of a region of the sequence.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or its start is after its end.
     ///
     /// # Examples
     /// ```rust
     /// use plasmid::prelude::*;
     ///
-    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// seq.as_mut_annotations().push(Annotation::new(0, 2, None, "Start Codon"));
-    /// assert_eq!(seq.annotation_iter().next().unwrap().text, "Start Codon");
+    /// let seq = DnaSequence::from_str("ATGTTCTAA").unwrap();
+    /// assert_eq!(seq.slice(3..6).to_string(), "TTC");
     /// ```
-    pub fn annotation_iter(&self) -> std::slice::Iter<Annotation> {
-        self.annotations.iter()
+    pub fn slice<R>(&self, range: R) -> SequenceView<'_, B, C, S>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = self.to_range(range);
+        assert!(
+            range.start <= range.end && range.end <= self.sequence.len(),
+            "range {:?} out of bounds for sequence of length {}",
+            range,
+            self.sequence.len()
+        );
+        SequenceView::new(self, range.start, range.end - range.start)
     }
 
-    /// An iterator over the associated annotations.
+    /// A borrowed view of the region from `start` up to (excluding) `end`,
+    /// which wraps around the origin of a circular sequence if `start` is after `end`.
+    ///
+    /// # Panics
+    /// Panics if the region is out of bounds, or if it wraps around the origin
+    /// of a linear sequence.
     ///
     /// # Examples
     /// ```rust
     /// use plasmid::prelude::*;
     ///
-    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// let ann = Annotation::new(0, 2, None, "Start Codon");
-    /// seq.as_mut_annotations().push(ann);
-    /// assert_eq!(seq.annotation_iter().next().unwrap().text, "Start Codon");
+    /// let mut seq = DnaSequence::from_str("ATGTTCTAA").unwrap();
+    /// seq.set_topology(Topology::Circular);
+    /// assert_eq!(seq.slice_wrapping(6, 3).to_string(), "TAAATG");
     /// ```
-    pub fn as_annotations(&self) -> &[Annotation] {
-        &self.annotations
+    pub fn slice_wrapping(&self, start: usize, end: usize) -> SequenceView<'_, B, C, S> {
+        let len = self.sequence.len();
+        if start <= end {
+            return self.slice(start..end);
+        }
+        assert!(
+            start <= len,
+            "range {:?} out of bounds for sequence of length {}",
+            start..end,
+            len
+        );
+        assert!(
+            self.is_circular(),
+            "range {:?} wraps around the origin of a linear sequence",
+            start..end
+        );
+        SequenceView::new(self, start, len - start + end)
     }
 
-    pub fn as_mut_annotations(&mut self) -> &mut Vec<Annotation> {
-        &mut self.annotations
+    /// The topology of the sequence, linear by default.
+    pub fn topology(&self) -> Topology {
+        self.topology
     }
 
-    /// An iterator over the nucleotides of a genetic sequence.
+    /// Whether the sequence is circular.
+    pub fn is_circular(&self) -> bool {
+        self.topology.is_circular()
+    }
+
+    /// Change the topology of the sequence.
+    ///
+    /// Linearizing a circular sequence opens it at the origin, so annotations
+    /// wrapping around the origin are split into two.
     ///
     /// # Examples
-    /// ```
-    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    /// ```rust
+    /// use plasmid::prelude::*;
     ///
-    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
-    /// let nucleotides = seq.as_nucleotides();
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.set_topology(Topology::Circular);
+    /// seq.as_mut_annotations().push(Annotation::new(4, 1, None, "Origin"));
+    /// seq.set_topology(Topology::Linear);
     ///
-    /// assert_eq!(nucleotides, [T, G, A, T, C, C])
+    /// assert_eq!(seq.as_annotations().len(), 2);
     /// ```
-    pub fn as_nucleotides(&self) -> &[B] {
-        &self.sequence
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.flush_pending_edits();
+        if let Some(history) = &mut self.history {
+            history.record(Edit::Topology(self.topology, self.annotations.clone()));
+        }
+        if topology == Topology::Linear {
+            let len = self.sequence.len();
+            self.annotations = self
+                .annotations
+                .iter()
+                .flat_map(|ann| ann.unwrap_origin(len))
+                .collect();
+        }
+        self.topology = topology;
     }
 
-    /// Convert a genetic sequence to a Vec of its anti-nucleotides.
+    /// Move the origin of a circular sequence to position `pos`.
+    ///
+    /// Annotations are rotated along with the sequence and may start or stop
+    /// wrapping around the origin.
     ///
     /// # Examples
-    /// ```
-    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    /// ```rust
+    /// use plasmid::prelude::*;
     ///
-    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
-    /// let nucleotides = seq.as_reverse_complement();
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.set_topology(Topology::Circular);
+    /// seq.as_mut_annotations().push(Annotation::new(0, 2, None, "Start Codon"));
+    /// seq.rotate_to(4).unwrap();
     ///
-    /// assert_eq!(nucleotides, [A, C, T, A, G, G])
+    /// assert_eq!(seq.to_string(), "TCATGT");
+    /// assert_eq!(seq.as_annotations()[0].start, 2);
+    /// assert_eq!(seq.as_annotations()[0].end, 4);
     /// ```
-    pub fn as_reverse_complement(&self) -> Vec<B> {
-        self.sequence
+    pub fn rotate_to(&mut self, pos: usize) -> anyhow::Result<()> {
+        if !self.is_circular() {
+            bail!("Cannot move the origin of a linear sequence")
+        }
+        let len = self.sequence.len();
+        if pos >= len {
+            bail!(
+                "Origin {} is out of bounds for sequence of length {}",
+                pos,
+                len
+            )
+        }
+        self.flush_pending_edits();
+        self.apply_rotation(pos);
+        if let Some(history) = &mut self.history {
+            history.record(Edit::Rotate(pos));
+        }
+        Ok(())
+    }
+
+    fn apply_rotation(&mut self, pos: usize) {
+        let len = self.sequence.len();
+        self.sequence.rotate_left(pos);
+        self.annotations = self
+            .annotations
             .iter()
-            .map(|b| b.complement())
-            .collect::<Vec<_>>()
+            .map(|ann| ann.rotate(len, pos))
+            .collect();
     }
 
-    /// Convert a genetic sequence to a Vec of its codons.
+    /// Start recording edits, keeping at most `limit` transactions.
+    ///
+    /// All edits through the methods of the sequence are recorded, including
+    /// nucleotide substitutions through `set_base` or indexing and annotation
+    /// changes through `as_mut_annotations` or `annotations_mut`. Does nothing
+    /// if the history is already enabled.
     ///
     /// # Examples
     /// ```rust
     /// use plasmid::prelude::{*, DnaNucleotide::*};
     ///
-    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.enable_history(100);
+    /// seq.insert_bases(3, [G, C, C]);
+    /// seq.set_base(0, T);
+    /// assert_eq!(seq.to_string(), "TTGGCCTTC");
     ///
-    /// assert_eq!(seq.as_codons(), [[T, G, A].into(), [T, C, C].into()])
+    /// seq.undo();
+    /// assert_eq!(seq.to_string(), "ATGGCCTTC");
+    /// seq.undo();
+    /// assert_eq!(seq.to_string(), "ATGTTC");
+    /// seq.redo();
+    /// assert_eq!(seq.to_string(), "ATGGCCTTC");
     /// ```
-    pub fn as_codons(&self) -> Vec<C> {
-        self.codon_iter().collect()
+    pub fn enable_history(&mut self, limit: usize) {
+        if self.history.is_none() {
+            self.history = Some(EditHistory::new(limit));
+        }
     }
 
-    /// Return the nucleotide sequence as a string.
-    ///
-    /// # Example
-    /// ```rust
-    /// use plasmid::prelude::*;
-    ///
-    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// assert_eq!(seq.to_nucleotide_string(), "ATGTTC");
-    /// ```
-    pub fn to_nucleotide_string(&self) -> String {
-        self.as_nucleotides()
-            .iter()
-            .map(|b| b.to_letter())
-            .collect()
+    /// Stop recording edits and discard the history.
+    pub fn disable_history(&mut self) {
+        self.history = None;
     }
 
-    /// Return the reverse complement of the nucleotide sequence as a string.
-    ///
-    /// # Example
-    /// ```rust
-    /// use plasmid::prelude::*;
-    ///
-    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// assert_eq!(seq.to_reverse_complement_string(), "TACAAG");
-    /// ```
-    pub fn to_reverse_complement_string(&self) -> String {
-        self.as_reverse_complement()
-            .iter()
-            .map(|b| b.to_letter())
-            .collect()
+    /// The edit history, or `None` if it isn't enabled.
+    pub fn history(&self) -> Option<&EditHistory<B>> {
+        self.history.as_ref()
     }
 
-    /// Count guanine and cytosine nucleotides
-    ///
-    /// # Example
-    /// ```rust
-    /// use plasmid::prelude::*;
-    ///
-    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// assert_eq!(seq.gc_count(), 2);
-    /// ```
-    pub fn gc_count(&self) -> usize {
-        use IupacNucleotide::*;
-        let nucleotides = [G, C];
-        self.iupac_iter()
-            .filter(|n| nucleotides.contains(n))
-            .count()
+    /// Whether there are recorded edits that can be undone.
+    pub fn can_undo(&self) -> bool {
+        match &self.history {
+            Some(history) => {
+                history.undo_len() > 0
+                    || history
+                        .pending_annotations()
+                        .is_some_and(|pending| *pending != self.annotations)
+                    || history
+                        .pending_base()
+                        .is_some_and(|(pos, base)| !self.is_base_at(*pos, base))
+            }
+            None => false,
+        }
     }
 
-    /// Count adenine and thymine/uracil nucleotides
-    ///
-    /// # Example
-    /// ```rust
-    /// use plasmid::prelude::*;
-    ///
-    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// assert_eq!(seq.at_count(), 4);
-    /// ```
-    pub fn at_count(&self) -> usize {
-        use IupacNucleotide::*;
-        let nucleotides = [A, T];
-        self.iupac_iter()
-            .filter(|n| nucleotides.contains(n))
-            .count()
+    /// Whether there are undone edits that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| history.redo_len() > 0)
     }
 
-    /// Compute guanine-cytosine ratio
+    /// Group all following edits into one transaction, which is undone and redone
+    /// as a whole. Transactions can be nested, only the outermost transaction is recorded.
     ///
-    /// # Example
+    /// # Examples
     /// ```rust
     /// use plasmid::prelude::*;
     ///
-    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// let expected = 2_f32 / 6_f32;
-    /// assert!((seq.gc_ratio() - expected).abs() <= std::f32::EPSILON);
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.enable_history(100);
+    /// seq.begin_transaction();
+    /// seq.remove_range(3..);
+    /// seq.push_base_str("TAA").unwrap();
+    /// seq.commit_transaction();
+    /// assert_eq!(seq.to_string(), "ATGTAA");
+    ///
+    /// seq.undo();
+    /// assert_eq!(seq.to_string(), "ATGTTC");
     /// ```
-    pub fn gc_ratio(&self) -> f32 {
-        self.gc_count() as f32 / self.sequence.len() as f32
+    pub fn begin_transaction(&mut self) {
+        self.flush_pending_edits();
+        if let Some(history) = &mut self.history {
+            history.begin_transaction();
+        }
     }
 
-    /// Compute adenine-thymine ratio
+    /// Close the transaction opened by `begin_transaction`.
+    pub fn commit_transaction(&mut self) {
+        self.flush_pending_edits();
+        if let Some(history) = &mut self.history {
+            history.commit_transaction();
+        }
+    }
+
+    /// Run `f` inside of a transaction.
     ///
-    /// # Example
+    /// # Examples
     /// ```rust
-    /// use plasmid::prelude::*;
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
     ///
-    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// let expected = 4_f32 / 6_f32;
-    /// assert!((seq.at_ratio() - expected).abs() <= std::f32::EPSILON);
+    /// let mut seq = DnaSequence::from_str("ATG").unwrap();
+    /// seq.enable_history(100);
+    /// seq.transaction(|seq| {
+    ///     seq.push_base(T);
+    ///     seq.push_base(A);
+    /// });
+    /// seq.undo();
+    /// assert_eq!(seq.to_string(), "ATG");
     /// ```
-    pub fn at_ratio(&self) -> f32 {
-        self.at_count() as f32 / self.sequence.len() as f32
+    pub fn transaction<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        self.begin_transaction();
+        let result = f(self);
+        self.commit_transaction();
+        result
     }
 
-    /// Compute adenine-thymine/uracil to guanine-cytosine ratio
+    /// Revert the last recorded transaction. Open transactions are committed first.
     ///
-    /// # Example
-    /// ```rust
-    /// use plasmid::prelude::*;
-    ///
-    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
-    /// let expected = 4_f32 / 2_f32;
-    /// assert!((seq.at_gc_ratio() - expected).abs() <= std::f32::EPSILON);
-    /// ```
-    pub fn at_gc_ratio(&self) -> f32 {
-        self.at_count() as f32 / self.gc_count() as f32
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        self.flush_pending_edits();
+        let edits = match &mut self.history {
+            Some(history) => {
+                history.close_transaction();
+                history.pop_undo()
+            }
+            None => None,
+        };
+        match edits {
+            Some(mut edits) => {
+                for edit in edits.iter_mut().rev() {
+                    self.revert_edit(edit);
+                }
+                if let Some(history) = &mut self.history {
+                    history.push_redo(edits);
+                }
+                true
+            }
+            None => false,
+        }
     }
 
-    /// Annotate known restriction enzymes.
+    /// Reapply the last undone transaction.
     ///
-    /// The algorithm will iterate over the sequence multiple times
-    /// and try to find cut sites of known restriction enzymes.
+    /// Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        self.flush_pending_edits();
+        let edits = match &mut self.history {
+            Some(history) => {
+                history.close_transaction();
+                history.pop_redo()
+            }
+            None => None,
+        };
+        match edits {
+            Some(mut edits) => {
+                for edit in edits.iter_mut() {
+                    self.reapply_edit(edit);
+                }
+                if let Some(history) = &mut self.history {
+                    history.push_undo(edits);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn revert_edit(&mut self, edit: &mut Edit<B>) {
+        match edit {
+            Edit::Splice {
+                pos,
+                removed,
+                inserted,
+                annotations,
+            } => {
+                self.apply_splice(*pos..*pos + inserted.len(), removed);
+                if let Some(annotations) = annotations {
+                    self.annotations = annotations.clone();
+                }
+            }
+            Edit::Rotate(pos) => {
+                let len = self.sequence.len();
+                self.apply_rotation((len - *pos) % len);
+            }
+            _ => self.swap_edit(edit),
+        }
+    }
+
+    fn reapply_edit(&mut self, edit: &mut Edit<B>) {
+        match edit {
+            Edit::Splice {
+                pos,
+                removed,
+                inserted,
+                ..
+            } => {
+                self.apply_splice(*pos..*pos + removed.len(), inserted);
+            }
+            Edit::Rotate(pos) => self.apply_rotation(*pos),
+            _ => self.swap_edit(edit),
+        }
+    }
+
+    /// Return the last codon from the sequence, or `None` if there are none.
+    /// The function will return the last proper codon in the sequence.
+    ///
+    /// Use `#last_codon_unsafe` if you need a codon from the last three nucleobases.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let mut seq1 = DnaSequence::from_str("AGTAA").unwrap();
+    /// let seq1_codon = seq1.last_codon().unwrap(); // AGT
+    ///
+    /// assert_eq!(seq1_codon, [A, G, T].into());
+    ///
+    /// let mut seq2 = DnaSequence::from_str("AA").unwrap();
+    /// let seq2_codon = seq2.last_codon(); // None
+    ///
+    /// assert!(seq2_codon.is_none());
+    /// ```
+    pub fn last_codon(&self) -> Option<C> {
+        self.codon_iter().last()
+    }
+
+    /// Return the last codon from the sequence, or `None` if there are none.
+    /// This function will build a codon from the last nucleotide triplet.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let mut seq1 = DnaSequence::from_str("AGTAA").unwrap();
+    /// let seq1_codon = seq1.last_codon_unsafe().unwrap(); // TAA
+    ///
+    /// assert_eq!(seq1_codon, [T, A, A].into());
+    ///
+    /// let mut seq2 = DnaSequence::from_str("AA").unwrap();
+    /// let seq2_codon = seq2.last_codon_unsafe(); // None
+    ///
+    /// assert!(seq2_codon.is_none());
+    /// ```
+    pub fn last_codon_unsafe(&self) -> Option<C> {
+        let seq: [B; 3] = self
+            .sequence
+            .iter()
+            .rev()
+            .take(3)
+            .rev()
+            .cloned()
+            .collect::<Vec<B>>()
+            .try_into()
+            .ok()?;
+        Some(C::from_triplet_arr(seq))
+    }
+
+    /// An iterator over the nucleotides of a genetic sequence.
+    ///
+    /// # Examples
+    /// ```
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// let nucleotides = seq.nucleotide_iter().map(|&x| x).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(nucleotides, [T, G, A, T, C, C])
+    /// ```
+    pub fn nucleotide_iter(&self) -> S::Iter<'_> {
+        self.sequence.iter()
+    }
+
+    /// An iterator over the nucleotides starting at position `pos`.
+    ///
+    /// On circular sequences the iterator wraps around the origin and yields
+    /// every nucleotide exactly once, on linear sequences it stops at the end.
+    ///
+    /// # Examples
+    /// ```
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let mut seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// assert_eq!(seq.nucleotide_iter_from(4).copied().collect::<Vec<_>>(), [C, C]);
+    ///
+    /// seq.set_topology(Topology::Circular);
+    /// let nucleotides = seq.nucleotide_iter_from(4).copied().collect::<Vec<_>>();
+    /// assert_eq!(nucleotides, [C, C, T, G, A, T]);
+    /// ```
+    pub fn nucleotide_iter_from(&self, pos: usize) -> impl Iterator<Item = &B> + '_ {
+        assert!(
+            pos <= self.sequence.len(),
+            "position {} out of bounds for sequence of length {}",
+            pos,
+            self.sequence.len()
+        );
+        let wrapped = match self.topology {
+            Topology::Linear => 0,
+            Topology::Circular => pos,
+        };
+        self.sequence
+            .iter()
+            .skip(pos)
+            .chain(self.sequence.iter().take(wrapped))
+    }
+
+    /// An iterator over the codons of a genetic sequence.
+    ///
+    /// On circular sequences the last codon may wrap around the origin,
+    /// on linear sequences trailing nucleotides that don't form a codon are skipped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// for codon in seq.codon_iter() {
+    ///     println!("{:?}", codon);
+    /// }
+    /// ```
+    pub fn codon_iter(&self) -> impl Iterator<Item = C> + '_ {
+        self.codon_iter_frame(ReadingFrame::Plus1)
+    }
+
+    /// An iterator over the codons of one of the six reading frames.
+    ///
+    /// Codons of reverse frames are read 5' to 3' on the reverse complement.
+    /// On circular sequences the last codon may wrap around the origin,
+    /// on linear sequences trailing nucleotides that don't form a codon are skipped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGCCGTAA").unwrap();
+    /// let codons = seq
+    ///     .codon_iter_frame(ReadingFrame::Minus2)
+    ///     .map(|codon| codon.to_string())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(codons, ["TAC", "GGC"]);
+    /// ```
+    pub fn codon_iter_frame(&self, frame: ReadingFrame) -> impl Iterator<Item = C> + '_ {
+        self.frame_codon_starts(frame)
+            .map(move |pos| C::from_triplet_arr(self.strand_triplet(frame.strand(), pos)))
+    }
+
+    /// Translate the codons of the sequence using the standard genetic code.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTGGTGA").unwrap();
+    /// assert_eq!(seq.translate(), [Eaa::Met, Eaa::Trp, Eaa::Ter]);
+    /// ```
+    pub fn translate(&self) -> Vec<Eaa>
+    where
+        C: Into<RnaCodon>,
+    {
+        self.translate_with(GeneticCode::standard())
+    }
+
+    /// Translate the codons of the sequence using an alternative genetic code.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTGGTGA").unwrap();
+    /// let code = GeneticCode::from_id(4).unwrap();
+    /// assert_eq!(seq.translate_with(code), [Eaa::Met, Eaa::Trp, Eaa::Trp]);
+    /// ```
+    pub fn translate_with(&self, code: &GeneticCode) -> Vec<Eaa>
+    where
+        C: Into<RnaCodon>,
+    {
+        self.codon_iter()
+            .map(|codon| code.translate(&codon.into()))
+            .collect()
+    }
+
+    /// Lazily translate one of the six reading frames.
+    ///
+    /// Every amino acid comes with its codon and the positions of the codon on the
+    /// top strand. The translation can begin at the first start codon of the genetic
+    /// code and end before the first stop codon, see `TranslationOptions`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("CCATGTTCTAAGG").unwrap();
+    /// let protein = seq
+    ///     .translate_iter(ReadingFrame::Plus3, TranslationOptions::protein())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(protein.len(), 2);
+    /// assert_eq!(protein[0].eaa.eaa, Eaa::Met);
+    /// assert_eq!((protein[1].start, protein[1].end), (5, 7));
+    /// ```
+    pub fn translate_iter(
+        &self,
+        frame: ReadingFrame,
+        options: TranslationOptions,
+    ) -> impl Iterator<Item = PositionedEaa> + '_
+    where
+        C: Into<RnaCodon>,
+    {
+        let mut started = !options.start_at_met;
+        self.frame_codon_starts(frame)
+            .filter_map(move |pos| {
+                let codon: RnaCodon =
+                    C::from_triplet_arr(self.strand_triplet(frame.strand(), pos)).into();
+                let mut eaa = options.genetic_code.translate(&codon);
+                if !started {
+                    if !options.genetic_code.is_start(&codon) {
+                        return None;
+                    }
+                    // Alternative start codons are translated as methionine too
+                    started = true;
+                    eaa = Eaa::Met;
+                }
+                let (start, end) = self.strand_codon_bounds(frame.strand(), pos);
+                Some(PositionedEaa {
+                    eaa: QualifiedEaa { eaa, codon },
+                    start,
+                    end,
+                })
+            })
+            .take_while(move |aa| !(options.stop_at_ter && aa.eaa.eaa == Eaa::Ter))
+    }
+
+    /// Find the open reading frames in all six reading frames.
+    ///
+    /// An open reading frame runs from one of the start codons in `options` up to
+    /// and including the next stop codon in the same frame. On circular sequences
+    /// open reading frames may span the origin, but never exceed one full turn.
+    /// The results are sorted by their start position on the top strand.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("CCATGAAACCCTGAGGTTAGGTAACAGACAT").unwrap();
+    /// let orfs = seq.find_orfs(&OrfOptions::default().with_min_codons(3));
+    ///
+    /// assert_eq!(orfs.len(), 2);
+    /// assert_eq!(orfs[0].protein_string(), "MKP");
+    /// assert_eq!(orfs[0].frame, ReadingFrame::Plus3);
+    /// assert_eq!((orfs[0].start, orfs[0].end), (2, 13));
+    /// assert_eq!(orfs[1].protein_string(), "MSVT");
+    /// assert_eq!(orfs[1].frame, ReadingFrame::Minus1);
+    /// assert_eq!((orfs[1].start, orfs[1].end), (16, 30));
+    /// ```
+    pub fn find_orfs(&self, options: &OrfOptions) -> Vec<Orf>
+    where
+        C: Into<RnaCodon>,
+    {
+        let len = self.sequence.len();
+        if len < 3 {
+            return Vec::new();
+        }
+        let mut orfs = Vec::new();
+        for strand in [Strand::Forward, Strand::Reverse] {
+            // Codons at every position of the strand
+            let positions = match self.topology {
+                Topology::Linear => len - 2,
+                Topology::Circular => len,
+            };
+            let codons = (0..positions)
+                .map(|pos| C::from_triplet_arr(self.strand_triplet(strand, pos)).into())
+                .collect::<Vec<RnaCodon>>();
+            let eaas = codons
+                .iter()
+                .map(|codon| options.genetic_code.translate(codon))
+                .collect::<Vec<_>>();
+            let stop_distance = self.stop_distances(&eaas);
+
+            // Open reading frames by the position of their stop codon
+            let mut by_stop: HashMap<usize, Vec<(usize, usize)>> = HashMap::new();
+            for (pos, codon) in codons.iter().enumerate() {
+                if !options.start_codons.contains(codon) {
+                    continue;
+                }
+                let Some(count) = stop_distance[pos] else {
+                    continue;
+                };
+                if count < options.min_codons {
+                    continue;
+                }
+                let stop = (pos + count * 3) % len;
+                by_stop.entry(stop).or_default().push((pos, count));
+            }
+            for (stop, mut starts) in by_stop {
+                if !options.nested {
+                    starts.sort_by_key(|&(_, count)| count);
+                    starts.drain(..starts.len() - 1);
+                }
+                for (pos, count) in starts {
+                    let (first, last) = match strand {
+                        Strand::Forward => (pos, stop),
+                        Strand::Reverse => (stop, pos),
+                    };
+                    orfs.push(Orf {
+                        strand,
+                        frame: ReadingFrame::new(strand, pos),
+                        start: self.strand_codon_bounds(strand, first).0,
+                        end: self.strand_codon_bounds(strand, last).1,
+                        protein: std::iter::once(Eaa::Met)
+                            .chain((1..count).map(|i| eaas[(pos + i * 3) % len]))
+                            .take(count)
+                            .collect(),
+                    });
+                }
+            }
+        }
+        orfs.sort_by_key(|orf| (orf.start, orf.end, orf.strand == Strand::Reverse));
+        orfs
+    }
+
+    /// The number of codons from every position up to the next stop codon in the
+    /// same frame, if there is one. On circular sequences the search is limited
+    /// to one full turn.
+    fn stop_distances(&self, eaas: &[Eaa]) -> Vec<Option<usize>> {
+        let mut distances = vec![None; eaas.len()];
+        match self.topology {
+            Topology::Linear => {
+                for pos in (0..eaas.len()).rev() {
+                    distances[pos] = if eaas[pos] == Eaa::Ter {
+                        Some(0)
+                    } else {
+                        distances.get(pos + 3).copied().flatten().map(|d| d + 1)
+                    };
+                }
+            }
+            Topology::Circular => {
+                // Codon positions form one cycle if the length isn't a multiple of
+                // three, and three cycles otherwise. Each cycle is walked backwards
+                // twice so that every position sees the next stop codon.
+                let len = eaas.len();
+                let max_codons = len / 3;
+                let mut visited = vec![false; len];
+                for first in 0..len {
+                    if visited[first] {
+                        continue;
+                    }
+                    let mut cycle = vec![first];
+                    let mut pos = (first + 3) % len;
+                    while pos != first {
+                        cycle.push(pos);
+                        pos = (pos + 3) % len;
+                    }
+                    let mut distance: Option<usize> = None;
+                    for i in (0..cycle.len() * 2).rev() {
+                        let pos = cycle[i % cycle.len()];
+                        distance = match eaas[pos] {
+                            Eaa::Ter => Some(0),
+                            _ => distance.map(|d| d + 1),
+                        };
+                        if i < cycle.len() {
+                            visited[pos] = true;
+                            distances[pos] = distance.filter(|&d| d < max_codons);
+                        }
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    /// Positions of the codons of a reading frame on the strand of the frame.
+    fn frame_codon_starts(&self, frame: ReadingFrame) -> impl Iterator<Item = usize> {
+        let len = self.sequence.len();
+        let count = match self.topology {
+            Topology::Linear => len.saturating_sub(frame.offset()) / 3,
+            // Shorter sequences can't hold a codon, even wrapping around the origin
+            Topology::Circular if len < 3 => 0,
+            Topology::Circular => len.div_ceil(3),
+        };
+        (0..count).map(move |i| frame.offset() + i * 3)
+    }
+
+    /// The nucleotides of the codon at `pos` on `strand`.
+    fn strand_triplet(&self, strand: Strand, pos: usize) -> [B; 3] {
+        let len = self.sequence.len();
+        let base = |i: usize| match strand {
+            Strand::Forward => self[(pos + i) % len],
+            Strand::Reverse => self[len - 1 - (pos + i) % len].complement(),
+        };
+        [base(0), base(1), base(2)]
+    }
+
+    /// The first and last top strand position of the codon at `pos` on `strand`.
+    fn strand_codon_bounds(&self, strand: Strand, pos: usize) -> (usize, usize) {
+        let len = self.sequence.len();
+        match strand {
+            Strand::Forward => (pos % len, (pos + 2) % len),
+            Strand::Reverse => (len - 1 - (pos + 2) % len, len - 1 - pos % len),
+        }
+    }
+
+    /// An iterator over the IUPAC sequence.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// assert_eq!(&seq.iupac_iter().collect::<Vec<_>>(), &[T, G, A, T, C, C])
+    /// ```
+    pub fn iupac_iter(&self) -> impl Iterator<Item = IupacNucleotide> + '_ {
+        self.sequence.iter().map(|n| n.to_iupac())
+    }
+
+    /// An iterator over the associated annotations.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.as_mut_annotations().push(Annotation::new(0, 2, None, "Start Codon"));
+    /// assert_eq!(seq.annotation_iter().next().unwrap().text, "Start Codon");
+    /// ```
+    pub fn annotation_iter(&self) -> std::slice::Iter<'_, Annotation> {
+        self.annotations.iter()
+    }
+
+    /// An iterator over the associated annotations.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// let ann = Annotation::new(0, 2, None, "Start Codon");
+    /// seq.as_mut_annotations().push(ann);
+    /// assert_eq!(seq.annotation_iter().next().unwrap().text, "Start Codon");
+    /// ```
+    pub fn as_annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Mutable access to the associated annotations.
+    ///
+    /// If the edit history is enabled, the annotations are copied on every call
+    /// and the changes are recorded as one edit the next time the history is used.
+    /// Use `annotations_mut` to only copy them once they are changed.
+    pub fn as_mut_annotations(&mut self) -> &mut Vec<Annotation> {
+        self.flush_pending_edits();
+        if let Some(history) = &mut self.history {
+            history.snapshot_annotations(&self.annotations);
+        }
+        &mut self.annotations
+    }
+
+    /// Mutable access to the associated annotations, like `as_mut_annotations`.
+    ///
+    /// If the edit history is enabled, the annotations are copied the first time
+    /// they are changed through the returned value, and the changes are recorded
+    /// as one edit the next time the history is used. Within a transaction, all
+    /// changes until the next other edit share one copy.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.enable_history(10);
+    /// seq.annotations_mut().push(Annotation::new(0, 2, None, "Start Codon"));
+    /// seq.undo();
+    /// assert!(seq.as_annotations().is_empty());
+    /// ```
+    pub fn annotations_mut(&mut self) -> AnnotationsMut<'_, B> {
+        self.flush_pending_edits();
+        AnnotationsMut::new(&mut self.annotations, self.history.as_mut())
+    }
+
+    /// Convert a genetic sequence to a Vec of its anti-nucleotides,
+    /// i.e. the complementary strand read 3' to 5'.
+    ///
+    /// # Examples
+    /// ```
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// let nucleotides = seq.as_complement();
+    ///
+    /// assert_eq!(nucleotides, [A, C, T, A, G, G])
+    /// ```
+    pub fn as_complement(&self) -> Vec<B> {
+        self.sequence.complement().iter().copied().collect()
+    }
+
+    /// Convert a genetic sequence to a Vec of its anti-nucleotides.
+    ///
+    /// Despite its name, the nucleotides are only complemented and not reversed,
+    /// i.e. the complementary strand is read 3' to 5'.
+    ///
+    /// # Examples
+    /// ```
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// let nucleotides = seq.as_reverse_complement();
+    ///
+    /// assert_eq!(nucleotides, [A, C, T, A, G, G])
+    /// ```
+    #[deprecated(
+        note = "only complements the nucleotides, use `as_complement` for the same result \
+                or `as_reverse_strand` for the reverse complement"
+    )]
+    pub fn as_reverse_complement(&self) -> Vec<B> {
+        self.as_complement()
+    }
+
+    /// Convert a genetic sequence to a Vec of its reverse complement,
+    /// i.e. the complementary strand read 5' to 3'.
+    ///
+    /// # Examples
+    /// ```
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// let nucleotides = seq.as_reverse_strand();
+    ///
+    /// assert_eq!(nucleotides, [G, G, A, T, C, A])
+    /// ```
+    pub fn as_reverse_strand(&self) -> Vec<B> {
+        self.reverse_complement_iter().collect()
+    }
+
+    /// Iterate over the reverse complement of the sequence, i.e. the complementary
+    /// strand read 5' to 3'.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("AAC").unwrap();
+    /// let mut iter = seq.reverse_complement_iter();
+    ///
+    /// assert_eq!(iter.next(), Some(G));
+    /// assert_eq!(iter.next(), Some(T));
+    /// assert_eq!(iter.next(), Some(T));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn reverse_complement_iter(&self) -> impl DoubleEndedIterator<Item = B> + '_ {
+        self.sequence.iter().rev().map(|b| b.complement())
+    }
+
+    /// Iterate over the base pairs of the sequence and its complementary strand.
+    ///
+    /// A single-stranded sequence is assumed to be perfectly paired, use
+    /// `DoubleStrandedDna::base_pair_iter` for mismatches and overhangs.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("AC").unwrap();
+    /// let pairs = seq.base_pair_iter().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(pairs[1].position, 1);
+    /// assert_eq!((pairs[1].top, pairs[1].bottom), (Some(C), Some(G)));
+    /// assert!(pairs.iter().all(|pair| pair.is_paired()));
+    /// ```
+    pub fn base_pair_iter(&self) -> impl DoubleEndedIterator<Item = BasePair<B>> + '_ {
+        self.sequence.iter().enumerate().map(|(i, b)| {
+            let (top, bottom) = b.base_pair();
+            BasePair {
+                position: i as isize,
+                top: Some(top),
+                bottom: Some(bottom),
+            }
+        })
+    }
+
+    /// Create the reverse complement of the sequence as a new sequence.
+    ///
+    /// The topology is kept and the annotations are mirrored onto the new strand.
+    /// The edit history is not carried over.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.as_mut_annotations().push(Annotation::new(0, 2, None, "ATG"));
+    ///
+    /// let rc = seq.reverse_complement();
+    /// assert_eq!(rc.to_nucleotide_string(), "GAACAT");
+    /// assert_eq!((rc.as_annotations()[0].start, rc.as_annotations()[0].end), (3, 5));
+    /// ```
+    pub fn reverse_complement(&self) -> Self {
+        let len = self.len();
+        let mut reversed = Self::new();
+        reversed.sequence = self.sequence.reverse_complement();
+        reversed.topology = self.topology;
+        reversed.annotations = self
+            .annotations
+            .iter()
+            .map(|ann| {
+                let ann = ann.reverse(len);
+                if self.is_circular() {
+                    ann.map_needles(|n| Some(n % len))
+                } else {
+                    ann
+                }
+            })
+            .collect();
+        reversed
+    }
+
+    /// Copy the sequence into another storage, e.g. to pack it into two bits
+    /// per nucleotide. The annotations and the topology are kept, the edit
+    /// history is not carried over.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// seq.as_mut_annotations().push(Annotation::new(0, 2, None, "ATG"));
+    ///
+    /// let packed: PackedDnaSequence = seq.to_storage();
+    /// assert_eq!(packed.to_string(), "ATGTTC");
+    /// assert_eq!(packed.as_annotations(), seq.as_annotations());
+    /// ```
+    pub fn to_storage<T>(&self) -> GeneticSequence<B, C, T>
+    where
+        T: SequenceStorage<B>,
+    {
+        let mut seq = self
+            .nucleotide_iter()
+            .copied()
+            .collect::<GeneticSequence<B, C, T>>();
+        seq.topology = self.topology;
+        seq.annotations = self.annotations.clone();
+        seq
+    }
+
+    /// Convert a genetic sequence to a Vec of its codons.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    ///
+    /// assert_eq!(seq.as_codons(), [[T, G, A].into(), [T, C, C].into()])
+    /// ```
+    pub fn as_codons(&self) -> Vec<C> {
+        self.codon_iter().collect()
+    }
+
+    /// Return the nucleotide sequence as a string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// assert_eq!(seq.to_nucleotide_string(), "ATGTTC");
+    /// ```
+    pub fn to_nucleotide_string(&self) -> String {
+        self.nucleotide_iter().map(|b| b.to_letter()).collect()
+    }
+
+    /// Return the complement of the nucleotide sequence as a string, read 3' to 5'.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// assert_eq!(seq.to_complement_string(), "TACAAG");
+    /// ```
+    pub fn to_complement_string(&self) -> String {
+        self.sequence
+            .complement()
+            .iter()
+            .map(|b| b.to_letter())
+            .collect()
+    }
+
+    /// Return the reverse complement of the nucleotide sequence as a string.
+    ///
+    /// Despite its name, the nucleotides are only complemented and not reversed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// assert_eq!(seq.to_reverse_complement_string(), "TACAAG");
+    /// ```
+    #[deprecated(
+        note = "only complements the nucleotides, use `to_complement_string` for the same \
+                result or `to_reverse_strand_string` for the reverse complement"
+    )]
+    pub fn to_reverse_complement_string(&self) -> String {
+        self.to_complement_string()
+    }
+
+    /// Return the reverse complement of the nucleotide sequence as a string, read 5' to 3'.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// assert_eq!(seq.to_reverse_strand_string(), "GAACAT");
+    /// ```
+    pub fn to_reverse_strand_string(&self) -> String {
+        self.sequence
+            .reverse_complement()
+            .iter()
+            .map(|b| b.to_letter())
+            .collect()
+    }
+
+    /// Count guanine and cytosine nucleotides
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// assert_eq!(seq.gc_count(), 2);
+    /// ```
+    pub fn gc_count(&self) -> usize {
+        self.sequence
+            .count(&[IupacNucleotide::G, IupacNucleotide::C])
+    }
+
+    /// Count adenine and thymine/uracil nucleotides
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// assert_eq!(seq.at_count(), 4);
+    /// ```
+    pub fn at_count(&self) -> usize {
+        self.sequence
+            .count(&[IupacNucleotide::A, IupacNucleotide::T])
+    }
+
+    /// Compute guanine-cytosine ratio
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// let expected = 2_f32 / 6_f32;
+    /// assert!((seq.gc_ratio() - expected).abs() <= std::f32::EPSILON);
+    /// ```
+    pub fn gc_ratio(&self) -> f32 {
+        self.gc_count() as f32 / self.len() as f32
+    }
+
+    /// Compute adenine-thymine ratio
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// let expected = 4_f32 / 6_f32;
+    /// assert!((seq.at_ratio() - expected).abs() <= std::f32::EPSILON);
+    /// ```
+    pub fn at_ratio(&self) -> f32 {
+        self.at_count() as f32 / self.len() as f32
+    }
+
+    /// Compute adenine-thymine/uracil to guanine-cytosine ratio
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
     ///
-    /// All detected cut sites will be annotated with their corresponding
-    /// start-, stop-, and cut-positions. The cut position will be stored
-    /// inside of the `needle` variable of the `Annotation`.
+    /// let seq = DnaSequence::from_str("ATGTTC").unwrap();
+    /// let expected = 4_f32 / 2_f32;
+    /// assert!((seq.at_gc_ratio() - expected).abs() <= std::f32::EPSILON);
+    /// ```
+    pub fn at_gc_ratio(&self) -> f32 {
+        self.at_count() as f32 / self.gc_count() as f32
+    }
+
+    /// Find all positions at which an IUPAC pattern matches the sequence.
+    ///
+    /// On circular sequences, matches spanning the origin are found as well.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// let mut seq = DnaSequence::from_str("CATGTTCA").unwrap();
+    /// assert_eq!(seq.find_pattern(&[C, A, N]), [0]);
     ///
-    /// This function is relatively slow, don't run it on every insertion/deletion.
+    /// seq.set_topology(Topology::Circular);
+    /// assert_eq!(seq.find_pattern(&[C, A, N]), [0, 6]);
+    /// ```
+    pub fn find_pattern<T>(&self, pattern: &[T]) -> Vec<usize>
+    where
+        T: ToIupac,
+    {
+        let pattern = pattern.iter().map(|p| p.to_iupac()).collect::<Vec<_>>();
+        self.sequence.find_pattern(&pattern, self.topology)
+    }
+
+    /// Annotate known restriction enzymes.
+    ///
+    /// The algorithm will iterate over the sequence once and find the
+    /// cut sites of all known restriction enzymes, see `RestrictionSiteScanner`.
+    /// On circular sequences, sites spanning the origin are found as well
+    /// and annotated with a wrapping annotation.
+    ///
+    /// Enzymes with non-palindromic recognition sites are searched for on both
+    /// strands, and their annotations carry the strand the site was found on.
+    /// Annotations of palindromic sites are unstranded.
+    ///
+    /// All detected cut sites will be annotated with their corresponding
+    /// start-, stop-, and cut-positions. The cut position on the top strand will
+    /// be stored inside of the `needle` variable of the `Annotation`, and the one
+    /// on the bottom strand inside of `bottom_needle`, both as the position of the
+    /// first nucleotide after the cut. Nicking enzymes only set one of them.
+    /// Annotations of enzymes cutting outside of their recognition site are
+    /// extended to cover the cut positions, and enzymes cutting on both sides
+    /// of their site are annotated once for each cut.
     ///
     /// # Examples
     /// ```rust
@@ -440,80 +1571,287 @@ where
     /// seq.annotate_restriction_enzymes(); // should find NdeI: CA/TATG
     /// let ann = seq.as_annotations().first().unwrap();
     /// assert_eq!(ann.text, "NdeI");
-    /// assert_eq!(ann.start, 5);
-    /// assert_eq!(ann.needle, Some(7));
+    /// assert_eq!(ann.start, 6);
+    /// assert_eq!(ann.needle, Some(8));
+    /// assert_eq!(ann.bottom_needle, Some(10));
     /// assert_eq!(ann.end, 11);
     /// ```
     pub fn annotate_restriction_enzymes(&mut self) {
-        let own_nucleotides = self.as_nucleotides();
+        self.annotate_restriction_enzymes_with(&BuiltinEnzymeDatabase, &Default::default());
+    }
+
+    /// Annotate the cut sites of the restriction enzymes of `database`,
+    /// see `annotate_restriction_enzymes`.
+    ///
+    /// Sites blocked by the methylation of `options` are annotated with the text
+    /// `"<enzyme> (blocked by <methylation>)"`, sites lacking a methylation their
+    /// enzyme requires with `"<enzyme> (requires <methylation>)"`. Both are left
+    /// out if `exclude_blocked` is set.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut database = EnzymeDatabase::builtin();
+    /// database.add_subset("freezer", ["EcoRI", "XbaI"]).unwrap();
+    /// let freezer = database.subset("freezer").unwrap();
+    /// let options = RestrictionOptions::default().with_methylation(MethylationProfile::ecoli());
+    ///
+    /// let mut seq = DnaSequence::from_str("GAATTCATATCTAGATC").unwrap();
+    /// seq.annotate_restriction_enzymes_with(&freezer, &options);
+    /// let names = seq.annotation_iter().map(|ann| ann.text.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(names, ["EcoRI", "XbaI (blocked by Dam)"]);
+    /// ```
+    pub fn annotate_restriction_enzymes_with(
+        &mut self,
+        database: &EnzymeDatabase,
+        options: &RestrictionOptions,
+    ) {
+        self.flush_pending_edits();
+        if let Some(history) = &mut self.history {
+            history.record(Edit::Annotations(self.annotations.clone()));
+        }
         let mut annotations: Vec<Annotation> = Vec::new();
-        for enzyme in RestrictionEnzymes.iter() {
-            let mut enzyme_seq = enzyme.before.clone();
-            enzyme_seq.extend(&enzyme.after);
-            let mut nucleotides: VecDeque<&B> = VecDeque::new();
-            for (own_index, own_nucleotide) in own_nucleotides.iter().enumerate() {
-                if nucleotides.len() == enzyme_seq.len() {
-                    nucleotides.pop_front();
-                }
-                nucleotides.push_back(own_nucleotide);
-                if nucleotides.len() == enzyme_seq.len() {
-                    let seq_matches = enzyme_seq
-                        .iter()
-                        .zip(nucleotides.iter().map(|n| n.to_iupac()))
-                        .all(|(n, m)| n.matches(&m));
-                    if seq_matches {
-                        let needle = own_index - nucleotides.len() + enzyme.before.len();
-                        let ann = Annotation::new_from_restriction_enzyme(
-                            own_index - nucleotides.len(),
-                            own_index,
-                            Some(needle),
-                            enzyme,
-                        );
-                        annotations.push(ann);
-                    }
-                }
+        let enzymes = database.iter().collect::<Vec<_>>();
+        let sites =
+            database.scan_methylated(self.sequence.iter(), self.topology, &options.methylation);
+        for site in sites {
+            let enzyme = enzymes[site.enzyme];
+            if !site.is_cut() && options.exclude_blocked {
+                continue;
             }
+            annotations.extend(site.cuts(enzyme).map(|cut| {
+                let annotation = self.restriction_annotation(enzyme, cut, site.start);
+                Annotation {
+                    strand: site.strand,
+                    text: match (site.blocked_by, site.unmethylated) {
+                        (Some(methylation), _) => {
+                            format!("{} (blocked by {})", annotation.text, methylation)
+                        }
+                        (None, Some(methylation)) => {
+                            format!("{} (requires {})", annotation.text, methylation)
+                        }
+                        (None, None) => annotation.text.clone(),
+                    },
+                    ..annotation
+                }
+            }));
         }
         self.annotations.extend(annotations);
     }
+
+    /// The recognition sites of the enzymes of `database` in the sequence,
+    /// to find e.g. unique cutters, see `RestrictionMap`.
+    pub fn restriction_map<'a>(
+        &self,
+        database: &'a EnzymeDatabase,
+        options: &RestrictionOptions,
+    ) -> RestrictionMap<'a> {
+        RestrictionMap::new(self.sequence.iter(), self.topology, database, options)
+    }
+
+    /// Annotation of the `cut` of `enzyme` at the recognition site at `start`,
+    /// covering the recognition site and both cut positions.
+    /// The cut positions are relative to `start` in top strand coordinates.
+    fn restriction_annotation(
+        &self,
+        enzyme: &RestrictionEnzyme,
+        cut: CutPositions,
+        start: usize,
+    ) -> Annotation {
+        let len = self.len() as isize;
+        let site_len = enzyme.recognition_site().len() as isize;
+        let cuts = cut.top.into_iter().chain(cut.bottom);
+        let (mut first, mut last) = cuts.fold((0, site_len - 1), |(first, last), pos| {
+            (first.min(pos), last.max(pos - 1))
+        });
+        let start = start as isize;
+        let position = |offset: isize| match self.topology {
+            Topology::Linear => (0..=len)
+                .contains(&(start + offset))
+                .then_some((start + offset) as usize),
+            Topology::Circular => Some((start + offset).rem_euclid(len) as usize),
+        };
+        if !self.is_circular() {
+            first = first.max(-start);
+            last = last.min(len - 1 - start);
+        }
+        let mut annotation = Annotation::new_from_restriction_enzyme(
+            position(first).unwrap(),
+            position(last).unwrap(),
+            cut.top.and_then(position),
+            enzyme,
+        );
+        annotation.bottom_needle = cut.bottom.and_then(position);
+        annotation
+    }
+
+    /// Find the open reading frames of the sequence and annotate them.
+    ///
+    /// Returns the open reading frames that have been annotated,
+    /// see `find_orfs` for details.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("CCATGAAACCCTGAGG").unwrap();
+    /// seq.annotate_orfs(&OrfOptions::default().with_min_codons(3));
+    ///
+    /// let ann = seq.as_annotations().first().unwrap();
+    /// assert_eq!(ann.text, "ORF +3 (3 aa)");
+    /// assert_eq!((ann.start, ann.end), (2, 13));
+    /// assert_eq!(ann.strand, Some(Strand::Forward));
+    /// ```
+    pub fn annotate_orfs(&mut self, options: &OrfOptions) -> Vec<Orf>
+    where
+        C: Into<RnaCodon>,
+    {
+        self.flush_pending_edits();
+        if let Some(history) = &mut self.history {
+            history.record(Edit::Annotations(self.annotations.clone()));
+        }
+        let orfs = self.find_orfs(options);
+        self.annotations
+            .extend(orfs.iter().map(|orf| orf.to_annotation()));
+        orfs
+    }
+}
+
+impl<B, C, S> GeneticSequence<B, C, S>
+where
+    B: Nucleotide + ToIupac + Copy,
+    C: Codon<B>,
+    S: SequenceStorage<B>,
+{
+    /// Record pending changes made through indexing and the annotation accessors.
+    fn flush_pending_edits(&mut self) {
+        let pending = self.history.as_mut().and_then(|h| h.take_pending_base());
+        let changed = pending.filter(|(pos, base)| !self.is_base_at(*pos, base));
+        if let Some(history) = &mut self.history {
+            if let Some((pos, base)) = changed {
+                history.record(Edit::Substitute { pos, base });
+            }
+            history.flush_annotations(&self.annotations);
+        }
+    }
+
+    /// Whether the nucleotide at `pos` is `base`.
+    fn is_base_at(&self, pos: usize, base: &B) -> bool {
+        self.sequence
+            .get(pos)
+            .is_some_and(|b| b.to_iupac() == base.to_iupac())
+    }
+
+    fn swap_edit(&mut self, edit: &mut Edit<B>) {
+        match edit {
+            Edit::Substitute { pos, base } => *base = self.sequence.replace(*pos, *base),
+            Edit::Annotations(annotations) => std::mem::swap(&mut self.annotations, annotations),
+            Edit::Topology(topology, annotations) => {
+                std::mem::swap(&mut self.topology, topology);
+                std::mem::swap(&mut self.annotations, annotations);
+            }
+            Edit::Splice { .. } | Edit::Rotate(_) => unreachable!(),
+        }
+    }
 }
 
-impl<B, C> ToString for GeneticSequence<B, C>
+impl<B, C> GeneticSequence<B, C>
 where
-    B: Nucleotide + ToLetter,
+    B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
     C: Codon<B>,
 {
-    fn to_string(&self) -> String {
-        self.sequence.iter().map(|b| b.to_letter()).collect()
+    /// The nucleotides of a genetic sequence as a slice.
+    ///
+    /// Only sequences with the default storage hold their nucleotides as a slice,
+    /// use `nucleotide_iter` for other storages.
+    ///
+    /// # Examples
+    /// ```
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// let nucleotides = seq.as_nucleotides();
+    ///
+    /// assert_eq!(nucleotides, [T, G, A, T, C, C])
+    /// ```
+    pub fn as_nucleotides(&self) -> &[B] {
+        &self.sequence
     }
 }
 
-impl<B, C> Index<usize> for GeneticSequence<B, C>
+impl<B, C, S> FromIterator<B> for GeneticSequence<B, C, S>
 where
-    B: Nucleotide,
+    B: Nucleotide + ToIupac + Copy,
+    C: Codon<B>,
+    S: SequenceStorage<B>,
+{
+    fn from_iter<I: IntoIterator<Item = B>>(iter: I) -> Self {
+        Self {
+            sequence: iter.into_iter().collect(),
+            annotations: Vec::new(),
+            topology: Topology::Linear,
+            history: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<B, C, S> std::fmt::Display for GeneticSequence<B, C, S>
+where
+    B: Nucleotide + ToLetter + ToIupac + Copy,
+    C: Codon<B>,
+    S: SequenceStorage<B>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s: String = self.sequence.iter().map(|b| b.to_letter()).collect();
+        write!(f, "{}", s)
+    }
+}
+
+impl<B, C, S> Index<usize> for GeneticSequence<B, C, S>
+where
+    B: Nucleotide + ToIupac + Copy,
     C: Codon<B>,
+    S: SequenceStorage<B>,
 {
     type Output = B;
 
     fn index(&self, index: usize) -> &Self::Output {
-        &self.sequence[index]
+        self.sequence.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.sequence.len(),
+                index
+            )
+        })
     }
 }
 
 impl<B, C> IndexMut<usize> for GeneticSequence<B, C>
 where
-    B: Nucleotide,
+    B: Nucleotide + ToIupac + Copy,
     C: Codon<B>,
 {
+    /// Changes through indexing are recorded in the edit history like `set_base`,
+    /// the next time the history is used.
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        if self.history.is_some() {
+            self.flush_pending_edits();
+            let base = self[index];
+            if let Some(history) = &mut self.history {
+                history.snapshot_base(index, base);
+            }
+        }
         &mut self.sequence[index]
     }
 }
 
-impl<B, C> NucleotideSequence for GeneticSequence<B, C>
+impl<B, C, S> NucleotideSequence for GeneticSequence<B, C, S>
 where
-    B: Nucleotide + ToIupac,
+    B: Nucleotide + ToIupac + Copy,
     C: Codon<B>,
+    S: SequenceStorage<B>,
 {
     fn matches<T>(&self, seq: &[T]) -> bool
     where
@@ -528,7 +1866,13 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::seq::{DnaSequence, RnaSequence};
+    use crate::eaa::{Eaa, GeneticCode};
+    use crate::seq::{
+        Annotation, DnaSequence, OrfOptions, ReadingFrame, RnaSequence, Strand, Topology,
+        TranslationOptions,
+    };
+    use crate::traits::ToLetter;
+    use crate::uni::{EnzymeDatabase, MethylationProfile, RestrictionEnzymes, RestrictionOptions};
 
     #[test]
     fn test_rna_sequence_from_str() {
@@ -559,9 +1903,574 @@ mod tests {
         seq.annotate_restriction_enzymes();
         assert_eq!(seq.annotations.len(), 1);
         let ann = seq.annotations.first().unwrap();
-        assert_eq!(ann.start, 3);
-        assert_eq!(ann.needle, Some(5));
+        assert_eq!(ann.start, 4);
+        assert_eq!(ann.needle, Some(6));
+        assert_eq!(ann.bottom_needle, Some(8));
         assert_eq!(ann.end, 9);
         assert_eq!(ann.text, "NdeI");
     }
+
+    #[test]
+    fn test_insert_bases() {
+        use crate::dna::DnaNucleotide::*;
+
+        let mut seq = DnaSequence::from_str("AAACCCGGG").unwrap();
+        seq.annotations.push(Annotation::new(3, 5, None, "C"));
+        seq.annotations.push(Annotation::new(6, 8, None, "G"));
+        seq.insert_bases(0, [T]);
+        seq.insert_bases(6, [T, T]);
+        seq.insert_bases(12, [T]);
+        assert_eq!(seq.to_string(), "TAAACCTTCGGGT");
+        assert_eq!(seq.annotations[0], Annotation::new(4, 8, None, "C"));
+        assert_eq!(seq.annotations[1], Annotation::new(9, 11, None, "G"));
+    }
+
+    #[test]
+    fn test_remove_range() {
+        let mut seq = DnaSequence::from_str("AAACCCGGGTTT").unwrap();
+        seq.annotations.push(Annotation::new(0, 2, None, "A"));
+        seq.annotations.push(Annotation::new(3, 5, None, "C"));
+        seq.annotations.push(Annotation::new(6, 8, None, "G"));
+        seq.annotations.push(Annotation::new(9, 11, None, "T"));
+        let removed = seq.remove_range(2..7);
+        assert_eq!(removed.len(), 5);
+        assert_eq!(seq.to_string(), "AAGGTTT");
+        assert_eq!(
+            seq.annotations,
+            [
+                Annotation::new(0, 1, None, "A"),
+                Annotation::new(2, 3, None, "G"),
+                Annotation::new(4, 6, None, "T"),
+            ]
+        );
+        seq.remove_range(..);
+        assert!(seq.annotations.is_empty());
+    }
+
+    #[test]
+    fn test_replace_range() {
+        use crate::dna::DnaNucleotide::*;
+
+        let mut seq = DnaSequence::from_str("AAACCCGGG").unwrap();
+        seq.annotations.push(Annotation::new(0, 8, Some(7), "all"));
+        seq.annotations.push(Annotation::new(3, 5, None, "C"));
+        let replaced = seq.replace_range(3..=5, [T]);
+        assert_eq!(replaced, [C, C, C]);
+        assert_eq!(seq.to_string(), "AAATGGG");
+        assert_eq!(
+            seq.annotations,
+            [
+                Annotation::new(0, 2, None, "all"),
+                Annotation::new(4, 6, Some(5), "all"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pop_base_shrinks_annotations() {
+        let mut seq = DnaSequence::from_str("ATGC").unwrap();
+        seq.annotations.push(Annotation::new(2, 3, None, "GC"));
+        seq.annotations.push(Annotation::new(3, 3, None, "C"));
+        seq.pop_base();
+        assert_eq!(seq.annotations, [Annotation::new(2, 2, None, "GC")]);
+    }
+
+    #[test]
+    fn test_circular_annotate_restriction_enzymes() {
+        let mut seq = DnaSequence::from_str("TATGAAAAAAAACA").unwrap();
+        seq.annotate_restriction_enzymes();
+        assert!(seq.annotations.iter().all(|ann| ann.text != "NdeI"));
+
+        seq.set_topology(Topology::Circular);
+        seq.annotate_restriction_enzymes();
+        let ann = seq
+            .annotations
+            .iter()
+            .find(|ann| ann.text == "NdeI")
+            .unwrap();
+        assert_eq!(ann.start, 12);
+        assert_eq!(ann.needle, Some(0));
+        assert_eq!(ann.bottom_needle, Some(2));
+        assert_eq!(ann.end, 3);
+    }
+
+    #[test]
+    fn test_annotate_outside_cutters() {
+        let mut seq = DnaSequence::from_str("AAGGTCTCAGTCCAAAA").unwrap();
+        seq.annotate_restriction_enzymes();
+        let ann = seq
+            .annotations
+            .iter()
+            .find(|ann| ann.text == "BsaI_HFv2")
+            .unwrap();
+        assert_eq!((ann.start, ann.end), (2, 12));
+        assert_eq!((ann.needle, ann.bottom_needle), (Some(9), Some(13)));
+
+        // The cut lies beyond the end of a linear sequence
+        let mut seq = DnaSequence::from_str("TTTTGGTCTCAG").unwrap();
+        seq.annotate_restriction_enzymes();
+        let ann = seq
+            .annotations
+            .iter()
+            .find(|ann| ann.text == "BsaI_HFv2")
+            .unwrap();
+        assert_eq!((ann.start, ann.end), (4, 11));
+        assert_eq!((ann.needle, ann.bottom_needle), (Some(11), None));
+
+        seq.set_topology(Topology::Circular);
+        seq.as_mut_annotations().clear();
+        seq.annotate_restriction_enzymes();
+        let ann = seq
+            .annotations
+            .iter()
+            .find(|ann| ann.text == "BsaI_HFv2")
+            .unwrap();
+        assert_eq!((ann.start, ann.end), (4, 2));
+        assert_eq!((ann.needle, ann.bottom_needle), (Some(11), Some(3)));
+    }
+
+    #[test]
+    fn test_annotate_reverse_strand_sites() {
+        let mut seq = DnaSequence::from_str("AAAAAGAGACCAAAA").unwrap();
+        seq.annotate_restriction_enzymes();
+        let ann = seq
+            .annotations
+            .iter()
+            .find(|ann| ann.text == "BsaI_HFv2")
+            .unwrap();
+        assert_eq!(ann.strand, Some(Strand::Reverse));
+        assert_eq!((ann.start, ann.end), (0, 10));
+        assert_eq!((ann.needle, ann.bottom_needle), (Some(0), Some(4)));
+
+        // Reverse complementing moves the site to the forward strand
+        let mut rc = seq.reverse_complement();
+        rc.as_mut_annotations().clear();
+        rc.annotate_restriction_enzymes();
+        let forward = rc
+            .annotations
+            .iter()
+            .find(|ann| ann.text == "BsaI_HFv2")
+            .unwrap();
+        assert_eq!(forward, &ann.reverse(seq.len()));
+
+        // Nicking enzymes nick the top strand at sites on the reverse strand
+        let mut seq = DnaSequence::from_str("AAGCATTCAA").unwrap();
+        seq.annotate_restriction_enzymes();
+        let ann = seq
+            .annotations
+            .iter()
+            .find(|ann| ann.text == "NbBsmI")
+            .unwrap();
+        assert_eq!(ann.strand, Some(Strand::Reverse));
+        assert_eq!((ann.needle, ann.bottom_needle), (Some(3), None));
+
+        // Palindromic sites are found once and unstranded
+        let mut seq = DnaSequence::from_str("AAGAATTCAA").unwrap();
+        seq.annotate_restriction_enzymes();
+        let sites = seq
+            .annotations
+            .iter()
+            .filter(|ann| ann.text == "EcoRI")
+            .collect::<Vec<_>>();
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].strand, None);
+    }
+
+    #[test]
+    fn test_annotate_restriction_enzymes_methylated() {
+        let database = EnzymeDatabase::builtin().filter(|e| e.name == "XbaI");
+        let mut seq = DnaSequence::from_str("TCTAGATCAATCTAGAA").unwrap();
+        let options = RestrictionOptions::default().with_methylation(MethylationProfile::ecoli());
+        seq.annotate_restriction_enzymes_with(&database, &options);
+        let names = seq
+            .annotation_iter()
+            .map(|ann| ann.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["XbaI (blocked by Dam)", "XbaI"]);
+
+        seq.as_mut_annotations().clear();
+        seq.annotate_restriction_enzymes_with(&database, &options.with_exclude_blocked(true));
+        assert_eq!(seq.as_annotations().len(), 1);
+        assert_eq!(seq.as_annotations()[0].start, 10);
+    }
+
+    /// The single-pass scanner finds the same sites as searching for each enzyme
+    /// separately, see `benches/restriction_scan.rs` for the timing comparison.
+    #[test]
+    fn test_annotate_restriction_enzymes_matches_naive_search() {
+        let mut state = 42u64;
+        let seq = (0..2_000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ['A', 'C', 'G', 'T'][(state >> 33) as usize % 4]
+            })
+            .collect::<String>();
+        let mut seq = DnaSequence::from_str(seq).unwrap();
+        seq.set_topology(Topology::Circular);
+
+        let naive = RestrictionEnzymes
+            .iter()
+            .map(|enzyme| {
+                let site = enzyme.recognition_site();
+                let mut found = seq.find_pattern(&site).len();
+                if !enzyme.is_palindromic() {
+                    found += seq.find_pattern(&enzyme.reverse_complement_site()).len();
+                }
+                found * enzyme.cuts().count()
+            })
+            .sum::<usize>();
+        seq.annotate_restriction_enzymes();
+        assert_eq!(seq.annotations.len(), naive);
+    }
+
+    #[test]
+    fn test_annotate_enzymes_cutting_both_sides() {
+        let seq = format!("{}ACAAAAGTACC{}", "T".repeat(20), "T".repeat(20));
+        let mut seq = DnaSequence::from_str(seq).unwrap();
+        seq.annotate_restriction_enzymes();
+        let cuts = seq
+            .annotations
+            .iter()
+            .filter(|ann| ann.text == "BaeI")
+            .map(|ann| (ann.start, ann.end, ann.needle, ann.bottom_needle))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cuts,
+            [(5, 30, Some(10), Some(5)), (20, 42, Some(43), Some(38))]
+        );
+    }
+
+    #[test]
+    fn test_circular_codon_iter() {
+        use crate::dna::DnaNucleotide::*;
+
+        let mut seq = DnaSequence::from_str("ATGTA").unwrap();
+        assert_eq!(seq.as_codons(), [[A, T, G].into()]);
+        seq.set_topology(Topology::Circular);
+        assert_eq!(seq.as_codons(), [[A, T, G].into(), [T, A, A].into()]);
+
+        let mut seq = DnaSequence::from_str("AT").unwrap();
+        seq.set_topology(Topology::Circular);
+        assert!(seq.as_codons().is_empty());
+        assert_eq!(seq.codon_iter_frame(ReadingFrame::Minus1).count(), 0);
+    }
+
+    #[test]
+    fn test_circular_edit_wrapping_annotation() {
+        use crate::dna::DnaNucleotide::*;
+
+        let mut seq = DnaSequence::from_str("AAACCCGGG").unwrap();
+        seq.set_topology(Topology::Circular);
+        seq.annotations.push(Annotation::new(7, 1, None, "origin"));
+        seq.push_base(T);
+        assert_eq!(seq.annotations, [Annotation::new(7, 1, None, "origin")]);
+        seq.remove_range(0..1);
+        assert_eq!(seq.annotations, [Annotation::new(6, 0, None, "origin")]);
+        seq.rotate_to(6).unwrap();
+        assert_eq!(seq.to_string(), "GGTAACCCG");
+        assert_eq!(seq.annotations, [Annotation::new(0, 3, None, "origin")]);
+    }
+
+    #[test]
+    fn test_rotate_linear_sequence() {
+        let mut seq = DnaSequence::from_str("AAACCCGGG").unwrap();
+        assert!(seq.rotate_to(3).is_err());
+    }
+
+    #[test]
+    fn test_history_restores_annotations() {
+        let mut seq = DnaSequence::from_str("AAACCCGGG").unwrap();
+        seq.annotations.push(Annotation::new(3, 5, None, "C"));
+        seq.annotations.push(Annotation::new(2, 6, Some(4), "ACG"));
+        seq.enable_history(10);
+        seq.remove_range(3..6);
+        seq.pop_base();
+        assert_eq!(seq.annotations, [Annotation::new(2, 3, None, "ACG")]);
+
+        assert!(seq.undo());
+        assert!(seq.undo());
+        assert!(!seq.undo());
+        assert_eq!(seq.to_string(), "AAACCCGGG");
+        assert_eq!(seq.annotations.len(), 2);
+        assert_eq!(seq.annotations[1], Annotation::new(2, 6, Some(4), "ACG"));
+
+        assert!(seq.redo());
+        assert!(seq.redo());
+        assert!(!seq.redo());
+        assert_eq!(seq.to_string(), "AAAGG");
+        assert_eq!(seq.annotations, [Annotation::new(2, 3, None, "ACG")]);
+    }
+
+    #[test]
+    fn test_history_records_annotation_changes() {
+        let mut seq = DnaSequence::from_str("AAACCCGGG").unwrap();
+        seq.enable_history(10);
+        seq.as_mut_annotations();
+        assert!(!seq.can_undo());
+        seq.as_mut_annotations()
+            .push(Annotation::new(0, 2, None, "A"));
+        assert!(seq.can_undo());
+        seq.as_mut_annotations()[0].text = "AAA".to_string();
+        seq.undo();
+        assert_eq!(seq.annotations, [Annotation::new(0, 2, None, "A")]);
+        seq.undo();
+        assert!(seq.annotations.is_empty());
+        assert!(!seq.can_undo());
+        seq.redo();
+        seq.redo();
+        assert_eq!(seq.annotations, [Annotation::new(0, 2, None, "AAA")]);
+
+        // Read access doesn't copy the annotations into the history
+        assert!(seq.annotations_mut().iter().all(|ann| ann.text == "AAA"));
+        assert!(seq.history().unwrap().pending_annotations().is_none());
+        // All changes within a transaction share one copy
+        seq.transaction(|seq| {
+            for text in ["B", "C"] {
+                seq.annotations_mut()
+                    .push(Annotation::new(3, 5, None, text));
+            }
+        });
+        assert_eq!(seq.annotations.len(), 3);
+        seq.undo();
+        assert_eq!(seq.annotations, [Annotation::new(0, 2, None, "AAA")]);
+    }
+
+    #[test]
+    fn test_history_substitutions() {
+        use crate::dna::DnaNucleotide::*;
+
+        let mut seq = DnaSequence::from_str("AAA").unwrap();
+        seq.enable_history(10);
+        seq[0] = A;
+        assert!(!seq.can_undo());
+        seq[1] = C;
+        assert!(seq.can_undo());
+        assert_eq!(seq.set_base(2, A), A);
+        assert_eq!(seq.set_base(2, G), A);
+        seq.undo();
+        assert_eq!(seq.to_string(), "ACA");
+        seq.undo();
+        assert_eq!(seq.to_string(), "AAA");
+        assert!(!seq.undo());
+        seq.redo();
+        seq.redo();
+        assert_eq!(seq.to_string(), "ACG");
+
+        // Unchanged writes through indexing aren't recorded
+        seq[2] = G;
+        seq.set_base(0, T);
+        seq.undo();
+        assert_eq!(seq.to_string(), "ACG");
+        seq.undo();
+        assert_eq!(seq.to_string(), "ACA");
+    }
+
+    #[test]
+    fn test_history_topology_and_rotation() {
+        let mut seq = DnaSequence::from_str("AAACCCGGG").unwrap();
+        seq.enable_history(10);
+        seq.set_topology(Topology::Circular);
+        seq.as_mut_annotations()
+            .push(Annotation::new(0, 2, None, "A"));
+        seq.rotate_to(2).unwrap();
+        seq.set_topology(Topology::Linear);
+        assert_eq!(seq.annotations.len(), 2);
+
+        seq.undo();
+        assert!(seq.is_circular());
+        assert_eq!(seq.annotations, [Annotation::new(7, 0, None, "A")]);
+        seq.undo();
+        assert_eq!(seq.to_string(), "AAACCCGGG");
+        assert_eq!(seq.annotations, [Annotation::new(0, 2, None, "A")]);
+        seq.undo();
+        seq.undo();
+        assert!(!seq.is_circular());
+    }
+
+    #[test]
+    fn test_history_transactions_and_limit() {
+        use crate::dna::DnaNucleotide::*;
+
+        let mut seq = DnaSequence::from_str("AAA").unwrap();
+        seq.enable_history(2);
+        seq.transaction(|seq| {
+            seq.push_base(C);
+            seq.set_base(0, G);
+        });
+        seq.push_base(C);
+        seq.push_base(C);
+        assert_eq!(seq.history().unwrap().undo_len(), 2);
+        while seq.undo() {}
+        assert_eq!(seq.to_string(), "GAAC");
+
+        seq.redo();
+        seq.push_base(T);
+        assert!(!seq.can_redo());
+        assert_eq!(seq.to_string(), "GAACCT");
+    }
+
+    #[test]
+    fn test_reverse_complement_circular() {
+        let mut seq = DnaSequence::from_str("AACCGGTA").unwrap();
+        seq.set_topology(Topology::Circular);
+        seq.as_mut_annotations().extend([
+            Annotation::new(6, 1, Some(0), "ori").with_bottom_needle(1),
+            Annotation::new(1, 3, Some(2), "foo").with_bottom_needle(3),
+        ]);
+        let rc = seq.reverse_complement();
+        assert!(rc.is_circular());
+        assert_eq!(rc.to_string(), "TACCGGTT");
+        assert_eq!(
+            rc.annotations,
+            [
+                Annotation::new(6, 1, Some(7), "ori").with_bottom_needle(0),
+                Annotation::new(4, 6, Some(5), "foo").with_bottom_needle(6),
+            ]
+        );
+        assert_eq!(rc.reverse_complement().annotations, seq.annotations);
+    }
+
+    #[test]
+    fn test_codon_iter_frames() {
+        let seq = DnaSequence::from_str("ATGCCGTAAC").unwrap();
+        let codons = |frame| {
+            seq.codon_iter_frame(frame)
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codons(ReadingFrame::Plus1), ["ATG", "CCG", "TAA"]);
+        assert_eq!(codons(ReadingFrame::Plus2), ["TGC", "CGT", "AAC"]);
+        assert_eq!(codons(ReadingFrame::Plus3), ["GCC", "GTA"]);
+        assert_eq!(codons(ReadingFrame::Minus1), ["GTT", "ACG", "GCA"]);
+        assert_eq!(codons(ReadingFrame::Minus2), ["TTA", "CGG", "CAT"]);
+        assert_eq!(codons(ReadingFrame::Minus3), ["TAC", "GGC"]);
+    }
+
+    #[test]
+    fn test_translate_iter_reverse_frame() {
+        // Reverse complement: TTACATGGAGCC, frame -2 reads Y M E
+        let seq = DnaSequence::from_str("GGCTCCATGTAA").unwrap();
+        let protein = seq
+            .translate_iter(ReadingFrame::Minus2, TranslationOptions::protein())
+            .map(|aa| (aa.to_letter(), aa.start, aa.end))
+            .collect::<Vec<_>>();
+        assert_eq!(protein, [('M', 5, 7), ('E', 2, 4)]);
+
+        let all = seq
+            .translate_iter(ReadingFrame::Minus1, TranslationOptions::default())
+            .map(|aa| aa.to_letter())
+            .collect::<String>();
+        assert_eq!(all, "LHGA");
+    }
+
+    #[test]
+    fn test_translate_iter_circular() {
+        // AUG is split by the origin
+        let mut seq = RnaSequence::from_str("UGGCGUAAA").unwrap();
+        seq.set_topology(Topology::Circular);
+        let protein = seq
+            .translate_iter(ReadingFrame::Plus3, TranslationOptions::protein())
+            .map(|aa| (aa.to_letter(), aa.start, aa.end))
+            .collect::<Vec<_>>();
+        assert_eq!(protein, [('M', 8, 1)]);
+        let protein = seq
+            .translate_iter(ReadingFrame::Plus1, TranslationOptions::default())
+            .map(|aa| (aa.to_letter(), aa.start, aa.end))
+            .collect::<Vec<_>>();
+        assert_eq!(protein, [('W', 0, 2), ('R', 3, 5), ('K', 6, 8)]);
+        seq.push_base_str("AU").unwrap();
+        let last = seq
+            .translate_iter(ReadingFrame::Plus1, TranslationOptions::default())
+            .last()
+            .unwrap();
+        assert_eq!((last.to_letter(), last.start, last.end), ('I', 9, 0));
+    }
+
+    #[test]
+    fn test_find_orfs_nested() {
+        let seq = DnaSequence::from_str("ATGAAAATGCCCTAA").unwrap();
+        let options = OrfOptions::default().with_min_codons(1);
+        let proteins = |options: &OrfOptions| {
+            seq.find_orfs(options)
+                .iter()
+                .map(|orf| orf.protein_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(proteins(&options), ["MKMP"]);
+        assert_eq!(proteins(&options.clone().with_nested(true)), ["MKMP", "MP"]);
+        assert!(proteins(&options.with_min_codons(5)).is_empty());
+    }
+
+    #[test]
+    fn test_find_orfs_circular() {
+        let mut seq = DnaSequence::from_str("CCCTAAGGATGAAA").unwrap();
+        let options = OrfOptions::default().with_min_codons(0);
+        assert!(seq.find_orfs(&options).is_empty());
+
+        seq.set_topology(Topology::Circular);
+        let orfs = seq.find_orfs(&options);
+        assert_eq!(orfs.len(), 1);
+        assert_eq!(orfs[0].protein_string(), "MKP");
+        assert_eq!((orfs[0].start, orfs[0].end), (8, 5));
+        assert_eq!(orfs[0].frame, ReadingFrame::Plus3);
+    }
+
+    #[test]
+    fn test_annotate_orfs_history() {
+        let mut seq = DnaSequence::from_str("ATGAAATAA").unwrap();
+        seq.enable_history(10);
+        let orfs = seq.annotate_orfs(&OrfOptions::default().with_min_codons(1));
+        assert_eq!(orfs.len(), 1);
+        assert_eq!(
+            seq.annotations,
+            [Annotation::new(0, 8, None, "ORF +1 (2 aa)").with_strand(Strand::Forward)]
+        );
+        seq.undo();
+        assert!(seq.annotations.is_empty());
+    }
+
+    #[test]
+    fn test_translate_with_genetic_code() {
+        let seq = DnaSequence::from_str("ATAAGATGA").unwrap();
+        let code = GeneticCode::from_id(2).unwrap();
+        assert_eq!(seq.translate(), [Eaa::Ile, Eaa::Arg, Eaa::Ter]);
+        assert_eq!(seq.translate_with(code), [Eaa::Met, Eaa::Ter, Eaa::Trp]);
+
+        let options = TranslationOptions::protein().with_genetic_code(code);
+        let protein = seq
+            .translate_iter(ReadingFrame::Plus1, options)
+            .map(|aa| aa.eaa.eaa)
+            .collect::<Vec<_>>();
+        assert_eq!(protein, [Eaa::Met]);
+
+        // GTG starts translation in the bacterial code, as methionine
+        let seq = DnaSequence::from_str("CCGTGCCCTAA").unwrap();
+        let translate = |options: TranslationOptions| {
+            seq.translate_iter(ReadingFrame::Plus3, options)
+                .map(|aa| aa.eaa.eaa)
+                .collect::<Vec<_>>()
+        };
+        assert!(translate(TranslationOptions::protein()).is_empty());
+        let code = GeneticCode::from_id(11).unwrap();
+        assert_eq!(
+            translate(TranslationOptions::protein().with_genetic_code(code)),
+            [Eaa::Met, Eaa::Pro]
+        );
+    }
+
+    #[test]
+    fn test_find_orfs_genetic_code() {
+        // GTG starts and TGA is read through in Mycoplasma
+        let seq = DnaSequence::from_str("GTGTGAAAATAA").unwrap();
+        let options = OrfOptions::default().with_min_codons(1);
+        assert!(seq.find_orfs(&options).is_empty());
+
+        let options = options.with_genetic_code(GeneticCode::from_id(4).unwrap());
+        let orfs = seq.find_orfs(&options);
+        assert_eq!(orfs.len(), 1);
+        assert_eq!(orfs[0].protein_string(), "MWK");
+        assert_eq!((orfs[0].start, orfs[0].end), (0, 11));
+    }
 }
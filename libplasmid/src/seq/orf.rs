@@ -0,0 +1,112 @@
+use crate::{
+    eaa::{Eaa, GeneticCode},
+    rna::{RnaCodon, RnaNucleotide},
+    traits::*,
+};
+
+use super::{Annotation, ReadingFrame, Strand};
+
+/// Options for open reading frame detection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrfOptions {
+    /// Codons an open reading frame may start with
+    pub start_codons: Vec<RnaCodon>,
+    /// Minimum number of codons of an open reading frame, not counting the stop codon
+    pub min_codons: usize,
+    /// Also report open reading frames that start at a later start codon
+    /// of a longer open reading frame in the same frame.
+    pub nested: bool,
+    /// The genetic code used to find stop codons and translate the frames
+    pub genetic_code: GeneticCode,
+}
+
+impl OrfOptions {
+    /// Use the given start codons instead of just `AUG`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    /// use plasmid::traits::TryFromStr;
+    ///
+    /// let options = OrfOptions::default().with_start_codons(
+    ///     ["ATG", "GTG", "TTG"].map(|s| DnaCodon::try_from_str(s).unwrap()),
+    /// );
+    /// assert_eq!(options.start_codons.len(), 3);
+    /// ```
+    pub fn with_start_codons<I, T>(mut self, codons: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<RnaCodon>,
+    {
+        self.start_codons = codons.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the minimum number of codons, not counting the stop codon.
+    pub fn with_min_codons(mut self, min_codons: usize) -> Self {
+        self.min_codons = min_codons;
+        self
+    }
+
+    /// Use an alternative genetic code, along with all of its start codons.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let options = OrfOptions::default().with_genetic_code(GeneticCode::from_id(11).unwrap());
+    /// assert_eq!(options.start_codons.len(), 7);
+    /// ```
+    pub fn with_genetic_code(mut self, genetic_code: &GeneticCode) -> Self {
+        self.genetic_code = *genetic_code;
+        self.start_codons = genetic_code.start_codons();
+        self
+    }
+
+    /// Report nested open reading frames as well, instead of only the longest one.
+    pub fn with_nested(mut self, nested: bool) -> Self {
+        self.nested = nested;
+        self
+    }
+}
+
+impl Default for OrfOptions {
+    /// Longest open reading frames starting with `AUG` that encode at least 30 amino acids.
+    fn default() -> Self {
+        use RnaNucleotide::*;
+        Self {
+            start_codons: vec![RnaCodon::from_triplet_arr([A, U, G])],
+            min_codons: 30,
+            nested: false,
+            genetic_code: GeneticCode::default(),
+        }
+    }
+}
+
+/// An open reading frame of a genetic sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Orf {
+    pub strand: Strand,
+    pub frame: ReadingFrame,
+    /// The first top strand position covered by the open reading frame
+    pub start: usize,
+    /// The last top strand position covered by the open reading frame, including
+    /// the stop codon. Smaller than `start` if the frame wraps around the origin.
+    pub end: usize,
+    /// The translated protein, without the stop codon.
+    /// Alternative start codons are translated as methionine.
+    pub protein: Vec<Eaa>,
+}
+
+impl Orf {
+    /// The translated protein in one-letter code.
+    pub fn protein_string(&self) -> String {
+        self.protein.iter().map(|eaa| eaa.to_letter()).collect()
+    }
+
+    /// Create an annotation covering the open reading frame.
+    pub fn to_annotation(&self) -> Annotation {
+        let text = format!("ORF {} ({} aa)", self.frame, self.protein.len());
+        Annotation::new(self.start, self.end, None, text).with_strand(self.strand)
+    }
+}
@@ -0,0 +1,647 @@
+use std::{borrow::Cow, marker::PhantomData, ops::Range};
+
+use crate::{
+    dna::{DnaCodon, DnaNucleotide},
+    traits::*,
+    uni::{IupacCodon, IupacNucleotide},
+};
+
+use super::{GeneticSequence, SequenceStorage, Topology};
+
+/// DNA sequence packed into two bits per nucleotide.
+///
+/// A compact alternative to `DnaSequence` for genome-sized inputs, with the same API.
+/// Counting, complementing and pattern search work on whole 64 bit words,
+/// i.e. 32 nucleotides at once.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::{*, IupacNucleotide::*};
+///
+/// let mut seq = PackedDnaSequence::from_str("ATGTTCCATATGTCTCGT").unwrap();
+/// assert_eq!(seq.gc_count(), 7);
+/// assert_eq!(seq.find_pattern(&[C, A, T, A, T, G]), [6]);
+/// assert_eq!(seq.reverse_complement().to_string(), "ACGAGACATATGGAACAT");
+///
+/// seq.annotate_restriction_enzymes();
+/// assert_eq!(seq.as_annotations()[0].text, "NdeI");
+/// assert_eq!(seq.translate()[0], Eaa::Met);
+/// ```
+pub type PackedDnaSequence = GeneticSequence<DnaNucleotide, DnaCodon, PackedStorage<DnaNucleotide>>;
+
+/// IUPAC sequence packed into four bits per nucleotide.
+///
+/// Each nucleotide is stored as the set of bases it stands for, so that ambiguous
+/// sequences can be counted, complemented and searched word-at-a-time like
+/// `PackedDnaSequence`.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::{*, IupacNucleotide::*};
+///
+/// let seq = PackedIupacSequence::from_str("ATGNNCATRTG").unwrap();
+/// assert_eq!(seq.find_pattern(&[C, A, T, A]), [5]);
+/// assert_eq!(seq.reverse_complement().to_string(), "CAYATGNNCAT");
+/// ```
+pub type PackedIupacSequence =
+    GeneticSequence<IupacNucleotide, IupacCodon, PackedStorage<IupacNucleotide>>;
+
+/// A nucleotide which can be stored in a `PackedStorage`.
+pub trait PackedNucleotide: Nucleotide + ToIupac + Copy + 'static {
+    /// The number of bits per nucleotide, a divisor of 64.
+    const BITS: usize;
+
+    /// The nucleotides by their code.
+    const CODES: &'static [Self];
+
+    /// The code of the nucleotide, an index into `CODES`.
+    fn encode(self) -> u64;
+
+    /// Complement all nucleotides of a word.
+    fn complement_word(word: u64) -> u64;
+
+    /// Reverse the order of the nucleotides of a word.
+    fn reverse_word(word: u64) -> u64;
+}
+
+impl PackedNucleotide for DnaNucleotide {
+    const BITS: usize = 2;
+    const CODES: &'static [Self] = &[
+        DnaNucleotide::A,
+        DnaNucleotide::C,
+        DnaNucleotide::G,
+        DnaNucleotide::T,
+    ];
+
+    fn encode(self) -> u64 {
+        match self {
+            DnaNucleotide::A => 0b00,
+            DnaNucleotide::C => 0b01,
+            DnaNucleotide::G => 0b10,
+            DnaNucleotide::T => 0b11,
+        }
+    }
+
+    fn complement_word(word: u64) -> u64 {
+        !word
+    }
+
+    fn reverse_word(word: u64) -> u64 {
+        let w = ((word >> 2) & 0x3333_3333_3333_3333) | ((word & 0x3333_3333_3333_3333) << 2);
+        let w = ((w >> 4) & 0x0f0f_0f0f_0f0f_0f0f) | ((w & 0x0f0f_0f0f_0f0f_0f0f) << 4);
+        w.swap_bytes()
+    }
+}
+
+/// Each nucleotide is coded as the set of bases it stands for, one bit per base (A, C, G, T).
+impl PackedNucleotide for IupacNucleotide {
+    const BITS: usize = 4;
+    const CODES: &'static [Self] = {
+        use IupacNucleotide::*;
+        &[Gap, A, C, M, G, R, S, V, T, W, Y, H, K, D, B, N]
+    };
+
+    fn encode(self) -> u64 {
+        use IupacNucleotide::*;
+        match self {
+            Gap => 0b0000,
+            A => 0b0001,
+            C => 0b0010,
+            M => 0b0011,
+            G => 0b0100,
+            R => 0b0101,
+            S => 0b0110,
+            V => 0b0111,
+            T => 0b1000,
+            W => 0b1001,
+            Y => 0b1010,
+            H => 0b1011,
+            K => 0b1100,
+            D => 0b1101,
+            B => 0b1110,
+            N => 0b1111,
+        }
+    }
+
+    fn complement_word(word: u64) -> u64 {
+        // Swap the A and T bits, and the C and G bits of every nucleotide
+        ((word & 0x1111_1111_1111_1111) << 3)
+            | ((word & 0x8888_8888_8888_8888) >> 3)
+            | ((word & 0x2222_2222_2222_2222) << 1)
+            | ((word & 0x4444_4444_4444_4444) >> 1)
+    }
+
+    fn reverse_word(word: u64) -> u64 {
+        let w = ((word >> 4) & 0x0f0f_0f0f_0f0f_0f0f) | ((word & 0x0f0f_0f0f_0f0f_0f0f) << 4);
+        w.swap_bytes()
+    }
+}
+
+/// Nucleotides packed into 64 bit words, the first nucleotide in the least
+/// significant bits. Unused bits of the last word are zero.
+///
+/// Storage backend of `PackedDnaSequence` and `PackedIupacSequence`, see `SequenceStorage`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedStorage<B> {
+    words: Vec<u64>,
+    len: usize,
+    phantom: PhantomData<B>,
+}
+
+impl<B> Default for PackedStorage<B> {
+    fn default() -> Self {
+        Self {
+            words: Vec::new(),
+            len: 0,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<B> PackedStorage<B>
+where
+    B: PackedNucleotide,
+{
+    const SYMBOLS_PER_WORD: usize = 64 / B::BITS;
+    const SYMBOL_MASK: u64 = (1 << B::BITS) - 1;
+
+    /// A word with the lowest bit of every symbol set.
+    const LOW_BITS: u64 = u64::MAX / Self::SYMBOL_MASK;
+
+    fn push(&mut self, base: B) {
+        let (word, shift) = Self::locate(self.len);
+        if word == self.words.len() {
+            self.words.push(0);
+        }
+        self.words[word] |= base.encode() << shift;
+        self.len += 1;
+    }
+
+    fn code(&self, index: usize) -> u64 {
+        let (word, shift) = Self::locate(index);
+        (self.words[word] >> shift) & Self::SYMBOL_MASK
+    }
+
+    /// Keep the first `len` nucleotides.
+    fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.words.truncate(len.div_ceil(Self::SYMBOLS_PER_WORD));
+            if let Some(last) = self.words.len().checked_sub(1) {
+                self.words[last] &= self.valid_mask(last);
+            }
+        }
+    }
+
+    fn locate(index: usize) -> (usize, usize) {
+        (
+            index / Self::SYMBOLS_PER_WORD,
+            index % Self::SYMBOLS_PER_WORD * B::BITS,
+        )
+    }
+
+    /// Mask of the bits of word `word` that belong to symbols of the sequence.
+    fn valid_mask(&self, word: usize) -> u64 {
+        let used = (self.len - word * Self::SYMBOLS_PER_WORD).min(Self::SYMBOLS_PER_WORD) * B::BITS;
+        if used == 64 {
+            u64::MAX
+        } else {
+            (1 << used) - 1
+        }
+    }
+
+    /// Apply `f` to every word, keeping the unused bits zero.
+    fn map_words<F>(&self, f: F) -> Self
+    where
+        F: Fn(u64) -> u64,
+    {
+        Self {
+            words: (0..self.words.len())
+                .map(|i| f(self.words[i]) & self.valid_mask(i))
+                .collect(),
+            len: self.len,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Reverse the order of the symbols.
+    fn reversed(&self) -> Self {
+        let reversed = self
+            .words
+            .iter()
+            .rev()
+            .map(|&w| B::reverse_word(w))
+            .collect::<Vec<_>>();
+        // The unused bits of the last word are now at the start, shift them out
+        let padding = (self.words.len() * Self::SYMBOLS_PER_WORD - self.len) * B::BITS;
+        Self {
+            words: (0..reversed.len())
+                .map(|i| Self::shifted_word(&reversed, i, padding))
+                .collect(),
+            len: self.len,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Bitset with the lowest bit of every symbol equal to `code` set.
+    fn eq_mask(&self, code: u64) -> Vec<u64> {
+        let pattern = code * Self::LOW_BITS;
+        (0..self.words.len())
+            .map(|i| {
+                let diff = self.words[i] ^ pattern;
+                // Fold all bits of a symbol into its lowest bit
+                let folded = (1..B::BITS).fold(diff, |folded, shift| folded | diff >> shift);
+                !folded & Self::LOW_BITS & self.valid_mask(i)
+            })
+            .collect()
+    }
+
+    /// Word `index` of the bitset `words` shifted towards the start by `shift` bits.
+    fn shifted_word(words: &[u64], index: usize, shift: usize) -> u64 {
+        let word = |i: usize| words.get(i).copied().unwrap_or(0);
+        let (offset, bits) = (index + shift / 64, shift % 64);
+        if bits == 0 {
+            word(offset)
+        } else {
+            (word(offset) >> bits) | (word(offset + 1) << (64 - bits))
+        }
+    }
+
+    /// The codes of the nucleotides matched by `pattern`.
+    fn matching_codes(pattern: IupacNucleotide) -> Vec<u64> {
+        (0..B::CODES.len() as u64)
+            .filter(|&code| pattern.matches(&B::CODES[code as usize].to_iupac()))
+            .collect()
+    }
+}
+
+impl<B> SequenceStorage<B> for PackedStorage<B>
+where
+    B: PackedNucleotide,
+{
+    type Iter<'a> = PackedIter<'a, B>;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> Option<&B> {
+        (index < self.len).then(|| &B::CODES[self.code(index) as usize])
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        PackedIter {
+            storage: self,
+            range: 0..self.len,
+        }
+    }
+
+    fn replace(&mut self, index: usize, base: B) -> B {
+        assert!(
+            index < self.len,
+            "index out of bounds: the len is {} but the index is {}",
+            self.len,
+            index
+        );
+        let previous = B::CODES[self.code(index) as usize];
+        let (word, shift) = Self::locate(index);
+        self.words[word] &= !(Self::SYMBOL_MASK << shift);
+        self.words[word] |= base.encode() << shift;
+        previous
+    }
+
+    fn splice(&mut self, range: Range<usize>, bases: &[B]) -> Vec<B> {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "range {:?} out of bounds for sequence of length {}",
+            range,
+            self.len
+        );
+        let removed = range.clone().map(|i| self[i]).collect::<Vec<_>>();
+        let tail = (range.end..self.len).map(|i| self[i]).collect::<Vec<_>>();
+        self.truncate(range.start);
+        bases.iter().chain(&tail).for_each(|&b| self.push(b));
+        removed
+    }
+
+    fn rotate_left(&mut self, mid: usize) {
+        *self = self
+            .iter()
+            .skip(mid)
+            .chain(self.iter().take(mid))
+            .copied()
+            .collect();
+    }
+
+    fn to_slice(&self) -> Cow<'_, [B]> {
+        Cow::Owned(self.iter().copied().collect())
+    }
+
+    fn complement(&self) -> Self {
+        self.map_words(B::complement_word)
+    }
+
+    fn reverse_complement(&self) -> Self {
+        self.reversed().complement()
+    }
+
+    fn count(&self, bases: &[IupacNucleotide]) -> usize {
+        (0..B::CODES.len() as u64)
+            .filter(|&code| bases.contains(&B::CODES[code as usize].to_iupac()))
+            .flat_map(|code| self.eq_mask(code))
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    /// Find all positions at which every symbol of a pattern matches,
+    /// comparing whole words at once.
+    fn find_pattern(&self, pattern: &[IupacNucleotide], topology: Topology) -> Vec<usize> {
+        let pattern_len = pattern.len();
+        if pattern_len == 0 || pattern_len > self.len {
+            return Vec::new();
+        }
+        // Bitsets of the positions matching each distinct pattern symbol
+        let mut sets: Vec<(IupacNucleotide, Vec<u64>)> = Vec::new();
+        let mut set_of_symbol = Vec::with_capacity(pattern_len);
+        for &p in pattern {
+            let set = match sets.iter().position(|(q, _)| *q == p) {
+                Some(set) => set,
+                None => {
+                    let mut mask = vec![0; self.words.len()];
+                    for code in Self::matching_codes(p) {
+                        for (m, e) in mask.iter_mut().zip(self.eq_mask(code)) {
+                            *m |= e;
+                        }
+                    }
+                    sets.push((p, mask));
+                    sets.len() - 1
+                }
+            };
+            set_of_symbol.push(set);
+        }
+
+        let mut positions = Vec::new();
+        for word in 0..self.words.len() {
+            let mut matches = Self::LOW_BITS;
+            for (i, &set) in set_of_symbol.iter().enumerate() {
+                matches &= Self::shifted_word(&sets[set].1, word, i * B::BITS);
+                if matches == 0 {
+                    break;
+                }
+            }
+            while matches != 0 {
+                let bit = matches.trailing_zeros() as usize;
+                positions.push(word * Self::SYMBOLS_PER_WORD + bit / B::BITS);
+                matches &= matches - 1;
+            }
+        }
+
+        if topology.is_circular() {
+            // Matches spanning the origin
+            let matches_at = |start: usize| {
+                (0..pattern_len)
+                    .all(|i| pattern[i].matches(&self[(start + i) % self.len].to_iupac()))
+            };
+            positions.extend((self.len - pattern_len + 1..self.len).filter(|&s| matches_at(s)));
+        }
+        positions
+    }
+}
+
+impl<B> FromIterator<B> for PackedStorage<B>
+where
+    B: PackedNucleotide,
+{
+    fn from_iter<I: IntoIterator<Item = B>>(iter: I) -> Self {
+        let mut storage = Self::default();
+        iter.into_iter().for_each(|b| storage.push(b));
+        storage
+    }
+}
+
+impl<B> std::ops::Index<usize> for PackedStorage<B>
+where
+    B: PackedNucleotide,
+{
+    type Output = B;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len, index
+            )
+        })
+    }
+}
+
+/// Iterator over the nucleotides of a `PackedStorage`.
+#[derive(Debug, Clone)]
+pub struct PackedIter<'a, B> {
+    storage: &'a PackedStorage<B>,
+    range: Range<usize>,
+}
+
+impl<'a, B> Iterator for PackedIter<'a, B>
+where
+    B: PackedNucleotide,
+{
+    type Item = &'a B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|i| &self.storage[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<B> DoubleEndedIterator for PackedIter<'_, B>
+where
+    B: PackedNucleotide,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|i| &self.storage[i])
+    }
+}
+
+impl<B> ExactSizeIterator for PackedIter<'_, B> where B: PackedNucleotide {}
+
+impl<B, C> From<&GeneticSequence<B, C>> for GeneticSequence<B, C, PackedStorage<B>>
+where
+    B: PackedNucleotide + TryFromLetter + ToLetter,
+    C: Codon<B>,
+{
+    fn from(seq: &GeneticSequence<B, C>) -> Self {
+        seq.to_storage()
+    }
+}
+
+impl<B, C> From<&GeneticSequence<B, C, PackedStorage<B>>> for GeneticSequence<B, C>
+where
+    B: PackedNucleotide + TryFromLetter + ToLetter,
+    C: Codon<B>,
+{
+    fn from(seq: &GeneticSequence<B, C, PackedStorage<B>>) -> Self {
+        seq.to_storage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::seq::GeneticSequence;
+    use crate::traits::TryFromLetter;
+
+    use super::{PackedDnaSequence, PackedIupacSequence};
+
+    /// Deterministic pseudo-random sequence of `len` letters from `alphabet`.
+    fn random_sequence(len: usize, alphabet: &str, seed: u64) -> String {
+        let letters = alphabet.chars().collect::<Vec<_>>();
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                letters[(state >> 33) as usize % letters.len()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_packed_dna_sequence_round_trip() {
+        for len in [0, 1, 31, 32, 33, 64, 100] {
+            let s = random_sequence(len, "ACGT", len as u64);
+            let packed = PackedDnaSequence::from_str(&s).unwrap();
+            assert_eq!(packed.len(), len);
+            assert_eq!(packed.to_string(), s);
+            assert_eq!(DnaSequence::from(&packed).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_packed_dna_sequence_matches_dna_sequence() {
+        for (len, seed) in [(5, 1), (32, 2), (63, 3), (200, 4), (1000, 5)] {
+            let mut seq = DnaSequence::from_str(random_sequence(len, "ACGT", seed)).unwrap();
+            for topology in [Topology::Linear, Topology::Circular] {
+                seq.set_topology(topology);
+                let packed = PackedDnaSequence::from(&seq);
+                assert_eq!(packed.gc_count(), seq.gc_count());
+                assert_eq!(packed.at_count(), seq.at_count());
+                assert_eq!(packed.to_complement_string(), seq.to_complement_string());
+                assert_eq!(
+                    packed.to_reverse_strand_string(),
+                    seq.to_reverse_strand_string()
+                );
+                for pattern in ["A", "GATC", "CCWGG", "GCNNNNNNNGC", "RGATCY"] {
+                    let pattern = pattern
+                        .chars()
+                        .map(|c| IupacNucleotide::try_from_letter(c).unwrap())
+                        .collect::<Vec<_>>();
+                    assert_eq!(packed.find_pattern(&pattern), seq.find_pattern(&pattern));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_packed_dna_sequence_restriction_sites() {
+        let database = EnzymeDatabase::builtin();
+        let options = RestrictionOptions::default().with_methylation(MethylationProfile::ecoli());
+        for topology in [Topology::Linear, Topology::Circular] {
+            let mut seq = DnaSequence::from_str(random_sequence(2000, "ACGT", 7)).unwrap();
+            seq.set_topology(topology);
+            let mut packed = PackedDnaSequence::from(&seq);
+            seq.annotate_restriction_enzymes_with(&database, &options);
+            packed.annotate_restriction_enzymes_with(&database, &options);
+            assert!(!seq.as_annotations().is_empty());
+            assert_eq!(packed.as_annotations(), seq.as_annotations());
+
+            let map = seq.restriction_map(&database, &options);
+            let packed_map = packed.restriction_map(&database, &options);
+            for enzyme in database.iter() {
+                assert_eq!(packed_map.sites(&enzyme.name), map.sites(&enzyme.name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_packed_dna_sequence_long_pattern() {
+        let s = random_sequence(500, "ACGT", 42);
+        let packed = PackedDnaSequence::from_str(&s).unwrap();
+        let pattern = DnaSequence::from_str(&s[100..180]).unwrap();
+        assert!(packed.find_pattern(pattern.as_nucleotides()).contains(&100));
+    }
+
+    #[test]
+    fn test_packed_dna_sequence_edits() {
+        let s = random_sequence(100, "ACGT", 9);
+        let mut seq = DnaSequence::from_str(&s).unwrap();
+        seq.set_topology(Topology::Circular);
+        seq.as_mut_annotations().extend([
+            Annotation::new(10, 40, Some(20), "long"),
+            Annotation::new(95, 4, None, "origin"),
+        ]);
+        let mut packed = PackedDnaSequence::from(&seq);
+        packed.enable_history(10);
+
+        fn edit<S>(seq: &mut GeneticSequence<DnaNucleotide, DnaCodon, S>)
+        where
+            S: SequenceStorage<DnaNucleotide>,
+        {
+            seq.insert_bases(30, [DnaNucleotide::G; 40]);
+            seq.remove_range(2..5);
+            seq.replace_range(60..70, [DnaNucleotide::T]);
+            seq.set_base(0, DnaNucleotide::C);
+            seq.rotate_to(50).unwrap();
+        }
+        edit(&mut seq);
+        edit(&mut packed);
+        assert_eq!(packed.to_string(), seq.to_string());
+        assert_eq!(packed.as_annotations(), seq.as_annotations());
+        assert_eq!(packed.codon_iter().collect::<Vec<_>>(), seq.as_codons());
+        assert_eq!(packed.translate(), seq.translate());
+        assert_eq!(
+            packed.find_orfs(&OrfOptions::default()).len(),
+            seq.find_orfs(&OrfOptions::default()).len()
+        );
+        assert_eq!(
+            packed.slice_wrapping(90, 10).to_string(),
+            seq.slice_wrapping(90, 10).to_string()
+        );
+
+        while packed.undo() {}
+        assert_eq!(packed.to_string(), s);
+    }
+
+    #[test]
+    fn test_packed_iupac_sequence_matches_iupac_iter() {
+        let s = random_sequence(300, "ACGTWSMKRYBDHVN-", 7);
+        let packed = PackedIupacSequence::from_str(&s).unwrap();
+        assert_eq!(packed.to_string(), s);
+
+        let bases = s
+            .chars()
+            .map(|c| IupacNucleotide::try_from_letter(c).unwrap())
+            .collect::<Vec<_>>();
+        let complement = bases.iter().map(|b| b.complement()).collect::<Vec<_>>();
+        assert_eq!(packed.as_complement(), complement);
+        assert_eq!(
+            packed.as_reverse_strand(),
+            complement.into_iter().rev().collect::<Vec<_>>()
+        );
+        let gc = bases
+            .iter()
+            .filter(|b| [IupacNucleotide::G, IupacNucleotide::C].contains(b))
+            .count();
+        assert_eq!(packed.gc_count(), gc);
+
+        let pattern = [IupacNucleotide::A, IupacNucleotide::S, IupacNucleotide::N];
+        let expected = (0..bases.len() - 2)
+            .filter(|&i| (0..3).all(|j| pattern[j].matches(&bases[i + j])))
+            .collect::<Vec<_>>();
+        assert_eq!(packed.find_pattern(&pattern), expected);
+    }
+}
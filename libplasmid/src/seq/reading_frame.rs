@@ -0,0 +1,109 @@
+use anyhow::bail;
+
+use super::Strand;
+
+/// One of the six reading frames of a double-stranded genetic sequence.
+///
+/// Forward frames start at the first, second or third nucleotide of the sequence,
+/// reverse frames start at the first, second or third nucleotide of its reverse
+/// complement.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ReadingFrame {
+    Plus1,
+    Plus2,
+    Plus3,
+    Minus1,
+    Minus2,
+    Minus3,
+}
+
+impl ReadingFrame {
+    /// The reading frame on `strand` whose codons start at `offset` (modulo 3).
+    pub fn new(strand: Strand, offset: usize) -> ReadingFrame {
+        use ReadingFrame::*;
+        match (strand, offset % 3) {
+            (Strand::Forward, 0) => Plus1,
+            (Strand::Forward, 1) => Plus2,
+            (Strand::Forward, _) => Plus3,
+            (Strand::Reverse, 0) => Minus1,
+            (Strand::Reverse, 1) => Minus2,
+            (Strand::Reverse, _) => Minus3,
+        }
+    }
+
+    /// All six reading frames, forward frames first.
+    pub fn all() -> [ReadingFrame; 6] {
+        use ReadingFrame::*;
+        [Plus1, Plus2, Plus3, Minus1, Minus2, Minus3]
+    }
+
+    /// The strand the frame is read on.
+    pub fn strand(&self) -> Strand {
+        use ReadingFrame::*;
+        match self {
+            Plus1 | Plus2 | Plus3 => Strand::Forward,
+            Minus1 | Minus2 | Minus3 => Strand::Reverse,
+        }
+    }
+
+    /// The position of the first codon on its strand.
+    pub fn offset(&self) -> usize {
+        use ReadingFrame::*;
+        match self {
+            Plus1 | Minus1 => 0,
+            Plus2 | Minus2 => 1,
+            Plus3 | Minus3 => 2,
+        }
+    }
+}
+
+impl TryFrom<i8> for ReadingFrame {
+    type Error = anyhow::Error;
+
+    /// Convert the conventional notation (`1`, `2`, `3`, `-1`, `-2`, `-3`)
+    /// to a reading frame.
+    fn try_from(frame: i8) -> anyhow::Result<Self> {
+        use ReadingFrame::*;
+        Ok(match frame {
+            1 => Plus1,
+            2 => Plus2,
+            3 => Plus3,
+            -1 => Minus1,
+            -2 => Minus2,
+            -3 => Minus3,
+            _ => bail!("Invalid reading frame: {}", frame),
+        })
+    }
+}
+
+impl From<ReadingFrame> for i8 {
+    fn from(frame: ReadingFrame) -> i8 {
+        let n = frame.offset() as i8 + 1;
+        match frame.strand() {
+            Strand::Forward => n,
+            Strand::Reverse => -n,
+        }
+    }
+}
+
+impl std::fmt::Display for ReadingFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.strand(), self.offset() + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReadingFrame, Strand};
+
+    #[test]
+    fn test_reading_frame_conversion() {
+        for frame in ReadingFrame::all() {
+            assert_eq!(ReadingFrame::try_from(i8::from(frame)).unwrap(), frame);
+        }
+        assert!(ReadingFrame::try_from(0).is_err());
+        assert!(ReadingFrame::try_from(4).is_err());
+        assert_eq!(ReadingFrame::Minus2.to_string(), "-2");
+        assert_eq!(ReadingFrame::new(Strand::Reverse, 4), ReadingFrame::Minus2);
+    }
+}
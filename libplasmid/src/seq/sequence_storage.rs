@@ -0,0 +1,131 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::{traits::*, uni::IupacNucleotide};
+
+use super::Topology;
+
+/// Storage of the nucleotides of a `GeneticSequence`.
+///
+/// `Vec<B>` keeps one nucleotide per element and is the default storage,
+/// `PackedStorage` packs the nucleotides into the bits of 64 bit words.
+/// Counting, complementing and pattern search have default implementations
+/// that storages can replace with ones working on their representation.
+pub trait SequenceStorage<B>: Clone + Default + FromIterator<B>
+where
+    B: Nucleotide + ToIupac + Copy,
+{
+    type Iter<'a>: DoubleEndedIterator<Item = &'a B> + ExactSizeIterator + Clone
+    where
+        Self: 'a,
+        B: 'a;
+
+    /// The number of nucleotides.
+    fn len(&self) -> usize;
+
+    /// Whether there are no nucleotides.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The nucleotide at `index`, or `None` if it is out of bounds.
+    fn get(&self, index: usize) -> Option<&B>;
+
+    /// An iterator over the nucleotides.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Replace the nucleotide at `index` and return the previous one.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    fn replace(&mut self, index: usize, base: B) -> B;
+
+    /// Replace the nucleotides in `range` with `bases` and return the removed ones.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    fn splice(&mut self, range: Range<usize>, bases: &[B]) -> Vec<B>;
+
+    /// Rotate the nucleotides so that the one at `mid` becomes the first.
+    fn rotate_left(&mut self, mid: usize);
+
+    /// The nucleotides as a slice, which is only a copy if they aren't
+    /// stored contiguously. Prefer `iter` on large sequences.
+    fn to_slice(&self) -> Cow<'_, [B]>;
+
+    /// The complemented nucleotides, i.e. the complementary strand read 3' to 5'.
+    fn complement(&self) -> Self {
+        self.iter().map(|b| b.complement()).collect()
+    }
+
+    /// The reverse complement, i.e. the complementary strand read 5' to 3'.
+    fn reverse_complement(&self) -> Self {
+        self.iter().rev().map(|b| b.complement()).collect()
+    }
+
+    /// Count the nucleotides which are one of `bases`.
+    fn count(&self, bases: &[IupacNucleotide]) -> usize {
+        self.iter()
+            .filter(|b| bases.contains(&b.to_iupac()))
+            .count()
+    }
+
+    /// Find all positions at which `pattern` matches, on circular sequences
+    /// including matches spanning the origin.
+    fn find_pattern(&self, pattern: &[IupacNucleotide], topology: Topology) -> Vec<usize> {
+        let len = self.len();
+        if pattern.is_empty() || pattern.len() > len {
+            return Vec::new();
+        }
+        let nucleotides = self.to_slice();
+        let last_start = match topology {
+            Topology::Linear => len - pattern.len() + 1,
+            Topology::Circular => len,
+        };
+        (0..last_start)
+            .filter(|&start| {
+                pattern
+                    .iter()
+                    .enumerate()
+                    .all(|(i, p)| p.matches(&nucleotides[(start + i) % len].to_iupac()))
+            })
+            .collect()
+    }
+}
+
+impl<B> SequenceStorage<B> for Vec<B>
+where
+    B: Nucleotide + ToIupac + Copy,
+{
+    type Iter<'a>
+        = std::slice::Iter<'a, B>
+    where
+        B: 'a;
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn get(&self, index: usize) -> Option<&B> {
+        self.as_slice().get(index)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.as_slice().iter()
+    }
+
+    fn replace(&mut self, index: usize, base: B) -> B {
+        std::mem::replace(&mut self[index], base)
+    }
+
+    fn splice(&mut self, range: Range<usize>, bases: &[B]) -> Vec<B> {
+        Vec::splice(self, range, bases.iter().copied()).collect()
+    }
+
+    fn rotate_left(&mut self, mid: usize) {
+        self.as_mut_slice().rotate_left(mid);
+    }
+
+    fn to_slice(&self) -> Cow<'_, [B]> {
+        Cow::Borrowed(self)
+    }
+}
@@ -0,0 +1,295 @@
+use std::ops::Index;
+
+use crate::{prelude::IupacNucleotide, traits::*};
+
+use super::{Annotation, GeneticSequence, SequenceStorage};
+
+/// Borrowed view of a region of a genetic sequence.
+///
+/// The view provides the analysis functions of a genetic sequence without copying
+/// any nucleotides. On circular sequences a view may wrap around the origin.
+pub struct SequenceView<'a, B, C, S = Vec<B>>
+where
+    B: Nucleotide,
+    C: Codon<B>,
+{
+    sequence: &'a GeneticSequence<B, C, S>,
+    start: usize,
+    len: usize,
+}
+
+impl<'a, B, C, S> SequenceView<'a, B, C, S>
+where
+    B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
+    C: Codon<B>,
+    S: SequenceStorage<B>,
+{
+    pub(crate) fn new(sequence: &'a GeneticSequence<B, C, S>, start: usize, len: usize) -> Self {
+        Self {
+            sequence,
+            start,
+            len,
+        }
+    }
+
+    /// The position of the first nucleotide of the view in the parent sequence.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The position after the last nucleotide of the view in the parent sequence.
+    /// Smaller than `start` if the view wraps around the origin.
+    pub fn end(&self) -> usize {
+        let parent_len = self.sequence.len();
+        if parent_len == 0 {
+            0
+        } else if self.start + self.len > parent_len {
+            self.start + self.len - parent_len
+        } else {
+            self.start + self.len
+        }
+    }
+
+    /// The number of nucleotides in the view.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the view contains no nucleotides.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// An iterator over the nucleotides of the view.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCC").unwrap();
+    /// let view = seq.slice(1..4);
+    /// assert_eq!(view.nucleotide_iter().copied().collect::<Vec<_>>(), [G, A, T]);
+    /// ```
+    pub fn nucleotide_iter(&self) -> impl Iterator<Item = &'a B> + 'a {
+        self.sequence
+            .nucleotide_iter_from(self.start)
+            .take(self.len)
+    }
+
+    /// An iterator over the codons of the view, starting at its first nucleotide.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::{*, DnaNucleotide::*};
+    ///
+    /// let seq = DnaSequence::from_str("TGATCCA").unwrap();
+    /// let codons = seq.slice(1..).codon_iter().collect::<Vec<_>>();
+    /// assert_eq!(codons, [[G, A, T].into(), [C, C, A].into()]);
+    /// ```
+    pub fn codon_iter(&self) -> impl Iterator<Item = C> + 'a {
+        let mut nucleotides = self.nucleotide_iter();
+        (0..self.len / 3).map(move |_| {
+            let mut base = || *nucleotides.next().unwrap();
+            C::from_triplet_arr([base(), base(), base()])
+        })
+    }
+
+    /// An iterator over the IUPAC sequence of the view.
+    pub fn iupac_iter(&self) -> impl Iterator<Item = IupacNucleotide> + 'a {
+        self.nucleotide_iter().map(|n| n.to_iupac())
+    }
+
+    /// The annotations of the parent sequence overlapping the view,
+    /// clipped to the view and re-based to its coordinates.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("ATGTTCTAA").unwrap();
+    /// seq.as_mut_annotations().push(Annotation::new(0, 5, None, "Met-Phe"));
+    /// let annotations = seq.slice(3..).annotations();
+    /// assert_eq!((annotations[0].start, annotations[0].end), (0, 2));
+    /// ```
+    pub fn annotations(&self) -> Vec<Annotation> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let parent_len = self.sequence.len();
+        self.sequence
+            .annotation_iter()
+            .flat_map(|ann| ann.project(parent_len, self.start, self.len))
+            .collect()
+    }
+
+    /// Count guanine and cytosine nucleotides
+    pub fn gc_count(&self) -> usize {
+        use IupacNucleotide::*;
+        let nucleotides = [G, C];
+        self.iupac_iter()
+            .filter(|n| nucleotides.contains(n))
+            .count()
+    }
+
+    /// Count adenine and thymine/uracil nucleotides
+    pub fn at_count(&self) -> usize {
+        use IupacNucleotide::*;
+        let nucleotides = [A, T];
+        self.iupac_iter()
+            .filter(|n| nucleotides.contains(n))
+            .count()
+    }
+
+    /// Compute guanine-cytosine ratio
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let seq = DnaSequence::from_str("ATGTTCGC").unwrap();
+    /// assert!((seq.slice(4..).gc_ratio() - 0.75).abs() <= std::f32::EPSILON);
+    /// ```
+    pub fn gc_ratio(&self) -> f32 {
+        self.gc_count() as f32 / self.len as f32
+    }
+
+    /// Compute adenine-thymine ratio
+    pub fn at_ratio(&self) -> f32 {
+        self.at_count() as f32 / self.len as f32
+    }
+
+    /// Compute adenine-thymine/uracil to guanine-cytosine ratio
+    pub fn at_gc_ratio(&self) -> f32 {
+        self.at_count() as f32 / self.gc_count() as f32
+    }
+
+    /// Copy the view into a new, linear genetic sequence,
+    /// including the projected annotations.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let mut seq = DnaSequence::from_str("ATGTTCTAA").unwrap();
+    /// seq.set_topology(Topology::Circular);
+    /// seq.as_mut_annotations().push(Annotation::new(6, 2, None, "Stop-Start"));
+    ///
+    /// let extracted = seq.slice_wrapping(6, 3).extract();
+    /// assert_eq!(extracted.to_string(), "TAAATG");
+    /// assert_eq!(extracted.as_annotations(), [Annotation::new(0, 5, None, "Stop-Start")]);
+    /// ```
+    pub fn extract(&self) -> GeneticSequence<B, C, S> {
+        let mut sequence = GeneticSequence::new();
+        sequence.insert_bases(0, self.nucleotide_iter());
+        sequence.as_mut_annotations().extend(self.annotations());
+        sequence
+    }
+}
+
+impl<B, C, S> std::fmt::Display for SequenceView<'_, B, C, S>
+where
+    B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
+    C: Codon<B>,
+    S: SequenceStorage<B>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s: String = self.nucleotide_iter().map(|b| b.to_letter()).collect();
+        write!(f, "{}", s)
+    }
+}
+
+impl<B, C, S> Index<usize> for SequenceView<'_, B, C, S>
+where
+    B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
+    C: Codon<B>,
+    S: SequenceStorage<B>,
+{
+    type Output = B;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        assert!(
+            index < self.len,
+            "index out of bounds: the len is {} but the index is {}",
+            self.len,
+            index
+        );
+        &self.sequence[(self.start + index) % self.sequence.len()]
+    }
+}
+
+impl<B, C, S> NucleotideSequence for SequenceView<'_, B, C, S>
+where
+    B: Nucleotide + TryFromLetter + ToLetter + ToIupac + Copy,
+    C: Codon<B>,
+    S: SequenceStorage<B>,
+{
+    fn matches<T>(&self, seq: &[T]) -> bool
+    where
+        T: ToIupac,
+    {
+        self.iupac_iter()
+            .zip(seq)
+            .all(|(a, b)| a.matches(&b.to_iupac()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::seq::{Annotation, DnaSequence, Topology};
+    use crate::traits::NucleotideSequence;
+    use crate::uni::IupacNucleotide;
+
+    #[test]
+    fn test_sequence_view_slice() {
+        let seq = DnaSequence::from_str("AAACCCGGGTTT").unwrap();
+        let view = seq.slice(3..9);
+        assert_eq!(view.len(), 6);
+        assert_eq!((view.start(), view.end()), (3, 9));
+        assert_eq!(view.to_string(), "CCCGGG");
+        assert_eq!(view.gc_count(), 6);
+        assert_eq!(view.codon_iter().count(), 2);
+        assert!(view.matches(&[IupacNucleotide::S, IupacNucleotide::C]));
+    }
+
+    #[test]
+    fn test_sequence_view_wrapping() {
+        let mut seq = DnaSequence::from_str("AAACCCGGGTTT").unwrap();
+        seq.set_topology(Topology::Circular);
+        let view = seq.slice_wrapping(9, 3);
+        assert_eq!(view.to_string(), "TTTAAA");
+        assert_eq!((view.start(), view.end()), (9, 3));
+        assert_eq!(view[3], crate::dna::DnaNucleotide::A);
+        assert_eq!(view.gc_count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sequence_view_wrapping_linear() {
+        let seq = DnaSequence::from_str("AAACCCGGGTTT").unwrap();
+        seq.slice_wrapping(9, 3);
+    }
+
+    #[test]
+    fn test_sequence_view_annotations() {
+        let mut seq = DnaSequence::from_str("AAACCCGGGTTT").unwrap();
+        seq.set_topology(Topology::Circular);
+        seq.as_mut_annotations().extend([
+            Annotation::new(0, 2, Some(1), "A"),
+            Annotation::new(2, 7, Some(6), "ACG"),
+            Annotation::new(10, 4, None, "TAC"),
+        ]);
+        let view = seq.slice(4..11);
+        assert_eq!(
+            view.annotations(),
+            [
+                Annotation::new(0, 3, Some(2), "ACG"),
+                Annotation::new(6, 6, None, "TAC"),
+                Annotation::new(0, 0, None, "TAC"),
+            ]
+        );
+        let extracted = seq.slice(4..11).extract();
+        assert_eq!(extracted.to_string(), "CCGGGTT");
+        assert!(!extracted.is_circular());
+        assert_eq!(extracted.as_annotations(), view.annotations());
+    }
+}
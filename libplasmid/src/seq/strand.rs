@@ -0,0 +1,27 @@
+/// Strand of a double-stranded genetic sequence
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Strand {
+    /// The top strand, read 5' to 3' from the start of the sequence
+    Forward,
+    /// The bottom strand, read 5' to 3' from the end of the sequence
+    Reverse,
+}
+
+impl Strand {
+    /// The opposite strand.
+    pub fn opposite(&self) -> Strand {
+        match self {
+            Strand::Forward => Strand::Reverse,
+            Strand::Reverse => Strand::Forward,
+        }
+    }
+}
+
+impl std::fmt::Display for Strand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strand::Forward => write!(f, "+"),
+            Strand::Reverse => write!(f, "-"),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// Topology of a genetic sequence
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Topology {
+    /// Linear sequence with distinct 5' and 3' ends
+    #[default]
+    Linear,
+    /// Circular sequence (e.g. a plasmid), the last nucleotide is followed by the first one
+    Circular,
+}
+
+impl Topology {
+    pub fn is_circular(&self) -> bool {
+        *self == Topology::Circular
+    }
+}
+
+impl std::fmt::Display for Topology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Topology::Linear => write!(f, "linear"),
+            Topology::Circular => write!(f, "circular"),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::ops::Deref;
+
+use crate::eaa::{GeneticCode, QualifiedEaa};
+
+/// Options for the lazy translation of a reading frame.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct TranslationOptions {
+    /// Skip all codons before the first start codon of the genetic code,
+    /// which is translated as methionine even if it is an alternative one.
+    pub start_at_met: bool,
+    /// End the translation before the first stop codon.
+    pub stop_at_ter: bool,
+    /// The genetic code used for the translation
+    pub genetic_code: GeneticCode,
+}
+
+impl TranslationOptions {
+    /// Translate from the first start codon up to the first stop codon.
+    pub fn protein() -> Self {
+        Self {
+            start_at_met: true,
+            stop_at_ter: true,
+            genetic_code: GeneticCode::default(),
+        }
+    }
+
+    /// Use an alternative genetic code.
+    pub fn with_genetic_code(mut self, genetic_code: &GeneticCode) -> Self {
+        self.genetic_code = *genetic_code;
+        self
+    }
+}
+
+/// An amino acid translated from a codon of a genetic sequence.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PositionedEaa {
+    /// The amino acid and its codon, read 5' to 3' on the translated strand
+    pub eaa: QualifiedEaa,
+    /// The position of the first nucleotide of the codon on the top strand
+    pub start: usize,
+    /// The position of the last nucleotide of the codon on the top strand.
+    /// Smaller than `start` if the codon wraps around the origin.
+    pub end: usize,
+}
+
+impl Deref for PositionedEaa {
+    type Target = QualifiedEaa;
+
+    fn deref(&self) -> &QualifiedEaa {
+        &self.eaa
+    }
+}
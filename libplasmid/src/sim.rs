@@ -0,0 +1,17 @@
+pub mod cloning;
+pub mod digest;
+pub mod domestication;
+pub mod gibson;
+pub mod golden_gate;
+pub mod ligation;
+pub mod pcr;
+pub mod primer;
+
+pub use self::cloning::*;
+pub use self::digest::*;
+pub use self::domestication::*;
+pub use self::gibson::*;
+pub use self::golden_gate::*;
+pub use self::ligation::*;
+pub use self::pcr::*;
+pub use self::primer::*;
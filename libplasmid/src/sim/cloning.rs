@@ -0,0 +1,327 @@
+use std::ops::Range;
+
+use crate::{
+    seq::{DnaSequence, OverhangType},
+    uni::{
+        EnzymeDatabase, EnzymeQuery, IupacNucleotide, MethylationProfile, ReactionBuffer,
+        RestrictionEnzyme, RestrictionMap, RestrictionOptions, RestrictionSite,
+    },
+};
+
+/// Options for `plan_restriction_cloning`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CloningOptions {
+    /// Methylation of the vector and the insert, blocked sites are not cut
+    pub methylation: MethylationProfile,
+    /// Allow enzymes without a site in the insert, which then has to be
+    /// amplified with primers adding the site in their tails
+    pub pcr_tails: bool,
+    /// Number of nucleotides at each end of the insert in which an existing
+    /// site of an enzyme is used instead of a PCR tail
+    pub insert_flank: usize,
+    /// Minimum number of specified nucleotides of the recognition sites
+    pub min_specificity: usize,
+    /// Also suggest pairs of enzymes producing compatible ends, which allow the
+    /// insert to be ligated in both orientations
+    pub allow_non_directional: bool,
+}
+
+impl CloningOptions {
+    pub fn with_methylation(mut self, methylation: MethylationProfile) -> Self {
+        self.methylation = methylation;
+        self
+    }
+
+    pub fn with_pcr_tails(mut self, pcr_tails: bool) -> Self {
+        self.pcr_tails = pcr_tails;
+        self
+    }
+
+    pub fn with_insert_flank(mut self, insert_flank: usize) -> Self {
+        self.insert_flank = insert_flank;
+        self
+    }
+
+    pub fn with_min_specificity(mut self, min_specificity: usize) -> Self {
+        self.min_specificity = min_specificity;
+        self
+    }
+
+    pub fn with_allow_non_directional(mut self, allow: bool) -> Self {
+        self.allow_non_directional = allow;
+        self
+    }
+}
+
+impl Default for CloningOptions {
+    fn default() -> Self {
+        Self {
+            methylation: MethylationProfile::none(),
+            pcr_tails: true,
+            insert_flank: 20,
+            min_specificity: 6,
+            allow_non_directional: false,
+        }
+    }
+}
+
+/// A pair of enzymes to clone an insert into a vector with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloningPair<'a> {
+    /// The enzyme cutting the vector first along the top strand
+    pub left: &'a RestrictionEnzyme,
+    /// The enzyme cutting the vector second along the top strand
+    pub right: &'a RestrictionEnzyme,
+    /// Top strand cut positions of `left` and `right` in the vector, as the first
+    /// nucleotide after the cut
+    pub vector_cuts: (usize, usize),
+    /// The site to add to the 5' end of the insert with a PCR primer, `None`
+    /// if the insert already carries a site of `left` near its start
+    pub left_tail: Option<Vec<IupacNucleotide>>,
+    /// The site to add to the 3' end of the insert with a PCR primer, `None`
+    /// if the insert already carries a site of `right` near its end
+    pub right_tail: Option<Vec<IupacNucleotide>>,
+    /// Whether the ends of `left` and `right` are incompatible, so the insert
+    /// can only be ligated in one orientation
+    pub directional: bool,
+    /// The buffer both enzymes are most active in and the lower of their activities
+    pub buffer: Option<(ReactionBuffer, u8)>,
+}
+
+impl CloningPair<'_> {
+    /// The shorter of the overhangs produced by the enzymes.
+    pub fn overhang_len(&self) -> usize {
+        self.left
+            .cut
+            .overhang_len()
+            .min(self.right.cut.overhang_len())
+    }
+
+    /// The number of PCR tails needed to add the sites to the insert.
+    pub fn tail_count(&self) -> usize {
+        self.left_tail.iter().count() + self.right_tail.iter().count()
+    }
+}
+
+/// The buffer `left` and `right` are most active in together, preferring
+/// rCutSmart if it is as good as any other.
+fn common_buffer(
+    left: &RestrictionEnzyme,
+    right: &RestrictionEnzyme,
+) -> Option<(ReactionBuffer, u8)> {
+    let (left, right) = (left.buffer_activities()?, right.buffer_activities()?);
+    ReactionBuffer::all()
+        .into_iter()
+        .zip(left.into_iter().zip(right))
+        .map(|(buffer, (left, right))| (buffer, left.min(right)))
+        .max_by_key(|&(_, activity)| activity)
+}
+
+/// Whether the ends of an enzyme are the same wherever it cuts, i.e. its cuts
+/// lie within its site and the overhang is fully specified.
+fn has_defined_ends(enzyme: &RestrictionEnzyme) -> bool {
+    use IupacNucleotide::*;
+    enzyme.upstream_cut.is_none()
+        && !enzyme.cuts_outside()
+        && enzyme.overhang_type().is_some()
+        && enzyme.overhang().iter().all(|n| matches!(n, A | C | G | T))
+}
+
+/// Sites of `enzyme` in `map` not blocked by methylation.
+fn cut_sites<'m>(map: &'m RestrictionMap, enzyme: &RestrictionEnzyme) -> Vec<&'m RestrictionSite> {
+    map.sites(&enzyme.name)
+        .unwrap_or_default()
+        .iter()
+        .filter(|site| site.is_cut())
+        .collect()
+}
+
+/// Suggest pairs of enzymes to clone `insert` into the region of `vector`
+/// annotated with `region`, e.g. its multiple cloning site.
+///
+/// Each pair cuts the vector exactly once inside of the region, in the order
+/// `left`, `right`, with non-overlapping sites. Neither enzyme cuts the insert,
+/// except for a single site within `CloningOptions::insert_flank` of the
+/// insert end the enzyme is used for. Missing sites are added with PCR tails.
+/// Only enzymes with defined ends are considered, so the insert ends match
+/// the vector ends.
+///
+/// The pairs are ranked by the activity of the enzymes in a common buffer,
+/// then by the length of their overhangs and by the number of PCR tails.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::plan_restriction_cloning;
+///
+/// let mut vector = DnaSequence::from_str("TTTTTTGAATTCAAAAGCTTTTTTTT").unwrap();
+/// vector.as_mut_annotations().push(Annotation::new(6, 19, None, "MCS"));
+/// let insert = DnaSequence::from_str("ATGGCCAAAGCCTAA").unwrap();
+///
+/// let database = EnzymeDatabase::builtin();
+/// let pairs = plan_restriction_cloning(&vector, "MCS", &insert, &database, &Default::default()).unwrap();
+/// let best = &pairs[0];
+/// assert_eq!((best.left.name.as_str(), best.right.name.as_str()), ("EcoRI", "HindIII"));
+/// assert_eq!(best.buffer, Some((ReactionBuffer::NEBuffer2_1, 100)));
+/// assert_eq!(best.tail_count(), 2);
+/// ```
+pub fn plan_restriction_cloning<'a>(
+    vector: &DnaSequence,
+    region: &str,
+    insert: &DnaSequence,
+    database: &'a EnzymeDatabase,
+    options: &CloningOptions,
+) -> anyhow::Result<Vec<CloningPair<'a>>> {
+    let Some(annotation) = vector.annotation_iter().find(|ann| ann.text == region) else {
+        bail!("The vector has no annotation {}", region);
+    };
+    if annotation.is_wrapping() {
+        bail!("Regions wrapping around the origin are not supported");
+    }
+    let region: Range<usize> = annotation.start..annotation.end + 1;
+    let restriction_options = RestrictionOptions::default().with_methylation(options.methylation);
+    let vector_map = vector.restriction_map(database, &restriction_options);
+    let insert_map = insert.restriction_map(database, &restriction_options);
+
+    let query = EnzymeQuery::default()
+        .with_cut_count(1..=1)
+        .with_cuts_within(region.clone())
+        .with_specificity(options.min_specificity..=usize::MAX);
+    // Candidates with their site in the vector and their sites in the insert
+    let candidates = vector_map
+        .query(&query)
+        .into_iter()
+        .filter(|&enzyme| has_defined_ends(enzyme))
+        .filter_map(|enzyme| {
+            let site = *cut_sites(&vector_map, enzyme).first()?;
+            let cut = site.cuts(enzyme).next()?.top?;
+            let cut = (site.start as isize + cut) as usize;
+            Some((enzyme, site, cut, cut_sites(&insert_map, enzyme)))
+        })
+        .collect::<Vec<_>>();
+
+    let flank = options.insert_flank.min(insert.len());
+    // The tail to add to the insert for an enzyme, `Err` if the insert can't be used
+    let tail = |enzyme: &RestrictionEnzyme,
+                sites: &[&RestrictionSite],
+                within: Range<usize>|
+     -> Result<Option<Vec<IupacNucleotide>>, ()> {
+        let site_len = enzyme.recognition_site().len();
+        match sites {
+            [] if options.pcr_tails => Ok(Some(enzyme.recognition_site())),
+            [site] if within.start <= site.start && site.start + site_len <= within.end => Ok(None),
+            _ => Err(()),
+        }
+    };
+
+    let mut pairs = Vec::new();
+    for (left, left_site, left_cut, left_insert) in candidates.iter() {
+        let Ok(left_tail) = tail(left, left_insert, 0..flank) else {
+            continue;
+        };
+        for (right, right_site, right_cut, right_insert) in candidates.iter() {
+            let left_site_end = left_site.start + left.recognition_site().len();
+            if right_cut <= left_cut || right_site.start < left_site_end {
+                continue;
+            }
+            let Ok(right_tail) = tail(right, right_insert, insert.len() - flank..insert.len())
+            else {
+                continue;
+            };
+            let directional = !(left.overhang_type() == right.overhang_type()
+                && left.overhang() == right.overhang()
+                || left.overhang_type() == Some(OverhangType::Blunt)
+                    && right.overhang_type() == Some(OverhangType::Blunt));
+            if !directional && !options.allow_non_directional {
+                continue;
+            }
+            pairs.push(CloningPair {
+                left,
+                right,
+                vector_cuts: (*left_cut, *right_cut),
+                left_tail: left_tail.clone(),
+                right_tail,
+                directional,
+                buffer: common_buffer(left, right),
+            });
+        }
+    }
+    pairs.sort_by_key(|pair| {
+        (
+            std::cmp::Reverse(pair.buffer.map_or(0, |(_, activity)| activity)),
+            std::cmp::Reverse(pair.overhang_len()),
+            pair.tail_count(),
+        )
+    });
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{plan_restriction_cloning, CloningOptions};
+
+    fn vector() -> DnaSequence {
+        // EcoRI, BamHI, XbaI, PstI in the MCS and a second BamHI site outside
+        let mut vector =
+            DnaSequence::from_str("GGATCCTTTTGAATTCAAGGATCCAATCTAGAAACTGCAGTTTTTTTT").unwrap();
+        vector.set_topology(Topology::Circular);
+        vector
+            .as_mut_annotations()
+            .push(Annotation::new(10, 39, None, "MCS"));
+        vector
+    }
+
+    fn names<'a>(pairs: &'a [super::CloningPair]) -> Vec<(&'a str, &'a str)> {
+        pairs
+            .iter()
+            .map(|p| (p.left.name.as_str(), p.right.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_restriction_cloning() {
+        let database = EnzymeDatabase::builtin()
+            .filter(|e| ["EcoRI", "BamHI", "XbaI", "PstI"].contains(&e.name.as_str()));
+        // PstI site in the middle of the insert
+        let insert = format!("ATG{}CTGCAG{}TAA", "A".repeat(30), "A".repeat(30));
+        let insert = DnaSequence::from_str(&insert).unwrap();
+        let pairs =
+            plan_restriction_cloning(&vector(), "MCS", &insert, &database, &Default::default())
+                .unwrap();
+        // BamHI cuts the vector twice, PstI cuts the insert
+        assert_eq!(names(&pairs), [("EcoRI", "XbaI")]);
+        assert_eq!(pairs[0].vector_cuts, (11, 27));
+        assert_eq!(pairs[0].buffer, Some((ReactionBuffer::NEBuffer2_1, 100)));
+        assert!(pairs[0].directional);
+
+        let options = CloningOptions::default().with_pcr_tails(false);
+        let pairs =
+            plan_restriction_cloning(&vector(), "MCS", &insert, &database, &options).unwrap();
+        assert!(pairs.is_empty());
+        assert!(plan_restriction_cloning(&vector(), "ori", &insert, &database, &options).is_err());
+    }
+
+    #[test]
+    fn test_plan_restriction_cloning_existing_sites() {
+        let database = EnzymeDatabase::builtin()
+            .filter(|e| ["EcoRI", "XbaI", "PstI"].contains(&e.name.as_str()));
+        use IupacNucleotide::*;
+        let xbai_site = [T, C, T, A, G, A];
+        // EcoRI at the start and PstI at the end of the insert
+        let insert = DnaSequence::from_str("GAATTCATGAAAAAATAACTGCAG").unwrap();
+        let options = CloningOptions::default().with_insert_flank(8);
+        let pairs =
+            plan_restriction_cloning(&vector(), "MCS", &insert, &database, &options).unwrap();
+        // Ranked by the common buffer activity, then the number of PCR tails
+        assert_eq!(
+            names(&pairs),
+            [("EcoRI", "XbaI"), ("EcoRI", "PstI"), ("XbaI", "PstI")]
+        );
+        assert_eq!(pairs[0].buffer, Some((ReactionBuffer::NEBuffer2_1, 100)));
+        assert_eq!(pairs[1].buffer, Some((ReactionBuffer::NEBuffer2_1, 75)));
+        assert_eq!(pairs[1].tail_count(), 0);
+        assert_eq!(pairs[2].left_tail.as_deref(), Some(&xbai_site[..]));
+    }
+}
@@ -0,0 +1,391 @@
+use std::borrow::Borrow;
+
+use crate::{
+    dna::DnaNucleotide,
+    seq::{DnaEnd, DnaSequence, DoubleStrandedDna, Topology},
+    traits::Nucleotide,
+    uni::{MethylationProfile, RestrictionEnzyme, RestrictionSiteScanner},
+};
+
+/// A fragment of a restriction digest.
+#[derive(Debug, Clone)]
+pub struct Fragment {
+    /// Both strands of the fragment, the top strand carries the annotations of
+    /// the parent sequence overlapping it, clipped to the fragment.
+    pub dna: DoubleStrandedDna,
+    /// First column of the parent sequence covered by the fragment, including overhangs
+    pub start: usize,
+    /// Last column of the parent sequence covered by the fragment, including overhangs.
+    /// Fragments of circular sequences may wrap around the origin, i.e. `end < start`.
+    pub end: usize,
+    /// The enzyme that cut the left end, `None` for the original end of a linear sequence
+    pub left_enzyme: Option<RestrictionEnzyme>,
+    /// The enzyme that cut the right end, `None` for the original end of a linear sequence
+    pub right_enzyme: Option<RestrictionEnzyme>,
+}
+
+impl Fragment {
+    /// The number of columns spanned by the fragment, including overhangs.
+    pub fn len(&self) -> usize {
+        self.dna.len()
+    }
+
+    /// Whether the fragment contains no nucleotides.
+    pub fn is_empty(&self) -> bool {
+        self.dna.is_empty()
+    }
+
+    /// Whether the fragment is an uncut circular sequence.
+    pub fn is_circular(&self) -> bool {
+        self.dna.is_circular()
+    }
+
+    /// The left end of the fragment, `None` for uncut circular sequences.
+    pub fn left_end(&self) -> Option<DnaEnd> {
+        self.dna.left_end()
+    }
+
+    /// The right end of the fragment, `None` for uncut circular sequences.
+    pub fn right_end(&self) -> Option<DnaEnd> {
+        self.dna.right_end()
+    }
+
+    /// The fragment flipped, so that its bottom strand becomes the top strand.
+    /// The coordinates in the parent sequence stay the same.
+    pub fn reverse_complement(&self) -> Fragment {
+        Fragment {
+            dna: self.dna.reverse_complement(),
+            left_enzyme: self.right_enzyme.clone(),
+            right_enzyme: self.left_enzyme.clone(),
+            ..self.clone()
+        }
+    }
+}
+
+/// A double-strand break in top strand coordinates of the parent sequence.
+#[derive(Debug, Clone)]
+struct Cut {
+    top: isize,
+    bottom: isize,
+    enzyme: Option<RestrictionEnzyme>,
+}
+
+impl Cut {
+    fn end(pos: isize) -> Self {
+        Cut {
+            top: pos,
+            bottom: pos,
+            enzyme: None,
+        }
+    }
+
+    /// Whether a fragment from this cut to `next` keeps at least one base pair.
+    fn is_before(&self, next: &Cut) -> bool {
+        next.top > self.top
+            && next.bottom > self.bottom
+            && next.top.min(next.bottom) > self.top.max(self.bottom)
+    }
+}
+
+/// Digest `seq` with a set of restriction enzymes and return the fragments,
+/// ordered by their position in `seq`.
+///
+/// Circular sequences without any cut are returned as a single circular fragment.
+/// Nicking enzymes don't break the sequence and are ignored, as are cuts outside
+/// of a linear sequence and cuts too close to a previous cut to leave a paired
+/// fragment between them. Methylation is not taken into account, see
+/// `digest_methylated`.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::digest;
+///
+/// let seq = DnaSequence::from_str("ATGAATTCCAGGATCCTA").unwrap();
+///
+/// let fragments = digest(&seq, [RestrictionEnzyme::by_name("EcoRI").unwrap(), RestrictionEnzyme::by_name("BamHI").unwrap()]);
+/// assert_eq!(fragments.len(), 3);
+/// assert_eq!(fragments[1].dna.top().to_nucleotide_string(), "AATTCCAG");
+/// assert_eq!(fragments[1].right_enzyme.as_ref().unwrap().name, "BamHI");
+/// assert_eq!(fragments[1].left_end().unwrap().overhang_type, OverhangType::FivePrime);
+/// assert_eq!((fragments[1].start, fragments[1].end), (3, 14));
+/// ```
+pub fn digest<I, E>(seq: &DnaSequence, enzymes: I) -> Vec<Fragment>
+where
+    I: IntoIterator<Item = E>,
+    E: Borrow<RestrictionEnzyme>,
+{
+    digest_methylated(seq, enzymes, &MethylationProfile::none())
+}
+
+/// Digest `seq` grown in a host with methylation `profile`, like `digest`.
+/// Sites blocked by the methylation are not cut.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::digest_methylated;
+///
+/// let xbai = RestrictionEnzyme::by_name("XbaI").unwrap();
+/// let seq = DnaSequence::from_str("AATCTAGATCAA").unwrap();
+///
+/// assert_eq!(digest_methylated(&seq, [xbai], &MethylationProfile::none()).len(), 2);
+/// assert_eq!(digest_methylated(&seq, [xbai], &MethylationProfile::ecoli()).len(), 1);
+/// ```
+pub fn digest_methylated<I, E>(
+    seq: &DnaSequence,
+    enzymes: I,
+    profile: &MethylationProfile,
+) -> Vec<Fragment>
+where
+    I: IntoIterator<Item = E>,
+    E: Borrow<RestrictionEnzyme>,
+{
+    let enzymes = enzymes.into_iter().collect::<Vec<_>>();
+    let scanner = RestrictionSiteScanner::new(enzymes.iter().map(|e| e.borrow()));
+    let methylated = profile.methylated_nucleotides(seq.as_nucleotides(), seq.topology());
+    let len = seq.len() as isize;
+    let mut cuts = Vec::new();
+    for mut site in scanner.scan(seq.as_nucleotides(), seq.topology()) {
+        let enzyme = enzymes[site.enzyme].borrow();
+        site.flag_blocked(enzyme, &methylated, seq.len());
+        if !site.is_cut() {
+            continue;
+        }
+        for cut in site.cuts(enzyme) {
+            let (Some(top), Some(bottom)) = (cut.top, cut.bottom) else {
+                continue;
+            };
+            let (mut top, mut bottom) = (site.start as isize + top, site.start as isize + bottom);
+            if seq.is_circular() {
+                let shift = top.div_euclid(len) * len;
+                (top, bottom) = (top - shift, bottom - shift);
+            } else if top.min(bottom) <= 0 || top.max(bottom) >= len {
+                continue;
+            }
+            cuts.push(Cut {
+                top,
+                bottom,
+                enzyme: Some(enzyme.clone()),
+            });
+        }
+    }
+    cuts.sort_by_key(|cut| (cut.top, cut.bottom));
+
+    if seq.is_circular() {
+        digest_circular(seq, cuts)
+    } else {
+        digest_linear(seq, cuts)
+    }
+}
+
+fn digest_linear(seq: &DnaSequence, cuts: Vec<Cut>) -> Vec<Fragment> {
+    let mut boundaries = vec![Cut::end(0)];
+    for cut in cuts {
+        if boundaries.last().unwrap().is_before(&cut) {
+            boundaries.push(cut);
+        }
+    }
+    boundaries.push(Cut::end(seq.len() as isize));
+    boundaries
+        .windows(2)
+        .map(|w| fragment(seq, &w[0], &w[1], 0))
+        .collect()
+}
+
+fn digest_circular(seq: &DnaSequence, cuts: Vec<Cut>) -> Vec<Fragment> {
+    let len = seq.len() as isize;
+    let mut boundaries: Vec<Cut> = Vec::new();
+    for cut in cuts {
+        if boundaries.last().is_none_or(|last| last.is_before(&cut)) {
+            boundaries.push(cut);
+        }
+    }
+    let Some(first) = boundaries.first().cloned() else {
+        let mut dna = seq.clone();
+        dna.disable_history();
+        return vec![Fragment {
+            dna: DoubleStrandedDna::new(dna),
+            start: 0,
+            end: seq.len().saturating_sub(1),
+            left_enzyme: None,
+            right_enzyme: None,
+        }];
+    };
+    let closing = Cut {
+        top: first.top + len,
+        bottom: first.bottom + len,
+        ..first
+    };
+    while boundaries.len() > 1 && !boundaries.last().unwrap().is_before(&closing) {
+        boundaries.pop();
+    }
+    boundaries.push(closing);
+
+    // Open the sequence at the first cut, so that all top strands lie within it
+    let origin = boundaries[0].top;
+    let mut opened = seq.clone();
+    opened.disable_history();
+    opened
+        .rotate_to(origin as usize)
+        .expect("cuts lie within the sequence");
+    opened.set_topology(Topology::Linear);
+    let shifted = boundaries
+        .into_iter()
+        .map(|cut| Cut {
+            top: cut.top - origin,
+            bottom: cut.bottom - origin,
+            ..cut
+        })
+        .collect::<Vec<_>>();
+    shifted
+        .windows(2)
+        .map(|w| fragment(&opened, &w[0], &w[1], origin))
+        .collect()
+}
+
+/// The fragment of `seq` between two cuts, `origin` is the column of the parent
+/// sequence at which `seq` starts.
+fn fragment(seq: &DnaSequence, left: &Cut, right: &Cut, origin: isize) -> Fragment {
+    let len = seq.len() as isize;
+    let top = seq.slice(left.top as usize..right.top as usize).extract();
+    let nucleotides = seq.as_nucleotides();
+    let bottom = (left.bottom..right.bottom)
+        .rev()
+        .map(|column| nucleotides[column.rem_euclid(len) as usize].complement())
+        .collect::<Vec<DnaNucleotide>>();
+    let dna = DoubleStrandedDna::from_strands(top, bottom, left.bottom - left.top)
+        .expect("strands between two cuts overlap");
+    let parent = |column: isize| (origin + column).rem_euclid(len) as usize;
+    Fragment {
+        dna,
+        start: parent(left.top.min(left.bottom)),
+        end: parent(right.top.max(right.bottom) - 1),
+        left_enzyme: left.enzyme.clone(),
+        right_enzyme: right.enzyme.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::digest;
+
+    fn circular(s: &str) -> DnaSequence {
+        let mut seq = DnaSequence::from_str(s).unwrap();
+        seq.set_topology(Topology::Circular);
+        seq
+    }
+
+    #[test]
+    fn test_digest_linear() {
+        // PstI: CTGCA/G, leaves 3' overhangs
+        let seq = DnaSequence::from_str("AACTGCAGTTTTCTGCAGAA").unwrap();
+        let fragments = digest(&seq, [RestrictionEnzyme::by_name("PstI").unwrap()]);
+        assert_eq!(fragments.len(), 3);
+        assert_eq!(fragments[0].left_end(), Some(DnaEnd::blunt()));
+        assert_eq!(
+            fragments[0].right_end().unwrap().overhang_type,
+            OverhangType::ThreePrime
+        );
+        assert_eq!(
+            fragments[1].dna.to_string(),
+            "5'     GTTTTCTGCA 3'\n3' ACGTCAAAAG     5'"
+        );
+        assert_eq!((fragments[1].start, fragments[1].end), (3, 16));
+        assert_eq!(fragments[1].left_end(), fragments[0].right_end());
+        assert_eq!(fragments[2].right_enzyme, None);
+        // The parent is covered by the fragments, overhangs overlap
+        let lengths = fragments.iter().map(|f| f.len()).collect::<Vec<_>>();
+        assert_eq!(lengths, [7, 14, 7]);
+    }
+
+    #[test]
+    fn test_digest_uncut() {
+        let seq = circular("ATATATATAT");
+        let fragments = digest(&seq, [RestrictionEnzyme::by_name("EcoRI").unwrap()]);
+        assert_eq!(fragments.len(), 1);
+        assert!(fragments[0].is_circular());
+        assert_eq!(fragments[0].len(), 10);
+
+        let linear = DnaSequence::from_str("ATATATATAT").unwrap();
+        assert_eq!(
+            digest(&linear, [RestrictionEnzyme::by_name("EcoRI").unwrap()])[0].len(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_digest_circular() {
+        // EcoRI site spanning the origin: G/AATTC
+        let mut seq = circular("ATTCTTTTTTGA");
+        seq.as_mut_annotations()
+            .push(Annotation::new(4, 7, None, "spacer"));
+        let fragments = digest(&seq, [RestrictionEnzyme::by_name("EcoRI").unwrap()]);
+        assert_eq!(fragments.len(), 1);
+        let fragment = &fragments[0];
+        assert!(!fragment.is_circular());
+        assert_eq!(fragment.len(), 16);
+        assert_eq!(fragment.dna.top().to_nucleotide_string(), "AATTCTTTTTTG");
+        assert_eq!(fragment.left_end(), fragment.right_end());
+        assert_eq!((fragment.start, fragment.end), (11, 2));
+        assert_eq!(
+            fragment.dna.top().as_annotations(),
+            [Annotation::new(5, 8, None, "spacer")]
+        );
+
+        let mut seq = circular("GAATTCAAAAGGATCCAAAA");
+        let fragments = digest(
+            &seq,
+            [
+                RestrictionEnzyme::by_name("EcoRI").unwrap(),
+                RestrictionEnzyme::by_name("BamHI").unwrap(),
+            ],
+        );
+        let tops = fragments
+            .iter()
+            .map(|f| f.dna.top().to_nucleotide_string())
+            .collect::<Vec<_>>();
+        assert_eq!(tops, ["AATTCAAAAG", "GATCCAAAAG"]);
+        seq.rotate_to(7).unwrap();
+        let rotated = digest(
+            &seq,
+            [
+                RestrictionEnzyme::by_name("EcoRI").unwrap(),
+                RestrictionEnzyme::by_name("BamHI").unwrap(),
+            ],
+        );
+        assert_eq!(rotated[0].dna.top().to_nucleotide_string(), "GATCCAAAAG");
+        assert_eq!(rotated[0].start, 4);
+    }
+
+    #[test]
+    fn test_digest_type_iis() {
+        // BsaI: GGTCTC(N1)/ and reverse site GAGACC, cutting outside of the sites
+        let seq = DnaSequence::from_str("TTGGTCTCAAATGCCCCCCCTGAGTTGAGACCTT").unwrap();
+        let fragments = digest(&seq, [RestrictionEnzyme::by_name("BsaI_HFv2").unwrap()]);
+        assert_eq!(fragments.len(), 3);
+        assert_eq!(
+            fragments[1].dna.top().to_nucleotide_string(),
+            "AATGCCCCCCCT"
+        );
+        assert_eq!(
+            fragments[1].left_end().unwrap().overhang,
+            DnaSequence::from_str("AATG").unwrap().as_nucleotides()
+        );
+        assert_eq!(
+            fragments[1].right_end().unwrap().overhang,
+            DnaSequence::from_str("GAGT").unwrap().as_nucleotides()
+        );
+    }
+
+    #[test]
+    fn test_digest_ignores_nicks() {
+        let seq = DnaSequence::from_str("AACCTCAGCAA").unwrap();
+        assert_eq!(
+            digest(&seq, [RestrictionEnzyme::by_name("NbBbvCI").unwrap()]).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use std::borrow::Borrow;
+
+use crate::{
+    dna::{DnaCodon, DnaNucleotide},
+    eaa::Eaa,
+    seq::{Annotation, DnaSequence, Strand},
+    traits::{Codon, Nucleotide},
+    uni::{RestrictionEnzyme, RestrictionSite, RestrictionSiteScanner},
+};
+
+/// A recognition site that has to be removed from a part.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForbiddenSite {
+    pub enzyme: String,
+    /// Position of the first nucleotide of the site on the top strand
+    pub start: usize,
+    /// The strand the site is located on, `None` for palindromic sites
+    pub strand: Option<Strand>,
+}
+
+/// A codon replaced by a synonymous codon.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SynonymousMutation {
+    /// Top strand position of the first nucleotide of the codon, i.e. of its last
+    /// nucleotide for coding sequences on the reverse strand
+    pub position: usize,
+    /// The strand of the coding sequence
+    pub strand: Strand,
+    /// The original codon, read along the coding sequence
+    pub from: DnaCodon,
+    /// The replacement codon, read along the coding sequence
+    pub to: DnaCodon,
+    /// The amino acid encoded by both codons
+    pub eaa: Eaa,
+}
+
+/// The result of `domesticate`.
+#[derive(Debug, Clone)]
+pub struct Domestication {
+    /// The sequence with all mutations applied
+    pub sequence: DnaSequence,
+    pub mutations: Vec<SynonymousMutation>,
+    /// Sites which can't be removed by synonymous mutations, e.g. sites outside
+    /// of coding sequences
+    pub unresolved: Vec<ForbiddenSite>,
+}
+
+/// Check that the coding sequences lie within `seq`, don't wrap around the
+/// origin and span whole codons.
+fn check_coding_sequences(seq: &DnaSequence, cds: &[Annotation]) -> anyhow::Result<()> {
+    for cds in cds {
+        if cds.is_wrapping() || cds.end >= seq.len() {
+            bail!(
+                "The coding sequence {} at {}..{} doesn't lie within the sequence",
+                cds.text,
+                cds.start,
+                cds.end
+            );
+        }
+        if (cds.end + 1 - cds.start) % 3 != 0 {
+            bail!(
+                "The coding sequence {} at {}..{} doesn't span whole codons",
+                cds.text,
+                cds.start,
+                cds.end
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Top strand positions of the codons of a coding sequence, in reading order.
+fn codon_positions(cds: &Annotation) -> Vec<usize> {
+    let count = (cds.end + 1 - cds.start) / 3;
+    match cds.strand {
+        Some(Strand::Reverse) => (0..count).map(|i| cds.end - 3 * i - 2).collect(),
+        _ => (0..count).map(|i| cds.start + 3 * i).collect(),
+    }
+}
+
+fn read_codon(nucleotides: &[DnaNucleotide], position: usize, strand: Strand) -> DnaCodon {
+    let [a, b, c] = [0, 1, 2].map(|i| nucleotides[position + i]);
+    match strand {
+        Strand::Forward => DnaCodon::from_triplet_arr([a, b, c]),
+        Strand::Reverse => DnaCodon::from_triplet_arr([c, b, a].map(|n| n.complement())),
+    }
+}
+
+fn write_codon(
+    nucleotides: &mut [DnaNucleotide],
+    position: usize,
+    strand: Strand,
+    codon: &DnaCodon,
+) {
+    let [a, b, c] = codon.to_triplet_arr();
+    let top = match strand {
+        Strand::Forward => [a, b, c],
+        Strand::Reverse => [c, b, a].map(|n| n.complement()),
+    };
+    nucleotides[position..position + 3].copy_from_slice(&top);
+}
+
+/// The proteins encoded by the coding sequences.
+fn translate(nucleotides: &[DnaNucleotide], cds: &[Annotation]) -> Vec<Vec<Eaa>> {
+    cds.iter()
+        .map(|cds| {
+            let strand = cds.strand.unwrap_or(Strand::Forward);
+            codon_positions(cds)
+                .into_iter()
+                .map(|pos| read_codon(nucleotides, pos, strand).translate())
+                .collect()
+        })
+        .collect()
+}
+
+/// All codons encoding the same amino acid as `codon`, except `codon` itself.
+fn synonymous_codons(codon: &DnaCodon) -> Vec<DnaCodon> {
+    use DnaNucleotide::*;
+    let bases = [A, C, G, T];
+    let eaa = codon.translate();
+    (0..64)
+        .map(|i| DnaCodon::from_triplet_arr([bases[i / 16], bases[i / 4 % 4], bases[i % 4]]))
+        .filter(|other| other != codon && other.translate() == eaa)
+        .collect()
+}
+
+/// Remove the recognition sites of `enzymes` from the coding sequences `cds` of
+/// `seq` by synonymous codon changes, e.g. to domesticate a part for Golden Gate assembly.
+///
+/// The coding sequences are read on their strand, the forward strand if they
+/// have none, and have to span whole codons. Sites on both strands are removed one at a time, each by the replacement of
+/// a single codon changing the fewest nucleotides. Replacements are only made
+/// if all coding sequences keep their protein, translated like `DnaCodon::translate`,
+/// and if no new site of any of the enzymes is created.
+///
+/// Fails for coding sequences which wrap around the origin, exceed `seq` or
+/// don't span whole codons.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::domesticate;
+///
+/// let bsai = RestrictionEnzyme::by_name("BsaI_HFv2").unwrap();
+/// // GGT CTC encodes Gly-Leu and contains a BsaI site
+/// let seq = DnaSequence::from_str("ATGGGTCTCAAATAA").unwrap();
+/// let cds = Annotation::new(0, 14, None, "CDS").with_strand(Strand::Forward);
+///
+/// let domestication = domesticate(&seq, &[cds], [bsai]).unwrap();
+/// assert_eq!(domestication.mutations.len(), 1);
+/// assert!(domestication.unresolved.is_empty());
+/// assert_eq!(domestication.sequence.translate(), seq.translate());
+/// assert_ne!(domestication.sequence.to_nucleotide_string(), seq.to_nucleotide_string());
+/// ```
+pub fn domesticate<I, E>(
+    seq: &DnaSequence,
+    cds: &[Annotation],
+    enzymes: I,
+) -> anyhow::Result<Domestication>
+where
+    I: IntoIterator<Item = E>,
+    E: Borrow<RestrictionEnzyme>,
+{
+    let enzymes = enzymes.into_iter().collect::<Vec<_>>();
+    let scanner = RestrictionSiteScanner::new(enzymes.iter().map(|e| e.borrow()));
+    let topology = seq.topology();
+    let scan = |nucleotides: &[DnaNucleotide]| scanner.scan(nucleotides, topology);
+    let key = |site: &RestrictionSite| ForbiddenSite {
+        enzyme: enzymes[site.enzyme].borrow().name.clone(),
+        start: site.start,
+        strand: site.strand,
+    };
+
+    check_coding_sequences(seq, cds)?;
+    let proteins = translate(seq.as_nucleotides(), cds);
+    let len = seq.len();
+    let mut nucleotides = seq.as_nucleotides().to_vec();
+    let mut mutations = Vec::new();
+    let mut unresolved = Vec::new();
+    loop {
+        let found = scan(&nucleotides);
+        let sites = found.iter().map(key).collect::<Vec<_>>();
+        let Some(index) = sites.iter().position(|site| !unresolved.contains(site)) else {
+            break;
+        };
+        let site = &sites[index];
+        let site_len = enzymes[found[index].enzyme]
+            .borrow()
+            .recognition_site()
+            .len();
+        let in_site = |pos: usize| (pos + len - site.start) % len < site_len;
+        // Codons overlapping the site, with the strand of their coding sequence
+        let codons = cds
+            .iter()
+            .flat_map(|cds| {
+                let strand = cds.strand.unwrap_or(Strand::Forward);
+                codon_positions(cds)
+                    .into_iter()
+                    .filter(|&pos| (pos..pos + 3).any(in_site))
+                    .map(move |pos| (pos, strand))
+            })
+            .collect::<Vec<_>>();
+
+        let mut best: Option<(usize, SynonymousMutation, Vec<DnaNucleotide>)> = None;
+        for &(position, strand) in codons.iter() {
+            let from = read_codon(&nucleotides, position, strand);
+            for to in synonymous_codons(&from) {
+                let changes = from
+                    .to_triplet_arr()
+                    .iter()
+                    .zip(to.to_triplet_arr())
+                    .filter(|(a, b)| *a != b)
+                    .count();
+                if best.as_ref().is_some_and(|(best, _, _)| *best <= changes) {
+                    continue;
+                }
+                let mut mutated = nucleotides.clone();
+                write_codon(&mut mutated, position, strand, &to);
+                let remaining = scan(&mutated).iter().map(key).collect::<Vec<_>>();
+                if remaining.contains(site)
+                    || remaining.iter().any(|s| !sites.contains(s))
+                    || translate(&mutated, cds) != proteins
+                {
+                    continue;
+                }
+                let mutation = SynonymousMutation {
+                    position,
+                    strand,
+                    eaa: from.translate(),
+                    from: from.clone(),
+                    to,
+                };
+                best = Some((changes, mutation, mutated));
+            }
+        }
+        match best {
+            Some((_, mutation, mutated)) => {
+                mutations.push(mutation);
+                nucleotides = mutated;
+            }
+            None => unresolved.push(site.clone()),
+        }
+    }
+
+    // Substitutions keep the annotations, which replacing ranges would split
+    let mut sequence = nucleotides.into_iter().collect::<DnaSequence>();
+    sequence.set_topology(topology);
+    sequence
+        .as_mut_annotations()
+        .extend(seq.annotation_iter().cloned());
+    Ok(Domestication {
+        sequence,
+        mutations,
+        unresolved,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{domesticate, ForbiddenSite};
+
+    #[test]
+    fn test_domesticate_both_strands() {
+        // BsaI GGTCTC in frame and its reverse complement GAGACC on the reverse
+        // strand CDS, BsmBI CGTCTC within the forward CDS
+        let mut seq = DnaSequence::from_str("ATGGGTCTCCGTCTCAAATAACCCTTAGAGACCTTTCAT").unwrap();
+        let cds = [
+            Annotation::new(0, 20, None, "gene").with_strand(Strand::Forward),
+            Annotation::new(21, 38, None, "reverse gene").with_strand(Strand::Reverse),
+        ];
+        seq.as_mut_annotations().extend(cds.clone());
+        let forbidden = [
+            RestrictionEnzyme::by_name("BsaI_HFv2").unwrap(),
+            RestrictionEnzyme::by_name("BsmBI_v2").unwrap(),
+        ];
+        let domestication = domesticate(&seq, &cds, forbidden).unwrap();
+        assert!(domestication.unresolved.is_empty());
+        assert_eq!(domestication.mutations.len(), 3);
+        assert!(domestication
+            .mutations
+            .iter()
+            .all(|m| m.from.translate() == m.to.translate()));
+
+        let domesticated = &domestication.sequence;
+        assert_eq!(domesticated.as_annotations(), seq.as_annotations());
+        assert!(RestrictionSiteScanner::new(forbidden)
+            .scan(domesticated.as_nucleotides(), Topology::Linear)
+            .is_empty());
+        let protein = |s: &DnaSequence, range: std::ops::Range<usize>, reverse: bool| {
+            let mut part = s.slice(range).extract();
+            if reverse {
+                part = part.reverse_complement();
+            }
+            part.translate()
+        };
+        assert_eq!(
+            protein(domesticated, 0..21, false),
+            protein(&seq, 0..21, false)
+        );
+        assert_eq!(
+            protein(domesticated, 21..39, true),
+            protein(&seq, 21..39, true)
+        );
+    }
+
+    #[test]
+    fn test_domesticate_outside_cds() {
+        let mut seq = DnaSequence::from_str("AAGGTCTCAA").unwrap();
+        // Stranded annotations spanning whole codons aren't taken for coding sequences
+        seq.as_mut_annotations()
+            .push(Annotation::new(2, 7, None, "BsaI").with_strand(Strand::Forward));
+        let bsai = RestrictionEnzyme::by_name("BsaI_HFv2").unwrap();
+        let domestication = domesticate(&seq, &[], [bsai]).unwrap();
+        assert!(domestication.mutations.is_empty());
+        assert_eq!(
+            domestication.unresolved,
+            [ForbiddenSite {
+                enzyme: "BsaI_HFv2".to_string(),
+                start: 2,
+                strand: Some(Strand::Forward),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_domesticate_invalid_cds() {
+        let seq = DnaSequence::from_str("ATGGGTCTCAAATAA").unwrap();
+        let bsai = RestrictionEnzyme::by_name("BsaI_HFv2").unwrap();
+        let error = |cds: Annotation| domesticate(&seq, &[cds], [bsai]).unwrap_err().to_string();
+        assert!(error(Annotation::new(0, 13, None, "CDS")).contains("whole codons"));
+        assert!(error(Annotation::new(3, 17, None, "CDS")).contains("within"));
+    }
+}
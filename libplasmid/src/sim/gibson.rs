@@ -0,0 +1,557 @@
+use std::ops::Range;
+
+use crate::{
+    dna::DnaNucleotide,
+    seq::{Annotation, DnaSequence, Topology},
+    traits::Nucleotide,
+};
+
+use super::{melting_temperature, Primer, PrimerPair, TmOptions};
+
+/// Options for homology-based assembly, like Gibson or NEBuilder HiFi assembly.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HomologyOptions {
+    /// Minimum length of an overlap
+    pub min_overlap: usize,
+    /// Maximum length of an overlap
+    pub max_overlap: usize,
+    /// Minimum melting temperature of an overlap, in °C
+    pub overlap_tm: f64,
+    /// Minimum melting temperature of the binding region of designed primers, in °C
+    pub primer_tm: f64,
+    /// Maximum length of the binding region of designed primers
+    pub max_binding: usize,
+    pub tm: TmOptions,
+}
+
+impl HomologyOptions {
+    pub fn with_overlap(mut self, min: usize, max: usize) -> Self {
+        self.min_overlap = min;
+        self.max_overlap = max;
+        self
+    }
+
+    pub fn with_overlap_tm(mut self, tm: f64) -> Self {
+        self.overlap_tm = tm;
+        self
+    }
+
+    pub fn with_primer_tm(mut self, tm: f64) -> Self {
+        self.primer_tm = tm;
+        self
+    }
+
+    pub fn with_max_binding(mut self, max_binding: usize) -> Self {
+        self.max_binding = max_binding;
+        self
+    }
+
+    pub fn with_tm_options(mut self, tm: TmOptions) -> Self {
+        self.tm = tm;
+        self
+    }
+}
+
+impl Default for HomologyOptions {
+    /// Overlaps of 15 to 60 nucleotides melting at 48 °C or above, primers
+    /// binding with up to 40 nucleotides at 58 °C or above.
+    fn default() -> Self {
+        Self {
+            min_overlap: 15,
+            max_overlap: 60,
+            overlap_tm: 48.0,
+            primer_tm: 58.0,
+            max_binding: 40,
+            tm: TmOptions::default(),
+        }
+    }
+}
+
+/// The overlap joining the end of one fragment to the start of the next one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlap {
+    /// Index of the fragment whose end overlaps
+    pub left: usize,
+    /// Index of the fragment whose start overlaps
+    pub right: usize,
+    /// Position of the first nucleotide of the overlap in the product
+    pub position: usize,
+    pub len: usize,
+    /// Melting temperature of the overlap, in °C
+    pub tm: f64,
+    /// Whether the end of `left` or the start of `right` also overlaps
+    /// another fragment
+    pub ambiguous: bool,
+    /// Whether the overlap also occurs elsewhere in the fragments, on either strand
+    pub mispriming: bool,
+}
+
+/// The product of a homology-based assembly.
+#[derive(Debug, Clone)]
+pub struct HomologyAssembly {
+    /// The assembled sequence with the annotations of all fragments
+    pub product: DnaSequence,
+    /// The overlaps between consecutive fragments. The overlap closing a circular
+    /// product is the last one.
+    pub overlaps: Vec<Overlap>,
+}
+
+impl HomologyAssembly {
+    /// Whether any of the overlaps is ambiguous or may misprime.
+    pub fn has_warnings(&self) -> bool {
+        self.overlaps.iter().any(|o| o.ambiguous || o.mispriming)
+    }
+}
+
+/// The longest overlap of the end of `left` with the start of `right` within
+/// the limits of `options`, with its melting temperature.
+fn terminal_overlap(
+    left: &[DnaNucleotide],
+    right: &[DnaNucleotide],
+    options: &HomologyOptions,
+) -> Option<(usize, f64)> {
+    let max = options.max_overlap.min(left.len()).min(right.len());
+    (options.min_overlap..=max)
+        .rev()
+        .find(|&len| left[left.len() - len..] == right[..len])
+        .map(|len| (len, melting_temperature(&right[..len], &options.tm)))
+        .filter(|&(_, tm)| tm >= options.overlap_tm)
+}
+
+/// Whether `ann` lies within the overlap `range` of the product and is also among
+/// `annotations`, i.e. is the copy of an annotation of the neighboring fragment
+/// which carries the same overlap.
+fn is_overlap_copy(ann: &Annotation, range: &Range<usize>, annotations: &[Annotation]) -> bool {
+    range.contains(&ann.start) && range.contains(&ann.end) && annotations.contains(ann)
+}
+
+/// Number of occurrences of `pattern` in `seq` on either strand.
+fn occurrences(seq: &DnaSequence, pattern: &[DnaNucleotide]) -> usize {
+    let reverse = pattern
+        .iter()
+        .rev()
+        .map(|n| n.complement())
+        .collect::<Vec<_>>();
+    let forward = seq.find_pattern(pattern).len();
+    if reverse == pattern {
+        forward
+    } else {
+        forward + seq.find_pattern(&reverse).len()
+    }
+}
+
+/// Simulate the homology-based assembly of `fragments` in the given order and
+/// orientation, e.g. PCR products with overlapping ends.
+///
+/// The end of each fragment has to overlap the start of the next one by an
+/// identical sequence within the length and melting temperature limits of
+/// `options`, the longest such overlap is used. The product is circular if the
+/// last fragment also overlaps the first one, a single fragment is circularized
+/// if its end overlaps its start. Overlaps are flagged as ambiguous
+/// if their fragment ends also overlap other fragments, and as mispriming if
+/// their sequence also occurs elsewhere in the fragments.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::gibson_assembly;
+///
+/// let fragments = [
+///     DnaSequence::from_str("TTTTTTTTTTGCGACGTACCTGAGCATCAA").unwrap(),
+///     DnaSequence::from_str("GCGACGTACCTGAGCATCAAAAAAACCCCC").unwrap(),
+/// ];
+/// let options = HomologyOptions::default().with_overlap(15, 30);
+/// let assembly = gibson_assembly(&fragments, &options).unwrap();
+/// assert_eq!(assembly.product.to_string(), "TTTTTTTTTTGCGACGTACCTGAGCATCAAAAAAACCCCC");
+/// assert_eq!(assembly.product.topology(), Topology::Linear);
+/// assert_eq!((assembly.overlaps[0].position, assembly.overlaps[0].len), (10, 20));
+/// ```
+pub fn gibson_assembly(
+    fragments: &[DnaSequence],
+    options: &HomologyOptions,
+) -> anyhow::Result<HomologyAssembly> {
+    if fragments.is_empty() {
+        bail!("No fragments to assemble");
+    }
+    let count = fragments.len();
+    // Overlaps of the end of every fragment with the start of every fragment.
+    // The overlap of a fragment with itself has to leave at least one nucleotide.
+    let matrix = fragments
+        .iter()
+        .enumerate()
+        .map(|(i, left)| {
+            fragments
+                .iter()
+                .enumerate()
+                .map(|(j, right)| {
+                    let right = right.as_nucleotides();
+                    let right = if i == j {
+                        &right[..right.len() - 1]
+                    } else {
+                        right
+                    };
+                    terminal_overlap(left.as_nucleotides(), right, options)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let closing = matrix[count - 1][0];
+    let junctions = (0..count - 1)
+        .map(|i| (i, i + 1))
+        .chain(closing.map(|_| (count - 1, 0)));
+
+    let mut product = fragments[0].clone();
+    product.disable_history();
+    product.set_topology(Topology::Linear);
+    let mut overlaps = Vec::new();
+    for (left, right) in junctions {
+        let Some((len, tm)) = matrix[left][right] else {
+            bail!(
+                "The end of fragment {} doesn't overlap the start of fragment {}",
+                left + 1,
+                right + 1
+            );
+        };
+        let ambiguous = (0..count)
+            .filter(|&other| other != left && other != right)
+            .any(|other| matrix[left][other].is_some() || matrix[other][right].is_some());
+        // Once at the end of `left` and once at the start of `right`
+        let sequence = &fragments[right].as_nucleotides()[..len];
+        let mispriming = fragments
+            .iter()
+            .map(|f| occurrences(f, sequence))
+            .sum::<usize>()
+            > 2;
+        let position = product.len() - len;
+        if right != 0 {
+            let fragment = &fragments[right];
+            product.insert_bases(product.len(), fragment.nucleotide_iter().skip(len));
+            let copies = position..position + len;
+            let added = fragment
+                .annotation_iter()
+                .map(|ann| Annotation {
+                    start: ann.start + position,
+                    end: ann.end + position,
+                    ..ann.map_needles(|n| Some(n + position))
+                })
+                .filter(|ann| !is_overlap_copy(ann, &copies, product.as_annotations()))
+                .collect::<Vec<_>>();
+            product.as_mut_annotations().extend(added);
+        }
+        overlaps.push(Overlap {
+            left,
+            right,
+            position,
+            len,
+            tm,
+            ambiguous,
+            mispriming,
+        });
+    }
+    if let Some(closing) = overlaps.last_mut().filter(|o| o.right == 0) {
+        // The end of the last fragment already is the start of the first one.
+        // The annotations are taken out while trimming it, so that annotations
+        // reaching into it wrap around the origin instead of being clipped.
+        let annotations = std::mem::take(product.as_mut_annotations());
+        let len = product.len();
+        product.remove_range(len - closing.len..len);
+        product.set_topology(Topology::Circular);
+        let len = product.len();
+        closing.position %= len;
+        let (mut kept, wrapped): (Vec<_>, Vec<_>) =
+            annotations.into_iter().partition(|ann| ann.end < len);
+        let copies = 0..closing.len;
+        for ann in wrapped {
+            let ann = Annotation {
+                start: ann.start % len,
+                end: ann.end % len,
+                ..ann.map_needles(|n| Some(n % len))
+            };
+            if !is_overlap_copy(&ann, &copies, &kept) {
+                kept.push(ann);
+            }
+        }
+        *product.as_mut_annotations() = kept;
+    }
+    Ok(HomologyAssembly { product, overlaps })
+}
+
+/// Primers to amplify a part for a homology-based assembly.
+#[derive(Debug, Clone)]
+pub struct AssemblyPrimers {
+    /// Index of the amplified part
+    pub part: usize,
+    /// The primers, with tails overlapping the neighboring parts
+    pub primers: PrimerPair,
+    /// The amplified part including the tails
+    pub amplicon: DnaSequence,
+}
+
+/// The shortest prefix of `seq` melting at `tm` or above, within `max` nucleotides.
+fn binding_region(seq: &[DnaNucleotide], options: &HomologyOptions) -> anyhow::Result<usize> {
+    let max = options.max_binding.min(seq.len());
+    (12..=max)
+        .find(|&len| melting_temperature(&seq[..len], &options.tm) >= options.primer_tm)
+        .ok_or_else(|| {
+            anyhow!(
+                "No primer of up to {} nucleotides melts at {} °C",
+                max,
+                options.primer_tm
+            )
+        })
+}
+
+/// Design the primers to assemble `parts` in the given order with a homology-based
+/// assembly, e.g. Gibson assembly. For `circular` assemblies the last part is
+/// joined to the first one.
+///
+/// Each junction gets an overlap of at least `min_overlap` nucleotides melting at
+/// `overlap_tm`, centered on the junction. The reverse primer of the left part
+/// carries the start of the overlap and the forward primer of the right part its
+/// end, as 5' tails. The amplicons assemble into the joined parts, see
+/// `gibson_assembly`.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::{design_gibson_primers, gibson_assembly};
+///
+/// let parts = [
+///     DnaSequence::from_str("ATGGCTAGCAAAGGAGAAGAACTTTTCACTGGAGTTGTCCCAATTCTTGTTGAATTAGATGGT").unwrap(),
+///     DnaSequence::from_str("GATGTTAATGGGCACAAATTTTCTGTCAGTGGAGAGGGTGAAGGTGATGCAACATACGGAAAA").unwrap(),
+/// ];
+/// let options = HomologyOptions::default();
+/// let designs = design_gibson_primers(&parts, false, &options).unwrap();
+/// assert_eq!(designs.len(), 2);
+/// assert!(designs[0].primers.forward.tail.is_empty());
+/// assert!(!designs[0].primers.reverse.tail.is_empty());
+///
+/// let amplicons = designs.iter().map(|d| d.amplicon.clone()).collect::<Vec<_>>();
+/// let assembly = gibson_assembly(&amplicons, &options).unwrap();
+/// assert_eq!(assembly.product.to_string(), format!("{}{}", parts[0], parts[1]));
+/// ```
+pub fn design_gibson_primers(
+    parts: &[DnaSequence],
+    circular: bool,
+    options: &HomologyOptions,
+) -> anyhow::Result<Vec<AssemblyPrimers>> {
+    let count = parts.len();
+    let reverse_complement =
+        |seq: &[DnaNucleotide]| seq.iter().rev().map(|n| n.complement()).collect::<Vec<_>>();
+    // Lengths of the start and the end of the overlap for each junction, taken
+    // from the left and the right part
+    let mut junctions = Vec::new();
+    for i in 0..count {
+        let j = (i + 1) % count;
+        if j == 0 && !circular {
+            junctions.push(None);
+            continue;
+        }
+        let (left, right) = (parts[i].as_nucleotides(), parts[j].as_nucleotides());
+        let split = (options.min_overlap..=options.max_overlap)
+            .map(|len| (len - len / 2, len / 2))
+            .filter(|&(a, b)| a <= left.len() && b <= right.len())
+            .find(|&(a, b)| {
+                let overlap = [&left[left.len() - a..], &right[..b]].concat();
+                melting_temperature(&overlap, &options.tm) >= options.overlap_tm
+            });
+        match split {
+            Some(split) => junctions.push(Some(split)),
+            None => bail!(
+                "No overlap between part {} and part {} melts at {} °C",
+                i + 1,
+                j + 1,
+                options.overlap_tm
+            ),
+        }
+    }
+
+    let mut designs = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let seq = part.as_nucleotides();
+        let previous = if i == 0 { count - 1 } else { i - 1 };
+        let forward_tail = match junctions[previous] {
+            Some((a, _)) => {
+                let previous = parts[previous].as_nucleotides();
+                previous[previous.len() - a..].to_vec()
+            }
+            None => Vec::new(),
+        };
+        let reverse_tail = match junctions[i] {
+            Some((_, b)) => reverse_complement(&parts[(i + 1) % count].as_nucleotides()[..b]),
+            None => Vec::new(),
+        };
+        let forward = binding_region(seq, options)?;
+        let reverse_seq = reverse_complement(seq);
+        let reverse = binding_region(&reverse_seq, options)?;
+        let primers = PrimerPair {
+            forward: Primer::new(&seq[..forward]).with_tail(&forward_tail),
+            reverse: Primer::new(&reverse_seq[..reverse]).with_tail(&reverse_tail),
+        };
+        let mut amplicon = forward_tail.iter().copied().collect::<DnaSequence>();
+        amplicon.insert_bases(amplicon.len(), seq.iter());
+        amplicon.insert_bases(amplicon.len(), reverse_complement(&reverse_tail).iter());
+        designs.push(AssemblyPrimers {
+            part: i,
+            primers,
+            amplicon,
+        });
+    }
+    Ok(designs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{design_gibson_primers, gibson_assembly};
+
+    fn seq(s: &str) -> DnaSequence {
+        DnaSequence::from_str(s).unwrap()
+    }
+
+    const A: &str = "ATGGCTAGCAAAGGAGAAGAACTTTTCACTGGAGTTGTCCCAATTCTTGTTGAATTAGATGGT";
+    const B: &str = "GATGTTAATGGGCACAAATTTTCTGTCAGTGGAGAGGGTGAAGGTGATGCAACATACGGAAAA";
+    const C: &str = "CTTACCCTTAAATTTATTTGCACTACTGGAAAACTACCTGTTCCATGGCCAACACTTGTCACT";
+
+    #[test]
+    fn test_gibson_circular() {
+        let parts = [seq(A), seq(B), seq(C)];
+        let options = HomologyOptions::default();
+        let designs = design_gibson_primers(&parts, true, &options).unwrap();
+        let mut amplicons = designs
+            .iter()
+            .map(|d| d.amplicon.clone())
+            .collect::<Vec<_>>();
+        amplicons[1]
+            .as_mut_annotations()
+            .push(Annotation::new(0, 9, None, "start of B"));
+
+        let assembly = gibson_assembly(&amplicons, &options).unwrap();
+        assert!(assembly.product.is_circular());
+        assert!(!assembly.has_warnings());
+        assert_eq!(assembly.overlaps.len(), 3);
+        assert!(assembly.overlaps.iter().all(|o| o.tm >= 48.0));
+        let mut expected = seq(&format!("{A}{B}{C}"));
+        expected.set_topology(Topology::Circular);
+        // The product starts with the tail of the first amplicon
+        let offset = designs[0].primers.forward.tail.len();
+        let product = &assembly.product;
+        assert_eq!(product.len(), expected.len());
+        let rotated = (0..product.len())
+            .map(|i| product[(i + offset) % product.len()])
+            .collect::<DnaSequence>();
+        assert_eq!(rotated.to_string(), expected.to_string());
+        // The annotation starts in the overlap with the first part
+        let ann = &product.as_annotations()[0];
+        assert_eq!(ann.text, "start of B");
+        assert_eq!(ann.start, assembly.overlaps[0].position);
+        // The closing overlap is the start of the product
+        assert_eq!(assembly.overlaps[2].position, 0);
+        assert!(assembly.overlaps.iter().all(|o| o.position < product.len()));
+    }
+
+    #[test]
+    fn test_gibson_single_fragment() {
+        let options = HomologyOptions::default().with_overlap(15, 30);
+        let overlap = "GCGACGTACCTGAGCATCAA";
+        let mut fragment = seq(&format!("{overlap}{A}{overlap}"));
+        let end = overlap.len() + A.len();
+        fragment.as_mut_annotations().extend([
+            Annotation::new(0, 19, None, "overlap"),
+            Annotation::new(end, end + 19, None, "overlap"),
+            Annotation::new(20, 25, None, "A"),
+            Annotation::new(20, 25, None, "A"),
+        ]);
+
+        let assembly = gibson_assembly(&[fragment], &options).unwrap();
+        let product = &assembly.product;
+        assert!(product.is_circular());
+        assert_eq!(product.len(), end);
+        assert_eq!(assembly.overlaps.len(), 1);
+        let closing = &assembly.overlaps[0];
+        assert_eq!((closing.left, closing.right), (0, 0));
+        assert_eq!((closing.position, closing.len), (0, 20));
+        assert!(!assembly.has_warnings());
+        // The copy in the overlap is dropped, identical annotations elsewhere are kept
+        assert_eq!(
+            product.as_annotations(),
+            [
+                Annotation::new(0, 19, None, "overlap"),
+                Annotation::new(20, 25, None, "A"),
+                Annotation::new(20, 25, None, "A"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gibson_single_fragment_without_overlap() {
+        let options = HomologyOptions::default().with_overlap(15, 30);
+        let assembly = gibson_assembly(&[seq(A)], &options).unwrap();
+        assert!(!assembly.product.is_circular());
+        assert!(assembly.overlaps.is_empty());
+    }
+
+    #[test]
+    fn test_gibson_circular_annotation_across_closing_overlap() {
+        let options = HomologyOptions::default().with_overlap(15, 30);
+        let overlap = "GCGACGTACCTGAGCATCAA";
+        let inner = "TTGACCGATAGCTCGGAACT";
+        let mut first = seq(&format!("{overlap}{A}{inner}"));
+        let mut last = seq(&format!("{inner}{B}{overlap}"));
+        // From the end of B into the overlap closing the circle
+        let end = inner.len() + B.len();
+        last.as_mut_annotations()
+            .push(Annotation::new(end - 5, end + 19, None, "junction"));
+        first
+            .as_mut_annotations()
+            .push(Annotation::new(0, 19, None, "overlap"));
+
+        let assembly = gibson_assembly(&[first, last], &options).unwrap();
+        let product = &assembly.product;
+        assert!(product.is_circular());
+        assert_eq!(
+            product.len(),
+            overlap.len() + A.len() + inner.len() + B.len()
+        );
+        let junction = product
+            .annotation_iter()
+            .find(|ann| ann.text == "junction")
+            .unwrap();
+        // Wraps around the origin, keeping all 25 nucleotides
+        assert_eq!((junction.start, junction.end), (product.len() - 5, 19));
+        let overlap = product
+            .annotation_iter()
+            .filter(|ann| ann.text == "overlap")
+            .collect::<Vec<_>>();
+        assert_eq!(overlap.len(), 1);
+        assert_eq!((overlap[0].start, overlap[0].end), (0, 19));
+    }
+
+    #[test]
+    fn test_gibson_warnings_and_errors() {
+        let options = HomologyOptions::default().with_overlap(15, 30);
+        let overlap = "GCGACGTACCTGAGCATCAA";
+        let left = seq(&format!("TTTTTTTTTT{overlap}"));
+        // The end of the first fragment also overlaps the start of the third one
+        let middle = seq(&format!("{overlap}CCCCCCCCCC{overlap}"));
+        let last = seq(&format!("{overlap}GGGGGGGGGG"));
+        let assembly = gibson_assembly(&[left.clone(), middle, last], &options).unwrap();
+        assert_eq!(assembly.overlaps.len(), 2);
+        assert!(assembly
+            .overlaps
+            .iter()
+            .all(|o| o.ambiguous && o.mispriming));
+        assert!(assembly.has_warnings());
+
+        let right = seq(&format!("{overlap}CCCCCCCCCC"));
+        let assembly = gibson_assembly(&[left.clone(), right.clone()], &options).unwrap();
+        assert!(!assembly.has_warnings());
+        let repeat = seq(&format!("CCCCC{overlap}CCCCC"));
+        let assembly = gibson_assembly(&[left.clone(), right, repeat], &options);
+        assert!(assembly.is_err());
+
+        let unrelated = seq("ACACACACACACACACACACACACA");
+        assert!(gibson_assembly(&[left, unrelated], &options).is_err());
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{
+    dna::DnaNucleotide,
+    seq::{DnaEnd, DnaSequence, OverhangType, Strand, Topology},
+    traits::{Nucleotide, ToLetter},
+    uni::{RestrictionEnzyme, RestrictionSiteScanner},
+};
+
+use super::{close, digest, join, junction, Fragment, Ligation, LigationPart};
+
+/// Relative ligation frequencies of the base pairs formed by annealing overhangs,
+/// indexed by the nucleotides of both strands in the order A, C, G, T.
+///
+/// Watson-Crick pairs ligate with a frequency of 1, the mismatches approximate
+/// the frequencies observed for T4 DNA ligase by Potapov et al. (2018), where
+/// G:T wobble pairs are by far the most tolerated.
+const MISMATCH_LIGATION: [[f64; 4]; 4] = [
+    // A      C      G      T
+    [0.001, 0.005, 0.02, 1.0],  // A
+    [0.005, 0.001, 1.0, 0.005], // C
+    [0.02, 1.0, 0.001, 0.1],    // G
+    [1.0, 0.005, 0.1, 0.02],    // T
+];
+
+fn index(n: DnaNucleotide) -> usize {
+    match n {
+        DnaNucleotide::A => 0,
+        DnaNucleotide::C => 1,
+        DnaNucleotide::G => 2,
+        DnaNucleotide::T => 3,
+    }
+}
+
+fn reverse_complement(overhang: &[DnaNucleotide]) -> Vec<DnaNucleotide> {
+    overhang.iter().rev().map(|n| n.complement()).collect()
+}
+
+/// Relative frequency of the ligation of two single-stranded overhangs, both
+/// read 5' to 3'.
+fn ligation_frequency(a: &[DnaNucleotide], b: &[DnaNucleotide]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter()
+        .zip(b.iter().rev())
+        .map(|(&a, &b)| MISMATCH_LIGATION[index(a)][index(b)])
+        .product()
+}
+
+/// The probability of all `overhangs` ligating only to their intended partners.
+///
+/// Each overhang, given as read along the top strand, may anneal to both strands
+/// of every overhang of the set. The fidelity of a strand is the share of its
+/// correct ligations among all of its ligations, using a built-in table of
+/// mismatch ligation frequencies. The fidelity of an overhang is the geometric
+/// mean of the fidelities of its strands, the fidelity of the set is the product
+/// of the fidelities of its overhangs.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::overhang_fidelity;
+///
+/// let overhangs = |s: &[&str]| {
+///     s.iter()
+///         .map(|o| DnaSequence::from_str(o).unwrap().as_nucleotides().to_vec())
+///         .collect::<Vec<_>>()
+/// };
+/// let distinct = overhang_fidelity(&overhangs(&["GGAG", "AATG", "GCTT", "CGCT"]));
+/// // AGTG anneals to CACT with a single G:T wobble pair
+/// let wobbly = overhang_fidelity(&overhangs(&["GGAG", "AGTG", "CACT", "CGCT"]));
+/// assert!(distinct > 0.99);
+/// assert!(wobbly < distinct);
+/// ```
+pub fn overhang_fidelity(overhangs: &[Vec<DnaNucleotide>]) -> f64 {
+    let strands = overhangs
+        .iter()
+        .flat_map(|o| [o.clone(), reverse_complement(o)])
+        .collect::<Vec<_>>();
+    strands
+        .iter()
+        .map(|strand| {
+            let correct = ligation_frequency(strand, &reverse_complement(strand));
+            let total = strands
+                .iter()
+                .map(|other| ligation_frequency(strand, other))
+                .sum::<f64>();
+            correct / total
+        })
+        .product::<f64>()
+        .sqrt()
+}
+
+/// The product of a Golden Gate assembly.
+#[derive(Debug, Clone)]
+pub struct GoldenGateAssembly {
+    /// The assembled plasmid, starting with the vector backbone. Its parts refer
+    /// to the vector as fragment 0 and to the parts as fragments 1 and following.
+    pub product: Ligation,
+    /// The fidelity of the overhangs of the assembly, see `overhang_fidelity`
+    pub fidelity: f64,
+}
+
+/// Excise the fragment flanked by sites of `enzyme` from `seq`, i.e. the
+/// fragment cut by `enzyme` on both ends which keeps no site.
+fn excise(seq: &DnaSequence, enzyme: &RestrictionEnzyme, name: &str) -> anyhow::Result<Fragment> {
+    let scanner = RestrictionSiteScanner::new([enzyme]);
+    let sites = scanner.scan(seq.as_nucleotides(), seq.topology());
+    if sites.len() > 2 {
+        let positions = sites
+            .iter()
+            .map(|s| s.start.to_string())
+            .collect::<Vec<_>>();
+        bail!(
+            "The {} has internal {} sites at {}",
+            name,
+            enzyme.name,
+            positions.join(", ")
+        );
+    }
+    if sites.len() < 2 {
+        bail!("The {} needs two {} sites", name, enzyme.name);
+    }
+    let mut excised = digest(seq, [enzyme]).into_iter().filter(|fragment| {
+        fragment.left_enzyme.is_some()
+            && fragment.right_enzyme.is_some()
+            && scanner
+                .scan(fragment.dna.top().as_nucleotides(), Topology::Linear)
+                .is_empty()
+    });
+    match (excised.next(), excised.next()) {
+        (Some(fragment), None) => Ok(fragment),
+        _ => bail!(
+            "The {} sites of the {} don't flank a fragment",
+            enzyme.name,
+            name
+        ),
+    }
+}
+
+fn overhang_string(overhang: &[DnaNucleotide]) -> String {
+    overhang.iter().map(|n| n.to_letter()).collect()
+}
+
+/// Simulate a Golden Gate assembly of `parts` into the destination `vector`
+/// with a Type IIS `enzyme`, like BsaI, BsmBI or BbsI.
+///
+/// The vector and each part are digested and the fragment flanked by two sites
+/// is kept, i.e. the vector backbone without its dropout and the parts without
+/// their flanks. Starting from the backbone, the fragments are ligated in the
+/// order given by their overhangs, flipping parts if necessary, until the
+/// backbone is closed into a circle. The annotations of all fragments are kept.
+///
+/// Fails for sequences with internal sites, palindromic or duplicate
+/// overhangs and for parts that can't be placed.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::golden_gate;
+///
+/// let bsai = RestrictionEnzyme::by_name("BsaI_HFv2").unwrap();
+/// // Backbone from AATG to GCTT with a dropout between inward facing sites
+/// let mut vector = DnaSequence::from_str("GCTTCCCCCCCCAATGAGAGACCTTTTGGTCTCA").unwrap();
+/// vector.set_topology(Topology::Circular);
+/// let part = DnaSequence::from_str("AAGGTCTCAAATGAAAAAAGCTTAGAGACCAA").unwrap();
+///
+/// let assembly = golden_gate(&vector, &[part], bsai).unwrap();
+/// assert!(assembly.product.is_circular());
+/// assert_eq!(assembly.product.dna.top().to_nucleotide_string(), "GCTTCCCCCCCCAATGAAAAAA");
+/// assert_eq!(assembly.product.junctions.len(), 2);
+/// assert!(assembly.fidelity > 0.99);
+/// ```
+pub fn golden_gate(
+    vector: &DnaSequence,
+    parts: &[DnaSequence],
+    enzyme: &RestrictionEnzyme,
+) -> anyhow::Result<GoldenGateAssembly> {
+    if !enzyme.cuts_outside() || enzyme.overhang_type() != Some(OverhangType::FivePrime) {
+        bail!(
+            "{} is not a Type IIS enzyme leaving 5' overhangs",
+            enzyme.name
+        );
+    }
+    let backbone = excise(vector, enzyme, "vector")?;
+    let fragments = parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| excise(part, enzyme, &format!("part {}", i + 1)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    // Every overhang has to occur exactly twice, once on each side of a junction
+    let mut overhangs: Vec<(Vec<DnaNucleotide>, usize)> = Vec::new();
+    for fragment in std::iter::once(&backbone).chain(&fragments) {
+        for end in [fragment.left_end(), fragment.right_end()]
+            .into_iter()
+            .flatten()
+        {
+            let reverse = reverse_complement(&end.overhang);
+            if reverse == end.overhang {
+                bail!("Palindromic overhang {}", overhang_string(&end.overhang));
+            }
+            match overhangs
+                .iter_mut()
+                .find(|(o, _)| *o == end.overhang || *o == reverse)
+            {
+                Some((_, count)) => *count += 1,
+                None => overhangs.push((end.overhang, 1)),
+            }
+        }
+    }
+    if let Some((overhang, _)) = overhangs.iter().find(|(_, count)| *count > 2) {
+        bail!("Duplicate overhang {}", overhang_string(overhang));
+    }
+
+    let mut dna = backbone.dna.clone();
+    let mut placed = vec![LigationPart {
+        fragment: 0,
+        strand: Strand::Forward,
+    }];
+    // Position, end and the fragments on both sides of each junction
+    let mut joins: Vec<(usize, DnaEnd, Fragment, Fragment)> = Vec::new();
+    let mut last = backbone.clone();
+    loop {
+        let end = dna.right_end().expect("linear molecules have ends");
+        let next = fragments.iter().enumerate().find_map(|(i, fragment)| {
+            if placed.iter().any(|p| p.fragment == i + 1) {
+                return None;
+            }
+            [Strand::Forward, Strand::Reverse]
+                .into_iter()
+                .find_map(|strand| {
+                    let fragment = match strand {
+                        Strand::Forward => fragment.clone(),
+                        Strand::Reverse => fragment.reverse_complement(),
+                    };
+                    let left = fragment.left_end()?;
+                    left.is_compatible_with(&end)
+                        .then_some((i, strand, fragment))
+                })
+        });
+        let Some((i, strand, fragment)) = next else {
+            break;
+        };
+        joins.push((dna.top().len(), end, last, fragment.clone()));
+        dna = join(&dna, &fragment.dna)?;
+        placed.push(LigationPart {
+            fragment: i + 1,
+            strand,
+        });
+        last = fragment;
+    }
+    let unplaced = (1..=fragments.len())
+        .filter(|i| !placed.iter().any(|p| p.fragment == *i))
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>();
+    if !unplaced.is_empty() {
+        bail!("Parts {} can't be placed", unplaced.join(", "));
+    }
+    let end = dna.right_end().expect("linear molecules have ends");
+    let dna = close(&dna).map_err(|_| {
+        anyhow!(
+            "The assembly ends with overhang {} which doesn't close the vector",
+            overhang_string(&end.overhang)
+        )
+    })?;
+    joins.push((0, end, last, backbone));
+
+    let fidelity = overhang_fidelity(
+        &joins
+            .iter()
+            .map(|(_, end, _, _)| end.overhang.clone())
+            .collect::<Vec<_>>(),
+    );
+    let junctions = joins
+        .into_iter()
+        .map(|(position, end, left, right)| junction(&dna, position, end, &left, &right))
+        .collect();
+    Ok(GoldenGateAssembly {
+        product: Ligation {
+            dna,
+            parts: placed,
+            junctions,
+        },
+        fidelity,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::golden_gate;
+
+    fn bsai() -> &'static RestrictionEnzyme {
+        RestrictionEnzyme::by_name("BsaI_HFv2").unwrap()
+    }
+
+    fn vector() -> DnaSequence {
+        let mut vector = DnaSequence::from_str("GCTTCCCCCCCCAATGAGAGACCTTTTGGTCTCA").unwrap();
+        vector.set_topology(Topology::Circular);
+        vector
+    }
+
+    fn part(left: &str, insert: &str, right: &str) -> DnaSequence {
+        DnaSequence::from_str(format!("AAGGTCTCA{left}{insert}{right}TGAGACCAA")).unwrap()
+    }
+
+    #[test]
+    fn test_golden_gate_order() {
+        let mut promoter = part("AATG", "CCCAAA", "GGAG");
+        promoter
+            .as_mut_annotations()
+            .push(Annotation::new(13, 18, None, "promoter"));
+        // Given flipped and out of order
+        let gene = part("GGAG", "GGGTTT", "GCTT").reverse_complement();
+        let assembly = golden_gate(&vector(), &[gene, promoter], bsai()).unwrap();
+        let product = &assembly.product;
+        assert_eq!(
+            product.dna.top().to_nucleotide_string(),
+            "GCTTCCCCCCCCAATGCCCAAAGGAGGGGTTT"
+        );
+        assert_eq!(
+            product.parts,
+            [
+                LigationPart {
+                    fragment: 0,
+                    strand: Strand::Forward
+                },
+                LigationPart {
+                    fragment: 2,
+                    strand: Strand::Forward
+                },
+                LigationPart {
+                    fragment: 1,
+                    strand: Strand::Reverse
+                },
+            ]
+        );
+        assert_eq!(
+            product.dna.top().as_annotations(),
+            [Annotation::new(16, 21, None, "promoter")]
+        );
+        let positions = product
+            .junctions
+            .iter()
+            .map(|j| j.position)
+            .collect::<Vec<_>>();
+        assert_eq!(positions, [12, 22, 0]);
+        assert!(product.dna.base_pair_iter().all(|pair| pair.is_paired()));
+        // The sites are removed from the product
+        assert!(product
+            .junctions
+            .iter()
+            .all(|j| j.destroyed == ["BsaI_HFv2"]));
+    }
+
+    #[test]
+    fn test_golden_gate_custom_enzyme() {
+        let enzyme = RestrictionEnzyme {
+            name: "MyBsaI".to_string(),
+            ..bsai().clone()
+        };
+        let part = part("AATG", "CCCAAA", "GCTT");
+        let assembly = golden_gate(&vector(), &[part], &enzyme).unwrap();
+        assert!(assembly
+            .product
+            .junctions
+            .iter()
+            .all(|j| j.destroyed == ["MyBsaI"] && j.regenerated.is_empty()));
+    }
+
+    #[test]
+    fn test_golden_gate_errors() {
+        let vector = vector();
+        let error =
+            |parts: &[DnaSequence]| golden_gate(&vector, parts, bsai()).unwrap_err().to_string();
+        assert!(error(&[part("AATG", "GGTCTC", "GCTT")]).contains("internal"));
+        assert!(
+            error(&[part("AATG", "AAA", "GATC"), part("GATC", "AAA", "GCTT")])
+                .contains("Palindromic")
+        );
+        assert!(error(&[
+            part("AATG", "AAA", "GGAG"),
+            part("GGAG", "AAA", "GCTT"),
+            part("GGAG", "CCC", "GCTT")
+        ])
+        .contains("Duplicate"));
+        assert!(
+            error(&[part("AATG", "AAA", "GCTT"), part("CCCC", "AAA", "GGGG")])
+                .contains("can't be placed")
+        );
+        assert!(error(&[part("AATG", "AAA", "GGAG")]).contains("doesn't close"));
+        let ecori = RestrictionEnzyme::by_name("EcoRI").unwrap();
+        assert!(golden_gate(&vector, &[], ecori).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{
+    seq::{Annotation, DnaEnd, DoubleStrandedDna, OverhangType, Strand, Topology},
+    uni::{RestrictionEnzyme, RestrictionSiteScanner},
+};
+
+use super::Fragment;
+
+/// A fragment built into a ligation product.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LigationPart {
+    /// Index of the fragment in the list of ligated fragments
+    pub fragment: usize,
+    /// `Reverse` if the fragment was flipped before ligation
+    pub strand: Strand,
+}
+
+/// A junction of two ends in a ligation product.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Junction {
+    /// Position of the first top strand nucleotide after the junction
+    pub position: usize,
+    /// The annealed ends
+    pub end: DnaEnd,
+    /// The enzymes that cut the joined ends, left and right of the junction
+    pub enzymes: (Option<String>, Option<String>),
+    /// Enzymes that cut the joined ends and whose site is restored by the junction
+    pub regenerated: Vec<String>,
+    /// Enzymes that cut the joined ends and whose site is lost at the junction
+    pub destroyed: Vec<String>,
+}
+
+/// The product of a ligation.
+///
+/// The annotations of the ligated fragments are kept on the top strand of the
+/// product, annotations of flipped fragments are mirrored onto it. Annotations
+/// ending at a junction are not joined with the annotations of the next fragment.
+#[derive(Debug, Clone)]
+pub struct Ligation {
+    pub dna: DoubleStrandedDna,
+    /// The ligated fragments, from left to right along the top strand
+    pub parts: Vec<LigationPart>,
+    /// The junctions, from left to right along the top strand. The junction closing
+    /// a circular product is the last one, at position 0.
+    pub junctions: Vec<Junction>,
+}
+
+impl Ligation {
+    pub fn is_circular(&self) -> bool {
+        self.dna.is_circular()
+    }
+}
+
+/// Join the right end of `left` to the left end of `right`.
+pub(super) fn join(
+    left: &DoubleStrandedDna,
+    right: &DoubleStrandedDna,
+) -> anyhow::Result<DoubleStrandedDna> {
+    let (Some(right_end), Some(left_end)) = (left.right_end(), right.left_end()) else {
+        bail!("Circular molecules can't be ligated");
+    };
+    if !right_end.is_compatible_with(&left_end) {
+        bail!(
+            "Incompatible ends: {} {:?} and {} {:?}",
+            right_end.overhang_type,
+            right_end.overhang,
+            left_end.overhang_type,
+            left_end.overhang
+        );
+    }
+    let shift = left.top().len();
+    let mut top = left.top().clone();
+    top.disable_history();
+    top.insert_bases(shift, right.top().nucleotide_iter());
+    top.as_mut_annotations()
+        .extend(right.top().annotation_iter().map(|ann| Annotation {
+            start: ann.start + shift,
+            end: ann.end + shift,
+            ..ann.map_needles(|n| Some(n + shift))
+        }));
+    let bottom = right.bottom().iter().chain(left.bottom());
+    DoubleStrandedDna::from_strands(top, bottom, left.offset())
+}
+
+/// Join the right end of `dna` to its own left end.
+pub(super) fn close(dna: &DoubleStrandedDna) -> anyhow::Result<DoubleStrandedDna> {
+    let (Some(right_end), Some(left_end)) = (dna.right_end(), dna.left_end()) else {
+        bail!("Circular molecules can't be ligated");
+    };
+    if !right_end.is_compatible_with(&left_end) {
+        bail!("The ends of the molecule are incompatible");
+    }
+    let len = dna.top().len() as isize;
+    let offset = dna.offset();
+    // The bottom strand of the circle, each column taken from wherever the
+    // linear bottom strand covers it
+    let bottom = (0..len)
+        .rev()
+        .filter_map(|column| {
+            let column = offset + (column - offset).rem_euclid(len);
+            dna.base_pair_at(column).and_then(|pair| pair.bottom)
+        })
+        .collect::<Vec<_>>();
+    let mut top = dna.top().clone();
+    top.disable_history();
+    top.set_topology(Topology::Circular);
+    DoubleStrandedDna::from_strands(top, bottom, 0)
+}
+
+/// The enzymes of `enzymes` with a recognition site spanning the junction at
+/// `position` of `dna`, i.e. overlapping both the left molecule and the annealed
+/// overhang or the right molecule.
+fn sites_spanning<'a>(
+    dna: &DoubleStrandedDna,
+    position: usize,
+    end: &DnaEnd,
+    enzymes: &[&'a RestrictionEnzyme],
+) -> Vec<&'a RestrictionEnzyme> {
+    let len = dna.top().len() as isize;
+    // The columns between the nicks of the top and the bottom strand
+    let overhang = end.overhang.len() as isize;
+    let start = match end.overhang_type {
+        OverhangType::ThreePrime => position as isize - overhang,
+        _ => position as isize,
+    };
+    let mut spanning = RestrictionSiteScanner::new(enzymes.iter().copied())
+        .scan(dna.top().as_nucleotides(), dna.topology())
+        .into_iter()
+        .filter(|site| {
+            let site_len = enzymes[site.enzyme].recognition_site().len() as isize;
+            // Start of the site relative to the junction
+            let mut relative = (site.start as isize - start).rem_euclid(len);
+            if relative > len - site_len {
+                relative -= len;
+            }
+            relative < overhang && relative + site_len > 0
+        })
+        .map(|site| enzymes[site.enzyme])
+        .collect::<Vec<_>>();
+    spanning.dedup_by(|a, b| a.name == b.name);
+    spanning
+}
+
+/// The junction at `position` of `dna`, joining the right end of `left` to the
+/// left end of `right`. Only the enzymes that cut these ends are looked for.
+pub(super) fn junction(
+    dna: &DoubleStrandedDna,
+    position: usize,
+    end: DnaEnd,
+    left: &Fragment,
+    right: &Fragment,
+) -> Junction {
+    let mut cutters = left
+        .right_enzyme
+        .iter()
+        .chain(right.left_enzyme.iter())
+        .collect::<Vec<_>>();
+    cutters.dedup_by(|a, b| a.name == b.name);
+    let sites = sites_spanning(dna, position, &end, &cutters);
+    let (regenerated, destroyed) = cutters
+        .into_iter()
+        .map(|enzyme| enzyme.name.clone())
+        .partition(|name| sites.iter().any(|enzyme| enzyme.name == *name));
+    let name = |enzyme: &Option<RestrictionEnzyme>| enzyme.as_ref().map(|e| e.name.clone());
+    Junction {
+        position,
+        end,
+        enzymes: (name(&left.right_enzyme), name(&right.left_enzyme)),
+        regenerated,
+        destroyed,
+    }
+}
+
+/// Ligate the right end of `left` to the left end of `right` into a linear product.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::{digest, ligate};
+///
+/// let vector = DnaSequence::from_str("AAAAGGATCCAAAA").unwrap();
+/// let insert = DnaSequence::from_str("TTTTAGATCTTTTT").unwrap();
+/// let vector = digest(&vector, [RestrictionEnzyme::by_name("BamHI").unwrap()]);
+/// let insert = digest(&insert, [RestrictionEnzyme::by_name("BglII").unwrap()]);
+///
+/// // BamHI and BglII leave compatible cohesive ends
+/// let product = ligate(&vector[0], &insert[1]).unwrap();
+/// assert_eq!(product.dna.top().to_nucleotide_string(), "AAAAGGATCTTTTT");
+/// let junction = &product.junctions[0];
+/// assert_eq!(junction.destroyed, ["BamHI", "BglII"]);
+/// ```
+pub fn ligate(left: &Fragment, right: &Fragment) -> anyhow::Result<Ligation> {
+    let dna = join(&left.dna, &right.dna)?;
+    let position = left.dna.top().len();
+    let end = left.right_end().expect("linear fragments have ends");
+    Ok(Ligation {
+        junctions: vec![junction(&dna, position, end, left, right)],
+        dna,
+        parts: vec![
+            LigationPart {
+                fragment: 0,
+                strand: Strand::Forward,
+            },
+            LigationPart {
+                fragment: 1,
+                strand: Strand::Forward,
+            },
+        ],
+    })
+}
+
+/// Ligate the ends of a fragment to each other into a circular product.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::{circularize, digest};
+///
+/// let ecori = RestrictionEnzyme::by_name("EcoRI").unwrap();
+/// let mut plasmid = DnaSequence::from_str("GAATTCAAAAAAAA").unwrap();
+/// plasmid.set_topology(Topology::Circular);
+///
+/// let linearized = digest(&plasmid, [ecori]);
+/// let religated = circularize(&linearized[0]).unwrap();
+/// assert!(religated.is_circular());
+/// assert_eq!(religated.junctions[0].regenerated, ["EcoRI"]);
+/// ```
+pub fn circularize(fragment: &Fragment) -> anyhow::Result<Ligation> {
+    let dna = close(&fragment.dna)?;
+    let end = fragment.right_end().expect("linear fragments have ends");
+    Ok(Ligation {
+        junctions: vec![junction(&dna, 0, end, fragment, fragment)],
+        dna,
+        parts: vec![LigationPart {
+            fragment: 0,
+            strand: Strand::Forward,
+        }],
+    })
+}
+
+/// All products of ligating one or two of `fragments`.
+///
+/// These are the circular products of single fragments, and the linear and
+/// circular products of two different fragments in both orientations, e.g. a
+/// vector backbone with an insert. Products of more fragments are not considered.
+pub fn ligation_products(fragments: &[Fragment]) -> Vec<Ligation> {
+    let part = |fragment: usize, strand: Strand| LigationPart { fragment, strand };
+    let mut products = Vec::new();
+    for (i, fragment) in fragments.iter().enumerate() {
+        if let Ok(mut product) = circularize(fragment) {
+            product.parts = vec![part(i, Strand::Forward)];
+            products.push(product);
+        }
+    }
+    for (i, a) in fragments.iter().enumerate() {
+        for (j, b) in fragments.iter().enumerate().skip(i + 1) {
+            for strand in [Strand::Forward, Strand::Reverse] {
+                let b = match strand {
+                    Strand::Forward => b.clone(),
+                    Strand::Reverse => b.reverse_complement(),
+                };
+                let parts = vec![part(i, Strand::Forward), part(j, strand)];
+                if let Ok(mut product) = ligate(a, &b) {
+                    product.parts = parts.clone();
+                    products.push(product.clone());
+                    if let Ok(dna) = close(&product.dna) {
+                        let end = b.right_end().expect("linear fragments have ends");
+                        product.junctions.push(junction(&dna, 0, end, &b, a));
+                        product.dna = dna;
+                        products.push(product);
+                    }
+                }
+                if let Ok(mut product) = ligate(&b, a) {
+                    product.parts = parts.into_iter().rev().collect();
+                    products.push(product);
+                }
+            }
+        }
+    }
+    products
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{circularize, ligate, ligation_products};
+    use crate::sim::digest;
+
+    #[test]
+    fn test_ligate_incompatible() {
+        let seq = DnaSequence::from_str("AAAGAATTCAAAACTGCAGAAA").unwrap();
+        let fragments = digest(
+            &seq,
+            [
+                RestrictionEnzyme::by_name("EcoRI").unwrap(),
+                RestrictionEnzyme::by_name("PstI").unwrap(),
+            ],
+        );
+        assert_eq!(fragments.len(), 3);
+        assert!(ligate(&fragments[0], &fragments[1]).is_ok());
+        assert!(ligate(&fragments[0], &fragments[2]).is_err());
+        assert!(ligate(&fragments[1], &fragments[0]).is_err());
+        // EcoRI and PstI ends of the middle fragment don't close
+        assert!(circularize(&fragments[1]).is_err());
+    }
+
+    #[test]
+    fn test_ligate_annotations() {
+        let mut vector = DnaSequence::from_str("CCCCGAATTCGGGG").unwrap();
+        vector
+            .as_mut_annotations()
+            .push(Annotation::new(10, 13, None, "right"));
+        let fragments = digest(&vector, [RestrictionEnzyme::by_name("EcoRI").unwrap()]);
+        let product = ligate(&fragments[0], &fragments[1]).unwrap();
+        assert_eq!(product.dna.top().to_nucleotide_string(), "CCCCGAATTCGGGG");
+        assert_eq!(
+            product.dna.top().as_annotations(),
+            [Annotation::new(10, 13, None, "right")]
+        );
+        assert_eq!(product.junctions[0].position, 5);
+        assert_eq!(product.junctions[0].regenerated, ["EcoRI"]);
+        assert!(product.dna.base_pair_iter().all(|pair| pair.is_paired()));
+    }
+
+    #[test]
+    fn test_ligate_custom_enzyme() {
+        use IupacNucleotide::*;
+
+        let enzyme = RestrictionEnzyme::from_halves("MyEco", &[G], &[A, A, T, T, C]);
+        let seq = DnaSequence::from_str("CCCCGAATTCGGGG").unwrap();
+        let fragments = digest(&seq, [&enzyme]);
+        let product = ligate(&fragments[0], &fragments[1]).unwrap();
+        assert_eq!(
+            product.junctions[0].enzymes,
+            (Some("MyEco".to_string()), Some("MyEco".to_string()))
+        );
+        assert_eq!(product.junctions[0].regenerated, ["MyEco"]);
+        assert!(product.junctions[0].destroyed.is_empty());
+    }
+
+    #[test]
+    fn test_ligation_products_vector_insert() {
+        let mut plasmid = DnaSequence::from_str("GAATTCAAAAAAGGATCCTTTTTT").unwrap();
+        plasmid.set_topology(Topology::Circular);
+        let vector = digest(
+            &plasmid,
+            [
+                RestrictionEnzyme::by_name("EcoRI").unwrap(),
+                RestrictionEnzyme::by_name("BamHI").unwrap(),
+            ],
+        );
+        assert_eq!(vector.len(), 2);
+
+        let products = ligation_products(&vector);
+        let circular = products
+            .iter()
+            .filter(|p| p.is_circular())
+            .collect::<Vec<_>>();
+        // Only the original plasmid can be restored, the ends don't self-ligate
+        assert_eq!(circular.len(), 1);
+        assert_eq!(circular[0].dna.top().len(), 24);
+        assert_eq!(circular[0].junctions.len(), 2);
+        assert!(circular[0]
+            .junctions
+            .iter()
+            .all(|j| j.regenerated.len() == 1 && j.destroyed.is_empty()));
+        assert!(circular[0]
+            .dna
+            .base_pair_iter()
+            .all(|pair| pair.is_paired()));
+        // The flipped insert doesn't fit between EcoRI and BamHI ends
+        assert!(circular[0]
+            .parts
+            .iter()
+            .all(|part| part.strand == Strand::Forward));
+    }
+}
@@ -0,0 +1,476 @@
+use crate::{
+    dna::DnaNucleotide,
+    seq::{Annotation, DnaSequence, Strand, Topology},
+    traits::{Nucleotide, ToIupac},
+    uni::IupacNucleotide,
+};
+
+use super::Primer;
+
+/// Options for `pcr_with_options`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PcrOptions {
+    /// Minimum number of 3' nucleotides of a primer annealing to the template
+    pub min_binding: usize,
+    /// Maximum number of mismatches in the annealing region, those within
+    /// `three_prime_window` nucleotides of the 3' end counting double. The 3'
+    /// nucleotide itself always has to match.
+    pub max_mismatches: usize,
+    /// Number of nucleotides at the 3' end in which mismatches count double
+    pub three_prime_window: usize,
+}
+
+impl PcrOptions {
+    pub fn with_min_binding(mut self, min_binding: usize) -> Self {
+        self.min_binding = min_binding;
+        self
+    }
+
+    pub fn with_max_mismatches(mut self, max_mismatches: usize) -> Self {
+        self.max_mismatches = max_mismatches;
+        self
+    }
+
+    pub fn with_three_prime_window(mut self, window: usize) -> Self {
+        self.three_prime_window = window;
+        self
+    }
+}
+
+impl Default for PcrOptions {
+    /// At least 15 annealing nucleotides with a single mismatch, which has to
+    /// be more than 5 nucleotides away from the 3' end.
+    fn default() -> Self {
+        Self {
+            min_binding: 15,
+            max_mismatches: 1,
+            three_prime_window: 5,
+        }
+    }
+}
+
+/// A site a primer anneals to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrimerSite {
+    /// 0 if the site is bound by the forward primer, 1 if it is bound by the
+    /// reverse primer
+    pub primer: usize,
+    /// The strand the primer is extended along, i.e. the strand carrying the
+    /// sequence of the primer
+    pub strand: Strand,
+    /// Top strand position of the first annealing nucleotide
+    pub start: usize,
+    /// Top strand position of the last annealing nucleotide, lower than `start`
+    /// for sites wrapping around the origin
+    pub end: usize,
+    /// Number of mismatches in the annealing region
+    pub mismatches: usize,
+    /// The primer split into its 5' tail and its annealing region. Degenerate
+    /// nucleotides annealing to the template are resolved to it.
+    pub annealed: Primer,
+}
+
+/// A product of `pcr`.
+#[derive(Debug, Clone)]
+pub struct Amplicon {
+    /// The amplified sequence including the primer tails, with the annotations
+    /// of the template inside of it
+    pub product: DnaSequence,
+    /// The site of the primer extended along the top strand
+    pub forward: PrimerSite,
+    /// The site of the primer extended along the bottom strand
+    pub reverse: PrimerSite,
+}
+
+impl Amplicon {
+    pub fn len(&self) -> usize {
+        self.product.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.product.is_empty()
+    }
+}
+
+fn definite(n: IupacNucleotide) -> Option<DnaNucleotide> {
+    match n {
+        IupacNucleotide::A => Some(DnaNucleotide::A),
+        IupacNucleotide::C => Some(DnaNucleotide::C),
+        IupacNucleotide::G => Some(DnaNucleotide::G),
+        IupacNucleotide::T => Some(DnaNucleotide::T),
+        _ => None,
+    }
+}
+
+/// Sites of `primer` on `strand`, as the index of the nucleotide opposite the
+/// 3' end of the primer, the number of annealing nucleotides and their mismatches.
+///
+/// The annealing region is the longest 3' part of the primer within the mismatch
+/// limit that ends with a matching nucleotide.
+fn anneal(
+    primer: &[IupacNucleotide],
+    strand: &[DnaNucleotide],
+    circular: bool,
+    options: &PcrOptions,
+) -> Vec<(usize, usize, usize)> {
+    let len = strand.len();
+    let mut sites = Vec::new();
+    for i in 0..len {
+        let reach = if circular { len } else { i + 1 };
+        let (mut penalty, mut mismatches) = (0, 0);
+        let mut best = None;
+        for k in 0..primer.len().min(reach) {
+            let n = strand[(i + len - k) % len];
+            if n.to_iupac().matches(&primer[primer.len() - 1 - k]) {
+                if k + 1 >= options.min_binding {
+                    best = Some((k + 1, mismatches));
+                }
+                continue;
+            }
+            if k == 0 {
+                break;
+            }
+            penalty += if k < options.three_prime_window { 2 } else { 1 };
+            mismatches += 1;
+            if penalty > options.max_mismatches {
+                break;
+            }
+        }
+        if let Some((binding, mismatches)) = best {
+            sites.push((i, binding, mismatches));
+        }
+    }
+    sites
+}
+
+/// All sites of `primer` on both strands of `template`.
+fn primer_sites(
+    template: &DnaSequence,
+    primer: &[IupacNucleotide],
+    index: usize,
+    options: &PcrOptions,
+) -> anyhow::Result<Vec<PrimerSite>> {
+    let len = template.len();
+    let circular = template.is_circular();
+    let mut sites = Vec::new();
+    for strand in [Strand::Forward, Strand::Reverse] {
+        let nucleotides = match strand {
+            Strand::Forward => template.as_nucleotides().to_vec(),
+            Strand::Reverse => template.as_reverse_strand(),
+        };
+        for (three_prime, binding, mismatches) in anneal(primer, &nucleotides, circular, options) {
+            let tail_len = primer.len() - binding;
+            let mut tail = Vec::with_capacity(tail_len);
+            for &n in primer[..tail_len].iter() {
+                match definite(n) {
+                    Some(n) => tail.push(n),
+                    None => bail!(
+                        "Primer {} has a degenerate nucleotide in its tail",
+                        index + 1
+                    ),
+                }
+            }
+            let mut annealed = Vec::with_capacity(binding);
+            for (k, &n) in primer[tail_len..].iter().enumerate() {
+                let opposite = nucleotides[(three_prime + len + k + 1 - binding) % len];
+                match definite(n) {
+                    _ if opposite.to_iupac().matches(&n) => annealed.push(opposite),
+                    Some(n) => annealed.push(n),
+                    None => bail!(
+                        "Primer {} has a degenerate nucleotide in a mismatch",
+                        index + 1
+                    ),
+                }
+            }
+            let (start, end) = match strand {
+                Strand::Forward => ((three_prime + len + 1 - binding) % len, three_prime),
+                Strand::Reverse => {
+                    let start = len - 1 - three_prime;
+                    (start, (start + binding - 1) % len)
+                }
+            };
+            sites.push(PrimerSite {
+                primer: index,
+                strand,
+                start,
+                end,
+                mismatches,
+                annealed: Primer::new(&annealed).with_tail(&tail),
+            });
+        }
+    }
+    Ok(sites)
+}
+
+/// The template annotations covering `start` and the following `count`
+/// nucleotides, projected onto a product in which `start` is at `offset`.
+fn project_annotations(
+    template: &DnaSequence,
+    start: usize,
+    count: usize,
+    offset: usize,
+) -> Vec<Annotation> {
+    let len = template.len();
+    template
+        .annotation_iter()
+        .filter_map(|ann| {
+            let relative = (ann.start + len - start) % len;
+            let ann_len = ann.len_in(len);
+            if relative + ann_len > count {
+                return None;
+            }
+            let new_start = relative + offset;
+            Some(Annotation {
+                start: new_start,
+                end: new_start + ann_len - 1,
+                ..ann.map_needles(|n| {
+                    let n = (n + len - ann.start) % len;
+                    (n <= ann_len).then_some(new_start + n)
+                })
+            })
+        })
+        .collect()
+}
+
+/// Simulate a PCR of `template` with the primers `fwd` and `rev`, read 5' to 3',
+/// with the default `PcrOptions`.
+///
+/// Both primers may anneal anywhere on either strand of the template, with
+/// mismatches and with 5' tails that don't anneal. Degenerate IUPAC primers
+/// anneal wherever one of their variants does. Every site extended along the
+/// top strand yields an amplicon with every site downstream of it extended along
+/// the bottom strand; on circular templates also across the origin. Amplicons
+/// include the primer tails and are sorted by their start on the template.
+///
+/// Fails if a primer with a degenerate nucleotide in its tail or in a mismatch
+/// anneals, since the product isn't defined then.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::pcr;
+///
+/// let template = DnaSequence::from_str(
+///     "GGGGGATGGCTAGCAAAGGAGAAGAACTTCCCCCCTTCACTGGAGTTGTCCCAATTGGGGGG",
+/// )
+/// .unwrap();
+/// // EcoRI tail and degenerate binding region
+/// let fwd = PackedIupacSequence::from_str("gaattcATGGCTAGCAARGGAGAAG").unwrap();
+/// let rev = DnaSequence::from_str("CAATTGGGACAACTCCAGTGAAG").unwrap();
+///
+/// let amplicons = pcr(&template, &fwd.nucleotide_iter().copied().collect::<Vec<_>>(), rev.as_nucleotides())
+///     .unwrap();
+/// assert_eq!(amplicons.len(), 1);
+/// assert_eq!(amplicons[0].forward.annealed.to_string(), "gaattcATGGCTAGCAAAGGAGAAG");
+/// assert_eq!(
+///     amplicons[0].product.to_string(),
+///     "GAATTCATGGCTAGCAAAGGAGAAGAACTTCCCCCCTTCACTGGAGTTGTCCCAATTG",
+/// );
+/// ```
+pub fn pcr<F, R>(template: &DnaSequence, fwd: &[F], rev: &[R]) -> anyhow::Result<Vec<Amplicon>>
+where
+    F: ToIupac,
+    R: ToIupac,
+{
+    pcr_with_options(template, fwd, rev, &PcrOptions::default())
+}
+
+/// Simulate a PCR like `pcr`, with the annealing limits of `options`.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::pcr_with_options;
+///
+/// let template = DnaSequence::from_str(
+///     "GGGGGATGGCTAGCAAAGGAGAAGAACTTCCCCCCTTCACTGGAGTTGTCCCAATTGGGGGG",
+/// )
+/// .unwrap();
+/// // Two mismatches in the forward primer
+/// let fwd = DnaSequence::from_str("ATGGCTTGCAAAGCAGAAG").unwrap();
+/// let rev = DnaSequence::from_str("CAATTGGGACAACTCCAGTGAAG").unwrap();
+///
+/// let strict = PcrOptions::default();
+/// let relaxed = strict.with_max_mismatches(2);
+/// let run = |options| pcr_with_options(&template, fwd.as_nucleotides(), rev.as_nucleotides(), options);
+/// assert!(run(&strict).unwrap().is_empty());
+/// assert_eq!(run(&relaxed).unwrap()[0].forward.mismatches, 2);
+/// ```
+pub fn pcr_with_options<F, R>(
+    template: &DnaSequence,
+    fwd: &[F],
+    rev: &[R],
+    options: &PcrOptions,
+) -> anyhow::Result<Vec<Amplicon>>
+where
+    F: ToIupac,
+    R: ToIupac,
+{
+    let len = template.len();
+    let fwd = fwd.iter().map(|n| n.to_iupac()).collect::<Vec<_>>();
+    let rev = rev.iter().map(|n| n.to_iupac()).collect::<Vec<_>>();
+    let mut sites = primer_sites(template, &fwd, 0, options)?;
+    for site in primer_sites(template, &rev, 1, options)? {
+        // Sites of identical primers only yield amplicons once
+        let duplicate = sites.iter().any(|s| {
+            PrimerSite {
+                primer: 1,
+                ..s.clone()
+            } == site
+        });
+        if !duplicate {
+            sites.push(site);
+        }
+    }
+    let (forward, reverse): (Vec<_>, Vec<_>) = sites
+        .into_iter()
+        .partition(|site| site.strand == Strand::Forward);
+
+    let mut amplicons = Vec::new();
+    for f in forward.iter() {
+        for r in reverse.iter() {
+            // Nucleotides between the 3' ends of the primers
+            let between = if template.is_circular() {
+                if f.end == r.start {
+                    continue;
+                }
+                (r.start + len - f.end - 1) % len
+            } else if f.end < r.start {
+                r.start - f.end - 1
+            } else {
+                continue;
+            };
+            let mut product = f.annealed.sequence().into_iter().collect::<DnaSequence>();
+            product.insert_bases(
+                product.len(),
+                (1..=between).map(|i| template[(f.end + i) % len]),
+            );
+            let tail = r.annealed.sequence();
+            product.insert_bases(product.len(), tail.iter().rev().map(|n| n.complement()));
+            product.set_topology(Topology::Linear);
+            let covered = f.annealed.binding.len() + between + r.annealed.binding.len();
+            *product.as_mut_annotations() =
+                project_annotations(template, f.start, covered, f.annealed.tail.len());
+            amplicons.push(Amplicon {
+                product,
+                forward: f.clone(),
+                reverse: r.clone(),
+            });
+        }
+    }
+    amplicons.sort_by_key(|a| (a.forward.start, a.len()));
+    Ok(amplicons)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{pcr, pcr_with_options};
+
+    fn seq(s: &str) -> DnaSequence {
+        DnaSequence::from_str(s).unwrap()
+    }
+
+    fn iupac(s: &str) -> Vec<IupacNucleotide> {
+        PackedIupacSequence::from_str(s)
+            .unwrap()
+            .nucleotide_iter()
+            .copied()
+            .collect()
+    }
+
+    const ORF: &str = "ATGGCTAGCAAAGGAGAAGAACTTTTCACTGGAGTTGTCCCAATTCTTGTTGAATTAGATGGT";
+
+    #[test]
+    fn test_pcr_circular_with_annotations() {
+        // The amplified region spans the origin
+        let mut template = seq(&format!("{}{}", &ORF[30..], &ORF[..30]));
+        template.set_topology(Topology::Circular);
+        let start = ORF.len() - 30;
+        template.as_mut_annotations().extend([
+            Annotation::new(start, 2, Some(start + 1), "start").with_strand(Strand::Forward),
+            Annotation::new(25, 30, None, "outside"),
+        ]);
+        let fwd = seq("ccATGGCTAGCAAAGGAGAAG");
+        let rev = seq("ggCAACAAGAATTGGGACAACTCC");
+        let amplicons = pcr(&template, fwd.as_nucleotides(), rev.as_nucleotides()).unwrap();
+        assert_eq!(amplicons.len(), 1);
+        let amplicon = &amplicons[0];
+        assert_eq!((amplicon.forward.start, amplicon.reverse.end), (start, 21));
+        assert_eq!(amplicon.forward.annealed.tail.len(), 2);
+        assert_eq!(amplicon.reverse.strand, Strand::Reverse);
+        assert_eq!(amplicon.product.to_string(), format!("CC{}CC", &ORF[..52]));
+        let annotations = amplicon.product.as_annotations();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(
+            (
+                annotations[0].start,
+                annotations[0].end,
+                annotations[0].needle
+            ),
+            (2, 2 + 32, Some(3))
+        );
+
+        // Linear templates don't amplify across the ends
+        template.set_topology(Topology::Linear);
+        let amplicons = pcr(&template, fwd.as_nucleotides(), rev.as_nucleotides()).unwrap();
+        assert!(amplicons.is_empty());
+    }
+
+    #[test]
+    fn test_pcr_mismatches_and_degenerate_primers() {
+        let template = seq(ORF);
+        let rev = iupac("ACCATCTAATTCAACAAGAAT");
+        // A mismatch near the 3' end counts double
+        let near = seq("ATGGCTAGCAAAGGAGTAG");
+        let far = seq("ATGGCTAGCAATGGAGAAG");
+        let options = PcrOptions::default();
+        assert!(
+            pcr_with_options(&template, near.as_nucleotides(), &rev, &options)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            pcr_with_options(&template, far.as_nucleotides(), &rev, &options).unwrap()[0]
+                .forward
+                .mismatches,
+            1
+        );
+        assert_eq!(
+            pcr_with_options(
+                &template,
+                near.as_nucleotides(),
+                &rev,
+                &options.with_max_mismatches(2)
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+        // The 3' nucleotide always has to match
+        let terminal = seq("ATGGCTAGCAAAGGAGAAC");
+        assert!(pcr_with_options(
+            &template,
+            terminal.as_nucleotides(),
+            &rev,
+            &options.with_max_mismatches(4)
+        )
+        .unwrap()
+        .is_empty());
+
+        // A single primer amplifies if it anneals on both strands
+        let palindromic = seq(&format!("{ORF}{}", seq(&ORF[..30]).reverse_complement()));
+        let fwd = iupac("ATGGCNAGCAAAGGAGAAG");
+        let amplicons = pcr(&palindromic, &fwd, &fwd).unwrap();
+        assert_eq!(amplicons.len(), 1);
+        assert_eq!(
+            (amplicons[0].forward.primer, amplicons[0].reverse.primer),
+            (0, 0)
+        );
+        assert_eq!(amplicons[0].forward.annealed.to_string(), &ORF[..19]);
+        assert_eq!(amplicons[0].len(), palindromic.len());
+
+        assert!(pcr(&template, &iupac("nnATGGCTAGCAAAGGAGAAG"), &rev).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    dna::DnaNucleotide,
+    traits::{Nucleotide, ToLetter},
+};
+
+/// Conditions for the calculation of melting temperatures.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TmOptions {
+    /// Concentration of monovalent cations, in mM
+    pub salt: f64,
+    /// Concentration of the oligonucleotide, in nM
+    pub oligo: f64,
+}
+
+impl TmOptions {
+    pub fn with_salt(mut self, salt: f64) -> Self {
+        self.salt = salt;
+        self
+    }
+
+    pub fn with_oligo(mut self, oligo: f64) -> Self {
+        self.oligo = oligo;
+        self
+    }
+}
+
+impl Default for TmOptions {
+    /// 50 mM Na+ and 250 nM oligonucleotide.
+    fn default() -> Self {
+        Self {
+            salt: 50.0,
+            oligo: 250.0,
+        }
+    }
+}
+
+/// Nearest-neighbor enthalpy (kcal/mol) and entropy (cal/K/mol) of the stack of
+/// `a` followed by `b`, after SantaLucia (1998).
+fn nearest_neighbor(a: DnaNucleotide, b: DnaNucleotide) -> (f64, f64) {
+    use DnaNucleotide::*;
+    match (a, b) {
+        (A, A) | (T, T) => (-7.9, -22.2),
+        (A, T) => (-7.2, -20.4),
+        (T, A) => (-7.2, -21.3),
+        (C, A) | (T, G) => (-8.5, -22.7),
+        (G, T) | (A, C) => (-8.4, -22.4),
+        (C, T) | (A, G) => (-7.8, -21.0),
+        (G, A) | (T, C) => (-8.2, -22.2),
+        (C, G) => (-10.6, -27.2),
+        (G, C) => (-9.8, -24.4),
+        (G, G) | (C, C) => (-8.0, -19.9),
+    }
+}
+
+/// Initiation enthalpy and entropy of a terminal base pair.
+fn initiation(n: DnaNucleotide) -> (f64, f64) {
+    match n {
+        DnaNucleotide::G | DnaNucleotide::C => (0.1, -2.8),
+        DnaNucleotide::A | DnaNucleotide::T => (2.3, 4.1),
+    }
+}
+
+/// The melting temperature of `seq` annealed to its complement, in °C.
+///
+/// Uses the unified nearest-neighbor parameters of SantaLucia (1998) with the
+/// entropy correction for the concentration of monovalent cations.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+/// use plasmid::sim::melting_temperature;
+///
+/// let primer = DnaSequence::from_str("AGCGGATAACAATTTCACACAGGA").unwrap();
+/// let tm = melting_temperature(primer.as_nucleotides(), &TmOptions::default());
+/// assert!((54.0..60.0).contains(&tm));
+/// ```
+pub fn melting_temperature(seq: &[DnaNucleotide], options: &TmOptions) -> f64 {
+    const GAS_CONSTANT: f64 = 1.987;
+    if seq.len() < 2 {
+        return f64::NEG_INFINITY;
+    }
+    let (mut dh, mut ds) = [seq[0], seq[seq.len() - 1]]
+        .map(initiation)
+        .into_iter()
+        .fold((0.0, 0.0), |(h, s), (dh, ds)| (h + dh, s + ds));
+    for pair in seq.windows(2) {
+        let (h, s) = nearest_neighbor(pair[0], pair[1]);
+        dh += h;
+        ds += s;
+    }
+    let self_complementary = seq
+        .iter()
+        .rev()
+        .map(|n| n.complement())
+        .eq(seq.iter().copied());
+    // Strand concentration term, non-self-complementary duplexes form from two strands
+    let concentration = options.oligo * 1e-9;
+    let concentration = if self_complementary {
+        ds += -1.4;
+        concentration
+    } else {
+        concentration / 4.0
+    };
+    ds += 0.368 * (seq.len() - 1) as f64 * (options.salt * 1e-3).ln();
+    dh * 1000.0 / (ds + GAS_CONSTANT * concentration.ln()) - 273.15
+}
+
+/// A PCR primer, read 5' to 3'.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Primer {
+    /// The 5' tail, which doesn't anneal to the template
+    pub tail: Vec<DnaNucleotide>,
+    /// The 3' region annealing to the template
+    pub binding: Vec<DnaNucleotide>,
+}
+
+impl Primer {
+    /// A primer without tail.
+    pub fn new(binding: &[DnaNucleotide]) -> Self {
+        Self {
+            tail: Vec::new(),
+            binding: binding.to_vec(),
+        }
+    }
+
+    pub fn with_tail(mut self, tail: &[DnaNucleotide]) -> Self {
+        self.tail = tail.to_vec();
+        self
+    }
+
+    /// The complete sequence of the primer.
+    pub fn sequence(&self) -> Vec<DnaNucleotide> {
+        self.tail.iter().chain(&self.binding).copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tail.len() + self.binding.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The melting temperature of the binding region.
+    pub fn tm(&self, options: &TmOptions) -> f64 {
+        melting_temperature(&self.binding, options)
+    }
+}
+
+impl std::fmt::Display for Primer {
+    /// The tail in lowercase letters, followed by the binding region in uppercase.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for n in self.tail.iter() {
+            write!(f, "{}", n.to_letter().to_ascii_lowercase())?;
+        }
+        for n in self.binding.iter() {
+            write!(f, "{}", n.to_letter())?;
+        }
+        Ok(())
+    }
+}
+
+/// A forward primer annealing to the bottom strand and a reverse primer
+/// annealing to the top strand.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrimerPair {
+    pub forward: Primer,
+    pub reverse: Primer,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::melting_temperature;
+
+    #[test]
+    fn test_melting_temperature() {
+        let tm = |s: &str, options: &TmOptions| {
+            melting_temperature(DnaSequence::from_str(s).unwrap().as_nucleotides(), options)
+        };
+        let options = TmOptions::default();
+        // GC rich and longer sequences melt at higher temperatures
+        assert!(tm("GCGCGGCCGCGC", &options) > tm("ATATTAATATAT", &options));
+        assert!(tm("ACGTTGCAAGGCTTCA", &options) > tm("ACGTTGCAAGG", &options));
+        assert!(tm("ACGTTGCAAGG", &options.with_salt(500.0)) > tm("ACGTTGCAAGG", &options));
+        assert_eq!(tm("A", &options), f64::NEG_INFINITY);
+    }
+}
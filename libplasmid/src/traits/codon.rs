@@ -44,19 +44,21 @@ macro_rules! impl_codon_traits {
             }
         }
 
-        impl ToString for $codon {
-            fn to_string(&self) -> String {
+        impl std::fmt::Display for $codon {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 use crate::traits::ToLetter;
-                self.to_triplet_arr()
+                let s: String = self
+                    .to_triplet_arr()
                     .iter()
                     .map(|base| base.to_letter())
-                    .collect()
+                    .collect();
+                write!(f, "{}", s)
             }
         }
 
-        impl Into<$codon> for [$base; 3] {
-            fn into(self) -> $codon {
-                <$codon>::from_triplet_arr(self)
+        impl From<[$base; 3]> for $codon {
+            fn from(triplet: [$base; 3]) -> $codon {
+                <$codon>::from_triplet_arr(triplet)
             }
         }
     };
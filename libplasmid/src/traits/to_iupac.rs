@@ -3,3 +3,12 @@ use crate::uni::IupacNucleotide;
 pub trait ToIupac {
     fn to_iupac(&self) -> IupacNucleotide;
 }
+
+impl<T> ToIupac for &T
+where
+    T: ToIupac + ?Sized,
+{
+    fn to_iupac(&self) -> IupacNucleotide {
+        (**self).to_iupac()
+    }
+}
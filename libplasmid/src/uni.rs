@@ -1,5 +1,17 @@
+pub mod enzyme_database;
+pub mod iupac_codon;
 pub mod iupac_nucleotide;
+pub mod methylation;
+pub mod pattern_scanner;
+pub mod reaction_buffer;
 pub mod restriction_enzymes;
+pub mod restriction_map;
 
+pub use self::enzyme_database::*;
+pub use self::iupac_codon::*;
 pub use self::iupac_nucleotide::*;
+pub use self::methylation::*;
+pub use self::pattern_scanner::*;
+pub use self::reaction_buffer::*;
 pub use self::restriction_enzymes::*;
+pub use self::restriction_map::*;
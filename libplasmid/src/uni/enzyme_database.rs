@@ -0,0 +1,409 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::OnceLock,
+};
+
+use crate::{
+    imp::{BairochFile, EmbossEnzymeFile, EmbossReferenceFile, EnzymeReference, Import},
+    seq::Topology,
+    traits::ToIupac,
+};
+
+use super::{
+    MethylationProfile, RestrictionEnzyme, RestrictionEnzymes, RestrictionSite,
+    RestrictionSiteScanner,
+};
+
+/// A collection of restriction enzymes to search for.
+///
+/// Databases are built from the built-in enzyme list, from REBASE files or from
+/// custom enzymes, and can be narrowed down to named subsets, e.g. the enzymes
+/// in the freezer. The site scanner of a database is built on first use.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+///
+/// let mut database = EnzymeDatabase::builtin();
+/// database.add_subset("freezer", ["EcoRI", "BamHI", "BsaI_HFv2"]).unwrap();
+///
+/// let freezer = database.subset("freezer").unwrap();
+/// assert_eq!(freezer.len(), 3);
+/// assert!(freezer.get("NotI").is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EnzymeDatabase {
+    enzymes: Vec<RestrictionEnzyme>,
+    references: HashMap<String, EnzymeReference>,
+    subsets: BTreeMap<String, Vec<String>>,
+    scanner: OnceLock<RestrictionSiteScanner>,
+}
+
+lazy_static! {
+    /// Database of the `RestrictionEnzymes`.
+    pub(crate) static ref BuiltinEnzymeDatabase: EnzymeDatabase = EnzymeDatabase::builtin();
+}
+
+impl EnzymeDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Database of the built-in `RestrictionEnzymes`, which are all supplied by NEB.
+    ///
+    /// The subset `"NEB HF"` contains the High-Fidelity variants.
+    pub fn builtin() -> Self {
+        let mut database = RestrictionEnzymes.iter().cloned().collect::<Self>();
+        for enzyme in RestrictionEnzymes.iter() {
+            database.insert_reference(EnzymeReference {
+                name: enzyme.name.clone(),
+                suppliers: "N".to_string(),
+                ..Default::default()
+            });
+        }
+        let hf = RestrictionEnzymes
+            .iter()
+            .filter(|enzyme| enzyme.name.contains("HF"))
+            .map(|enzyme| enzyme.name.clone())
+            .collect::<Vec<_>>();
+        database.subsets.insert("NEB HF".to_string(), hf);
+        database
+    }
+
+    /// Load a database from REBASE files in EMBOSS format, i.e. an `emboss_e.###`
+    /// enzyme file and optionally the matching `emboss_r.###` reference file.
+    pub fn load_emboss<P>(enzymes: P, references: Option<P>) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut database = EmbossEnzymeFile::import_from_path(enzymes)?
+            .enzymes
+            .into_iter()
+            .collect::<Self>();
+        if let Some(references) = references {
+            for reference in EmbossReferenceFile::import_from_path(references)?.references {
+                database.insert_reference(reference);
+            }
+        }
+        Ok(database)
+    }
+
+    /// Load a database from a REBASE file in Bairoch format (`bairoch.###`).
+    pub fn load_bairoch<P>(path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(BairochFile::import_from_path(path)?.into())
+    }
+
+    /// Add an enzyme, replacing any enzyme of the same name.
+    pub fn insert(&mut self, enzyme: RestrictionEnzyme) {
+        self.scanner = OnceLock::new();
+        match self.enzymes.iter_mut().find(|e| e.name == enzyme.name) {
+            Some(existing) => *existing = enzyme,
+            None => self.enzymes.push(enzyme),
+        }
+    }
+
+    /// Add the supplier information of an enzyme, replacing any previous one.
+    pub fn insert_reference(&mut self, reference: EnzymeReference) {
+        self.references.insert(reference.name.clone(), reference);
+    }
+
+    /// Merge the enzymes, references and subsets of another database into this one.
+    /// Enzymes of the other database replace the ones of the same name.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// let mut custom = EnzymeDatabase::new();
+    /// custom.insert(RestrictionEnzyme::from_cut("MyEnzyme", &[G, C, T, C, T, T, C], CutPositions::new(8, 11)));
+    ///
+    /// let mut database = EnzymeDatabase::builtin();
+    /// database.merge(custom);
+    /// assert!(database.get("MyEnzyme").is_some());
+    /// assert!(!database.is_commercially_available("MyEnzyme"));
+    /// ```
+    pub fn merge(&mut self, other: EnzymeDatabase) {
+        for enzyme in other.enzymes {
+            self.insert(enzyme);
+        }
+        self.references.extend(other.references);
+        self.subsets.extend(other.subsets);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RestrictionEnzyme> {
+        self.enzymes.iter().find(|enzyme| enzyme.name == name)
+    }
+
+    /// The supplier information of an enzyme, if known.
+    pub fn reference(&self, name: &str) -> Option<&EnzymeReference> {
+        self.references.get(name)
+    }
+
+    /// Whether an enzyme of the database can be bought from any supplier.
+    pub fn is_commercially_available(&self, name: &str) -> bool {
+        self.reference(name)
+            .is_some_and(|reference| reference.is_commercially_available())
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, RestrictionEnzyme> {
+        self.enzymes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.enzymes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.enzymes.is_empty()
+    }
+
+    /// Define a named subset of the enzymes of the database.
+    pub fn add_subset<I, T>(&mut self, name: &str, enzymes: I) -> anyhow::Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let enzymes = enzymes
+            .into_iter()
+            .map(|enzyme| {
+                let enzyme = enzyme.as_ref();
+                match self.get(enzyme) {
+                    Some(_) => Ok(enzyme.to_string()),
+                    None => Err(anyhow!("Unknown enzyme in subset {}: {}", name, enzyme)),
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.subsets.insert(name.to_string(), enzymes);
+        Ok(())
+    }
+
+    /// The names of the subsets of the database.
+    pub fn subset_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.subsets.keys().map(|name| name.as_str())
+    }
+
+    /// A database of the enzymes of a named subset.
+    pub fn subset(&self, name: &str) -> Option<EnzymeDatabase> {
+        let names = self.subsets.get(name)?;
+        Some(self.filter(|enzyme| names.contains(&enzyme.name)))
+    }
+
+    /// A database of the enzymes for which `predicate` returns true.
+    pub fn filter<F>(&self, predicate: F) -> EnzymeDatabase
+    where
+        F: Fn(&RestrictionEnzyme) -> bool,
+    {
+        let enzymes = self
+            .enzymes
+            .iter()
+            .filter(|enzyme| predicate(enzyme))
+            .cloned()
+            .collect::<Vec<_>>();
+        let references = enzymes
+            .iter()
+            .filter_map(|enzyme| self.references.get(&enzyme.name))
+            .map(|reference| (reference.name.clone(), reference.clone()))
+            .collect();
+        EnzymeDatabase {
+            enzymes,
+            references,
+            ..Default::default()
+        }
+    }
+
+    /// A database of the enzymes that can be bought from any supplier.
+    pub fn commercially_available(&self) -> EnzymeDatabase {
+        self.filter(|enzyme| self.is_commercially_available(&enzyme.name))
+    }
+
+    /// Group the enzymes that are isoschizomers of each other, in the order of
+    /// the database. Enzymes without isoschizomers form a group of their own.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let database = EnzymeDatabase::builtin();
+    /// let groups = database.isoschizomer_groups();
+    /// let apoi = groups.iter().find(|g| g[0].name == "ApoI_HF").unwrap();
+    /// assert_eq!(apoi.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), ["ApoI_HF", "ApoI"]);
+    /// ```
+    pub fn isoschizomer_groups(&self) -> Vec<Vec<&RestrictionEnzyme>> {
+        let mut groups: Vec<Vec<&RestrictionEnzyme>> = Vec::new();
+        for enzyme in self.enzymes.iter() {
+            match groups
+                .iter_mut()
+                .find(|group| group[0].is_isoschizomer_of(enzyme))
+            {
+                Some(group) => group.push(enzyme),
+                None => groups.push(vec![enzyme]),
+            }
+        }
+        groups
+    }
+
+    /// A database with only the first enzyme of each group of isoschizomers.
+    pub fn collapse_isoschizomers(&self) -> EnzymeDatabase {
+        let first = self
+            .isoschizomer_groups()
+            .into_iter()
+            .map(|group| group[0].name.clone())
+            .collect::<Vec<_>>();
+        self.filter(|enzyme| first.contains(&enzyme.name))
+    }
+
+    /// The enzymes recognizing the same site as `enzyme` but cutting it differently.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let database = EnzymeDatabase::builtin();
+    /// let names = database.neoschizomers("KpnI").iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(names, ["Acc65I"]);
+    /// ```
+    pub fn neoschizomers(&self, enzyme: &str) -> Vec<&RestrictionEnzyme> {
+        let Some(enzyme) = self.get(enzyme) else {
+            return Vec::new();
+        };
+        self.enzymes
+            .iter()
+            .filter(|other| other.is_neoschizomer_of(enzyme))
+            .collect()
+    }
+
+    /// Find the recognition sites of all enzymes in `seq`, see `RestrictionSiteScanner`.
+    /// The `enzyme` of each site is the index of the enzyme in `iter`.
+    /// Methylation isn't taken into account, see `scan_methylated`.
+    pub fn scan<I>(&self, seq: I, topology: Topology) -> Vec<RestrictionSite>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator + Clone,
+        I::Item: ToIupac,
+    {
+        self.scanner
+            .get_or_init(|| RestrictionSiteScanner::new(&self.enzymes))
+            .scan(seq, topology)
+    }
+
+    /// Find the recognition sites of all enzymes in `seq` like `scan`, flagging
+    /// the sites blocked by the methylation of a host with `profile`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let database = EnzymeDatabase::builtin().filter(|e| e.name == "XbaI");
+    /// // TCTAGA followed by TC forms GATC, so the XbaI site is Dam methylated
+    /// let seq = DnaSequence::from_str("TCTAGATC").unwrap();
+    ///
+    /// let sites = database.scan_methylated(seq.as_nucleotides(), Topology::Linear, &MethylationProfile::ecoli());
+    /// assert_eq!(sites[0].blocked_by, Some(Methylation::Dam));
+    /// ```
+    pub fn scan_methylated<I>(
+        &self,
+        seq: I,
+        topology: Topology,
+        profile: &MethylationProfile,
+    ) -> Vec<RestrictionSite>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator + Clone,
+        I::Item: ToIupac,
+    {
+        let seq = seq.into_iter();
+        let methylated = profile.methylated_nucleotides(seq.clone(), topology);
+        let mut sites = self.scan(seq.clone(), topology);
+        for site in sites.iter_mut() {
+            site.flag_blocked(&self.enzymes[site.enzyme], &methylated, seq.len());
+        }
+        sites
+    }
+}
+
+impl FromIterator<RestrictionEnzyme> for EnzymeDatabase {
+    fn from_iter<I: IntoIterator<Item = RestrictionEnzyme>>(iter: I) -> Self {
+        let mut database = Self::new();
+        iter.into_iter().for_each(|enzyme| database.insert(enzyme));
+        database
+    }
+}
+
+impl From<BairochFile> for EnzymeDatabase {
+    fn from(file: BairochFile) -> Self {
+        let mut database = file.enzymes.into_iter().collect::<Self>();
+        for reference in file.references {
+            database.insert_reference(reference);
+        }
+        database
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_enzyme_database_builtin() {
+        let database = EnzymeDatabase::builtin();
+        assert_eq!(database.len(), RestrictionEnzymes.len());
+        assert!(database.is_commercially_available("EcoRI"));
+        let hf = database.subset("NEB HF").unwrap();
+        assert!(hf.get("BsaI_HFv2").is_some());
+        assert!(hf.get("BsmBI_v2").is_none());
+        assert_eq!(database.subset_names().collect::<Vec<_>>(), ["NEB HF"]);
+    }
+
+    #[test]
+    fn test_enzyme_database_subsets() {
+        let mut database = EnzymeDatabase::builtin();
+        assert!(database.add_subset("freezer", ["EcoRI", "Foo"]).is_err());
+        database.add_subset("freezer", ["EcoRI", "NdeI"]).unwrap();
+        let freezer = database.subset("freezer").unwrap();
+        assert_eq!(freezer.len(), 2);
+        assert!(freezer.is_commercially_available("NdeI"));
+        assert!(database.subset("fridge").is_none());
+    }
+
+    #[test]
+    fn test_enzyme_database_insert_replaces() {
+        let mut database = EnzymeDatabase::builtin();
+        let len = database.len();
+        let site = database.get("EcoRI").unwrap().recognition_site();
+        database.insert(RestrictionEnzyme::from_cut(
+            "EcoRI",
+            &site,
+            CutPositions::new(3, 3),
+        ));
+        assert_eq!(database.len(), len);
+        assert_eq!(
+            database.get("EcoRI").unwrap().overhang_type(),
+            Some(OverhangType::Blunt)
+        );
+    }
+
+    #[test]
+    fn test_enzyme_database_load() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir();
+        let (enzymes, references) = (
+            dir.join("plasmid_emboss_e.test"),
+            dir.join("plasmid_emboss_r.test"),
+        );
+        std::fs::write(
+            &enzymes,
+            "EcoRI\tGAATTC\t6\t2\t0\t1\t5\t0\t0\nMyI\tGGATCC\t6\t2\t0\t1\t5\t0\t0\n",
+        )?;
+        std::fs::write(
+            &references,
+            "EcoRI\n\nEscherichia coli\n\n\nN\n0\n//\nMyI\n\n\n\n\n\n0\n//\n",
+        )?;
+        let database = EnzymeDatabase::load_emboss(&enzymes, Some(&references))?;
+        assert_eq!(database.len(), 2);
+        assert_eq!(database.commercially_available().len(), 1);
+        Ok(())
+    }
+}
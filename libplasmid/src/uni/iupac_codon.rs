@@ -0,0 +1,11 @@
+use crate::traits::*;
+
+use super::IupacNucleotide;
+
+/// Codon of possibly ambiguous nucleotides, e.g. of a `PackedIupacSequence`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct IupacCodon {
+    triplet: [IupacNucleotide; 3],
+}
+
+impl_codon_traits!(IupacNucleotide => IupacCodon);
@@ -0,0 +1,178 @@
+use std::fmt::Display;
+
+use crate::{seq::Topology, traits::ToIupac};
+
+use super::{IupacNucleotide, PatternScanner};
+
+/// A DNA methylation system of the host a plasmid was grown in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Methylation {
+    /// The A of `GATC` on both strands (E. coli Dam methylase)
+    Dam,
+    /// The internal C of `CCWGG` on both strands (E. coli Dcm methylase)
+    Dcm,
+    /// The C of `CG` on both strands (e.g. mammalian CpG methylation)
+    CpG,
+}
+
+impl Methylation {
+    /// The motif recognized by the methylase.
+    pub fn motif(&self) -> Vec<IupacNucleotide> {
+        use IupacNucleotide::*;
+        match self {
+            Methylation::Dam => vec![G, A, T, C],
+            Methylation::Dcm => vec![C, C, W, G, G],
+            Methylation::CpG => vec![C, G],
+        }
+    }
+
+    /// Positions of the methylated nucleotides within the motif, on the top and
+    /// on the bottom strand, in top strand coordinates.
+    pub fn methylated_positions(&self) -> [usize; 2] {
+        match self {
+            Methylation::Dam => [1, 2],
+            Methylation::Dcm => [1, 3],
+            Methylation::CpG => [0, 1],
+        }
+    }
+}
+
+impl Display for Methylation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Methylation::Dam => write!(f, "Dam"),
+            Methylation::Dcm => write!(f, "Dcm"),
+            Methylation::CpG => write!(f, "CpG"),
+        }
+    }
+}
+
+/// The methylation systems active in a host.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+///
+/// let profile = MethylationProfile::ecoli();
+/// let seq = DnaSequence::from_str("TCTAGATC").unwrap();
+///
+/// // The A of GATC lies within the XbaI site TCTAGA
+/// let methylated = profile.methylated_nucleotides(seq.as_nucleotides(), Topology::Linear);
+/// assert_eq!(methylated, [(5, Methylation::Dam), (6, Methylation::Dam)]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MethylationProfile {
+    pub dam: bool,
+    pub dcm: bool,
+    pub cpg: bool,
+}
+
+impl MethylationProfile {
+    /// No methylation, e.g. PCR products or DNA from dam-/dcm- strains like JM110.
+    pub fn none() -> Self {
+        Self {
+            dam: false,
+            dcm: false,
+            cpg: false,
+        }
+    }
+
+    /// Dam and Dcm methylation of most E. coli lab strains, e.g. DH5α or TOP10.
+    pub fn ecoli() -> Self {
+        Self {
+            dam: true,
+            dcm: true,
+            cpg: false,
+        }
+    }
+
+    /// CpG methylation of mammalian cells.
+    pub fn mammalian() -> Self {
+        Self {
+            dam: false,
+            dcm: false,
+            cpg: true,
+        }
+    }
+
+    /// The active methylation systems.
+    pub fn methylations(&self) -> impl Iterator<Item = Methylation> {
+        [
+            (self.dam, Methylation::Dam),
+            (self.dcm, Methylation::Dcm),
+            (self.cpg, Methylation::CpG),
+        ]
+        .into_iter()
+        .filter_map(|(active, methylation)| active.then_some(methylation))
+    }
+
+    /// Positions of all methylated nucleotides of `seq` on either strand, in order.
+    /// `seq` is a slice of nucleotides or an iterator over them, see `PatternScanner::scan`.
+    pub fn methylated_nucleotides<I>(&self, seq: I, topology: Topology) -> Vec<(usize, Methylation)>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator + Clone,
+        I::Item: ToIupac,
+    {
+        let seq = seq.into_iter();
+        let methylations = self.methylations().collect::<Vec<_>>();
+        let scanner = PatternScanner::new(methylations.iter().map(|m| m.motif()));
+        let len = seq.len();
+        let mut methylated = scanner
+            .scan(seq, topology)
+            .into_iter()
+            .flat_map(|m| {
+                let methylation = methylations[m.pattern];
+                methylation
+                    .methylated_positions()
+                    .map(|pos| ((m.start + pos) % len, methylation))
+            })
+            .collect::<Vec<_>>();
+        methylated.sort_by_key(|&(pos, _)| pos);
+        methylated.dedup();
+        methylated
+    }
+}
+
+impl Default for MethylationProfile {
+    /// No methylation.
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_methylated_nucleotides() {
+        let seq = DnaSequence::from_str("CCAGGATCG").unwrap();
+        let nucleotides = seq.as_nucleotides();
+        assert!(MethylationProfile::none()
+            .methylated_nucleotides(nucleotides, Topology::Linear)
+            .is_empty());
+        assert_eq!(
+            MethylationProfile::ecoli().methylated_nucleotides(nucleotides, Topology::Linear),
+            [
+                (1, Methylation::Dcm),
+                (3, Methylation::Dcm),
+                (5, Methylation::Dam),
+                (6, Methylation::Dam),
+            ]
+        );
+        assert_eq!(
+            MethylationProfile::mammalian().methylated_nucleotides(nucleotides, Topology::Linear),
+            [(7, Methylation::CpG), (8, Methylation::CpG)]
+        );
+    }
+
+    #[test]
+    fn test_methylated_nucleotides_circular() {
+        // GATC spanning the origin
+        let seq = DnaSequence::from_str("TCAAGA").unwrap();
+        let methylated = MethylationProfile::ecoli()
+            .methylated_nucleotides(seq.as_nucleotides(), Topology::Circular);
+        assert_eq!(methylated, [(0, Methylation::Dam), (5, Methylation::Dam)]);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{seq::Topology, traits::ToIupac};
+
+use super::IupacNucleotide;
+
+/// A match of one of the patterns of a `PatternScanner`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    /// Index of the pattern in the list the scanner was built from
+    pub pattern: usize,
+    /// Position of the first nucleotide of the match
+    pub start: usize,
+}
+
+/// Finds a set of IUPAC patterns in a single pass over a sequence.
+///
+/// The scanner runs a bit-parallel Shift-And automaton over all patterns at once:
+/// the patterns are laid out next to each other in one bit vector, and every
+/// nucleotide of the sequence advances all partial matches with a few word
+/// operations. Degenerate pattern nucleotides are handled by the match masks, so
+/// patterns are never expanded, and matching follows `IupacNucleotide::matches`
+/// like `GeneticSequence::find_pattern`.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::{*, IupacNucleotide::*};
+///
+/// let scanner = PatternScanner::new([vec![G, A, T, C], vec![C, A, N]]);
+/// let seq = DnaSequence::from_str("CAGATCA").unwrap();
+///
+/// let matches = scanner.scan(seq.as_nucleotides(), Topology::Linear);
+/// assert_eq!(matches, [
+///     PatternMatch { pattern: 1, start: 0 },
+///     PatternMatch { pattern: 0, start: 2 },
+/// ]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PatternScanner {
+    /// Bits of the pattern nucleotides matching each IUPAC nucleotide
+    masks: Vec<Vec<u64>>,
+    /// Bits of the first nucleotide of each pattern
+    initial: Vec<u64>,
+    /// Bits of the last nucleotide of each pattern
+    last: Vec<u64>,
+    /// The pattern ending at each bit
+    pattern_at: Vec<usize>,
+    lengths: Vec<usize>,
+}
+
+impl PatternScanner {
+    pub fn new<I, P>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[IupacNucleotide]>,
+    {
+        use IupacNucleotide::*;
+        let alphabet = [A, C, G, T, W, S, M, K, R, Y, B, D, H, V, N, Gap];
+        let patterns = patterns
+            .into_iter()
+            .map(|p| p.as_ref().to_vec())
+            .collect::<Vec<_>>();
+        let bits = patterns.iter().map(|p| p.len()).sum::<usize>();
+        let words = bits.div_ceil(64);
+        let set = |bitset: &mut Vec<u64>, bit: usize| bitset[bit / 64] |= 1 << (bit % 64);
+
+        let mut masks = vec![vec![0; words]; alphabet.len()];
+        let mut initial = vec![0; words];
+        let mut last = vec![0; words];
+        let mut pattern_at = vec![usize::MAX; bits];
+        let mut bit = 0;
+        for (index, pattern) in patterns.iter().enumerate() {
+            if pattern.is_empty() {
+                continue;
+            }
+            set(&mut initial, bit);
+            for p in pattern {
+                for (mask, n) in masks.iter_mut().zip(alphabet) {
+                    if p.matches(&n) {
+                        set(mask, bit);
+                    }
+                }
+                bit += 1;
+            }
+            set(&mut last, bit - 1);
+            pattern_at[bit - 1] = index;
+        }
+        Self {
+            masks,
+            initial,
+            last,
+            pattern_at,
+            lengths: patterns.iter().map(|p| p.len()).collect(),
+        }
+    }
+
+    /// Number of patterns of the scanner.
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Whether the scanner has no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    fn mask(&self, n: IupacNucleotide) -> &[u64] {
+        use IupacNucleotide::*;
+        let index = match n {
+            A => 0,
+            C => 1,
+            G => 2,
+            T => 3,
+            W => 4,
+            S => 5,
+            M => 6,
+            K => 7,
+            R => 8,
+            Y => 9,
+            B => 10,
+            D => 11,
+            H => 12,
+            V => 13,
+            N => 14,
+            Gap => 15,
+        };
+        &self.masks[index]
+    }
+
+    /// Find all matches of all patterns in `seq`, a slice of nucleotides or
+    /// an iterator over them, e.g. `GeneticSequence::nucleotide_iter`.
+    ///
+    /// On circular sequences, matches spanning the origin are found as well.
+    /// Patterns longer than the sequence never match. The matches are ordered
+    /// by their last nucleotide, then by pattern.
+    pub fn scan<I>(&self, seq: I, topology: Topology) -> Vec<PatternMatch>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator + Clone,
+        I::Item: ToIupac,
+    {
+        let seq = seq.into_iter();
+        let len = seq.len();
+        let longest = self.lengths.iter().copied().max().unwrap_or(0);
+        let wrapped = match topology {
+            Topology::Linear => 0,
+            Topology::Circular => longest.saturating_sub(1).min(len),
+        };
+
+        let mut state = vec![0u64; self.initial.len()];
+        let mut matches = Vec::new();
+        for (i, n) in seq.clone().chain(seq.take(wrapped)).enumerate() {
+            let mask = self.mask(n.to_iupac());
+            let mut carry = 0;
+            for (word, (s, (m, init))) in state
+                .iter_mut()
+                .zip(mask.iter().zip(&self.initial))
+                .enumerate()
+            {
+                let shifted = (*s << 1) | carry | init;
+                carry = *s >> 63;
+                *s = shifted & m;
+                let mut found = *s & self.last[word];
+                while found != 0 {
+                    let bit = word * 64 + found.trailing_zeros() as usize;
+                    let pattern = self.pattern_at[bit];
+                    let pattern_len = self.lengths[pattern];
+                    let start = i + 1 - pattern_len;
+                    if start < len && pattern_len <= len {
+                        matches.push(PatternMatch { pattern, start });
+                    }
+                    found &= found - 1;
+                }
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::{PatternMatch, PatternScanner};
+
+    #[test]
+    fn test_pattern_scanner_matches_find_pattern() {
+        // Deterministic pseudo-random sequence
+        let mut state = 7u64;
+        let seq = (0..2000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ['A', 'C', 'G', 'T'][(state >> 33) as usize % 4]
+            })
+            .collect::<String>();
+        let mut seq = DnaSequence::from_str(seq).unwrap();
+        let patterns = RestrictionEnzymes
+            .iter()
+            .map(|enzyme| enzyme.recognition_site())
+            .collect::<Vec<_>>();
+        let scanner = PatternScanner::new(&patterns);
+        assert_eq!(scanner.len(), patterns.len());
+
+        for topology in [Topology::Linear, Topology::Circular] {
+            seq.set_topology(topology);
+            let mut found = scanner.scan(seq.as_nucleotides(), topology);
+            found.sort_by_key(|m| (m.pattern, m.start));
+            let expected = patterns
+                .iter()
+                .enumerate()
+                .flat_map(|(pattern, p)| {
+                    seq.find_pattern(p)
+                        .into_iter()
+                        .map(move |start| PatternMatch { pattern, start })
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_pattern_scanner_circular() {
+        use IupacNucleotide::*;
+        let scanner = PatternScanner::new([vec![T, A, A], vec![A, T, A, A, T]]);
+        let seq = DnaSequence::from_str("AATAT").unwrap();
+        assert!(scanner
+            .scan(seq.as_nucleotides(), Topology::Linear)
+            .is_empty());
+        assert_eq!(
+            scanner.scan(seq.as_nucleotides(), Topology::Circular),
+            [
+                PatternMatch {
+                    pattern: 0,
+                    start: 4
+                },
+                PatternMatch {
+                    pattern: 1,
+                    start: 3
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt::Display;
+
+/// A restriction enzyme reaction buffer of the NEB buffer system.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ReactionBuffer {
+    NEBuffer1_1,
+    NEBuffer2_1,
+    NEBuffer3_1,
+    CutSmart,
+}
+
+impl ReactionBuffer {
+    /// All buffers, in the order of `RestrictionEnzyme::buffer_activities`.
+    pub fn all() -> [ReactionBuffer; 4] {
+        [
+            ReactionBuffer::NEBuffer1_1,
+            ReactionBuffer::NEBuffer2_1,
+            ReactionBuffer::NEBuffer3_1,
+            ReactionBuffer::CutSmart,
+        ]
+    }
+}
+
+impl Display for ReactionBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReactionBuffer::NEBuffer1_1 => write!(f, "NEBuffer r1.1"),
+            ReactionBuffer::NEBuffer2_1 => write!(f, "NEBuffer r2.1"),
+            ReactionBuffer::NEBuffer3_1 => write!(f, "NEBuffer r3.1"),
+            ReactionBuffer::CutSmart => write!(f, "rCutSmart"),
+        }
+    }
+}
@@ -1,7 +1,18 @@
 #![allow(non_upper_case_globals)]
 
-use crate::uni::{IupacNucleotide, IupacNucleotide::*};
+use std::cmp::Ordering;
 
+use crate::{
+    seq::{OverhangType, Strand, Topology},
+    traits::{Nucleotide, ToIupac},
+    uni::{
+        IupacNucleotide, IupacNucleotide::*, Methylation, MethylationProfile, PatternScanner,
+        ReactionBuffer,
+    },
+};
+
+/// How a restriction enzyme cuts, superseded by `CutPositions`.
+#[deprecated(note = "the cuts of an enzyme are described by `CutPositions`")]
 #[derive(Debug, PartialEq, Eq)]
 pub enum CutMode {
     /// Cut in the middle
@@ -10,224 +21,1159 @@ pub enum CutMode {
     A,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Cut positions of a restriction enzyme on both strands.
+///
+/// Positions are relative to the first nucleotide of the recognition site and
+/// given in top strand coordinates, as the position of the first nucleotide after
+/// the cut. They may lie outside of the recognition site, e.g. `-1` is a cut just
+/// before the site. Nicking enzymes cut only one of the strands.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CutPositions {
+    pub top: Option<isize>,
+    pub bottom: Option<isize>,
+}
+
+impl CutPositions {
+    /// Cuts on both strands.
+    pub fn new(top: isize, bottom: isize) -> Self {
+        Self {
+            top: Some(top),
+            bottom: Some(bottom),
+        }
+    }
+
+    /// A nick on the top strand only.
+    pub fn top_nick(top: isize) -> Self {
+        Self {
+            top: Some(top),
+            bottom: None,
+        }
+    }
+
+    /// A nick on the bottom strand only.
+    pub fn bottom_nick(bottom: isize) -> Self {
+        Self {
+            top: None,
+            bottom: Some(bottom),
+        }
+    }
+
+    /// Whether only one of the strands is cut.
+    pub fn is_nick(&self) -> bool {
+        self.top.is_none() || self.bottom.is_none()
+    }
+
+    /// The type of the ends produced by the cut, `None` for nicks.
+    pub fn overhang_type(&self) -> Option<OverhangType> {
+        let (top, bottom) = (self.top?, self.bottom?);
+        Some(match top.cmp(&bottom) {
+            Ordering::Less => OverhangType::FivePrime,
+            Ordering::Equal => OverhangType::Blunt,
+            Ordering::Greater => OverhangType::ThreePrime,
+        })
+    }
+
+    /// The cut positions as seen from the other strand, for a recognition site
+    /// of `site_len` nucleotides.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// // BsaI: GGTCTC(1/5) is GAGACC(-5/-1) on the other strand
+    /// assert_eq!(CutPositions::new(7, 11).mirrored(6), CutPositions::new(-5, -1));
+    /// assert_eq!(CutPositions::bottom_nick(5).mirrored(6), CutPositions::top_nick(1));
+    /// ```
+    pub fn mirrored(&self, site_len: usize) -> Self {
+        let mirror = |pos: isize| site_len as isize - pos;
+        Self {
+            top: self.bottom.map(mirror),
+            bottom: self.top.map(mirror),
+        }
+    }
+
+    /// Number of single-stranded nucleotides at the ends produced by the cut.
+    pub fn overhang_len(&self) -> usize {
+        match (self.top, self.bottom) {
+            (Some(top), Some(bottom)) => top.abs_diff(bottom),
+            _ => 0,
+        }
+    }
+}
+
+/// A restriction enzyme and its recognition site.
+///
+/// The recognition site is split into the nucleotides `before` and `after` the
+/// cut on the top strand, see `cut` for the exact cut positions on both strands.
+/// Enzymes cutting outside of their recognition site, like the Type IIS enzymes
+/// used for Golden Gate assembly, keep the whole site in `before`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct RestrictionEnzyme {
     pub name: String,
     pub before: Vec<IupacNucleotide>,
     pub after: Vec<IupacNucleotide>,
-    mode: CutMode,
+    pub cut: CutPositions,
+    /// The cut before the recognition site of enzymes that cut on both
+    /// sides of it, e.g. BaeI.
+    pub upstream_cut: Option<CutPositions>,
+    /// Methylations that block the enzyme if they overlap its recognition site
+    pub blocked_by: Vec<Methylation>,
+    /// Methylations the enzyme needs at its recognition site to cut, e.g. Dam for DpnI
+    pub requires_methylation: Vec<Methylation>,
 }
 
 impl RestrictionEnzyme {
+    /// Create an enzyme which cuts the top strand between `before` and `after`,
+    /// see `from_halves`. The cut mode is ignored.
+    #[deprecated(note = "use `from_halves`, the cut positions are set with `with_cut`")]
+    #[allow(deprecated)]
     pub fn new(
         name: &str,
         before: &[IupacNucleotide],
         after: &[IupacNucleotide],
-        mode: CutMode,
+        _mode: CutMode,
     ) -> Self {
+        Self::from_halves(name, before, after)
+    }
+
+    /// Create an enzyme which cuts the top strand between `before` and `after`.
+    ///
+    /// The bottom strand is cut at the mirrored position, as is the case
+    /// for enzymes with palindromic recognition sites.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// let enzyme = RestrictionEnzyme::from_halves("EcoRI", &[G], &[A, A, T, T, C]);
+    /// assert_eq!(enzyme.cut, CutPositions::new(1, 5));
+    /// assert_eq!(enzyme.overhang_type(), Some(OverhangType::FivePrime));
+    /// ```
+    pub fn from_halves(name: &str, before: &[IupacNucleotide], after: &[IupacNucleotide]) -> Self {
+        let (before_len, after_len) = (before.len() as isize, after.len() as isize);
         Self {
             name: name.to_string(),
             before: Vec::from_iter(before.iter().copied()),
             after: Vec::from_iter(after.iter().copied()),
-            mode,
+            cut: CutPositions::new(before_len, after_len),
+            upstream_cut: None,
+            blocked_by: Vec::new(),
+            requires_methylation: Vec::new(),
         }
     }
+
+    /// Create an enzyme with a recognition site and its cut positions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// let enzyme = RestrictionEnzyme::from_cut("PstI", &[C, T, G, C, A, G], CutPositions::new(5, 1));
+    /// assert_eq!(enzyme.before, [C, T, G, C, A]);
+    /// assert_eq!(enzyme.overhang_type(), Some(OverhangType::ThreePrime));
+    /// ```
+    pub fn from_cut(name: &str, site: &[IupacNucleotide], cut: CutPositions) -> Self {
+        let split = cut
+            .top
+            .and_then(|top| usize::try_from(top).ok())
+            .filter(|&top| top <= site.len())
+            .unwrap_or(site.len());
+        Self::from_halves(name, &site[..split], &site[split..]).with_cut(cut)
+    }
+
+    /// The built-in enzyme with the name `name`, see `RestrictionEnzymes`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let ecori = RestrictionEnzyme::by_name("EcoRI").unwrap();
+    /// assert_eq!(ecori.overhang_type(), Some(OverhangType::FivePrime));
+    /// assert!(RestrictionEnzyme::by_name("MyEco").is_none());
+    /// ```
+    pub fn by_name(name: &str) -> Option<&'static RestrictionEnzyme> {
+        RestrictionEnzymes.iter().find(|enzyme| enzyme.name == name)
+    }
+
+    /// Set the cut positions of the enzyme.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// // Nb.BsmI: GAATG/C on the bottom strand only
+    /// let enzyme = RestrictionEnzyme::from_halves("Nb.BsmI", &[G, A, A, T, G, C], &[])
+    ///     .with_cut(CutPositions::bottom_nick(5));
+    /// assert!(enzyme.is_nicking());
+    /// assert_eq!(enzyme.overhang_type(), None);
+    /// ```
+    pub fn with_cut(mut self, cut: CutPositions) -> Self {
+        self.cut = cut;
+        self
+    }
+
+    /// Set the cut before the recognition site, for enzymes that cut on both sides.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// // BaeI: (10/15)ACNNNNGTAYC(12/7)
+    /// let enzyme = RestrictionEnzyme::from_halves("BaeI", &[A, C, N, N, N, N, G, T, A, Y, C], &[])
+    ///     .with_cut(CutPositions::new(23, 18))
+    ///     .with_upstream_cut(CutPositions::new(-10, -15));
+    /// assert_eq!(enzyme.cuts().count(), 2);
+    /// assert!(enzyme.cuts_outside());
+    /// ```
+    pub fn with_upstream_cut(mut self, cut: CutPositions) -> Self {
+        self.upstream_cut = Some(cut);
+        self
+    }
+
+    /// Set the methylations that block the enzyme if they overlap its recognition site.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let enzyme = RestrictionEnzyme::by_name("XbaI").unwrap();
+    /// assert!(enzyme.is_blocked_by(Methylation::Dam));
+    /// assert!(!enzyme.is_blocked_by(Methylation::CpG));
+    /// ```
+    pub fn with_blocked_by(mut self, methylations: &[Methylation]) -> Self {
+        self.blocked_by = methylations.to_vec();
+        self
+    }
+
+    /// Whether the enzyme is blocked by `methylation` overlapping its recognition site.
+    pub fn is_blocked_by(&self, methylation: Methylation) -> bool {
+        self.blocked_by.contains(&methylation)
+    }
+
+    /// Set the methylations the enzyme needs at its recognition site to cut.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let enzyme = RestrictionEnzyme::by_name("DpnI").unwrap();
+    /// assert!(enzyme.requires(Methylation::Dam));
+    /// assert!(!RestrictionEnzyme::by_name("DpnII").unwrap().requires(Methylation::Dam));
+    /// ```
+    pub fn with_requires_methylation(mut self, methylations: &[Methylation]) -> Self {
+        self.requires_methylation = methylations.to_vec();
+        self
+    }
+
+    /// Whether the enzyme only cuts its recognition site if `methylation` overlaps it.
+    pub fn requires(&self, methylation: Methylation) -> bool {
+        self.requires_methylation.contains(&methylation)
+    }
+
+    /// All cuts of the enzyme, from the first to the last one along the top strand.
+    pub fn cuts(&self) -> impl Iterator<Item = CutPositions> + '_ {
+        self.upstream_cut.into_iter().chain([self.cut])
+    }
+
+    /// Approximate activity of the enzyme in each of `ReactionBuffer::all()`, in percent.
+    ///
+    /// Only known for common cloning enzymes of the built-in list, looked up by name.
+    pub fn buffer_activities(&self) -> Option<[u8; 4]> {
+        BufferActivity
+            .iter()
+            .find(|(name, _)| *name == self.name)
+            .map(|(_, activities)| *activities)
+    }
+
+    /// Approximate activity of the enzyme in `buffer`, in percent.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// assert_eq!(RestrictionEnzyme::by_name("XbaI").unwrap().buffer_activity(ReactionBuffer::CutSmart), Some(100));
+    /// assert_eq!(RestrictionEnzyme::by_name("NotI").unwrap().buffer_activity(ReactionBuffer::NEBuffer1_1), Some(0));
+    /// assert_eq!(RestrictionEnzyme::by_name("HinfI").unwrap().buffer_activity(ReactionBuffer::CutSmart), None);
+    /// ```
+    pub fn buffer_activity(&self, buffer: ReactionBuffer) -> Option<u8> {
+        let index = ReactionBuffer::all().iter().position(|&b| b == buffer)?;
+        self.buffer_activities().map(|activities| activities[index])
+    }
+
+    /// Whether any of the cuts lies outside of the recognition site.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// assert!(RestrictionEnzyme::by_name("BsaI_HFv2").unwrap().cuts_outside());
+    /// assert!(!RestrictionEnzyme::by_name("EcoRI").unwrap().cuts_outside());
+    /// ```
+    pub fn cuts_outside(&self) -> bool {
+        let site = 0..=self.recognition_site().len() as isize;
+        self.cuts()
+            .flat_map(|cut| cut.top.into_iter().chain(cut.bottom))
+            .any(|pos| !site.contains(&pos))
+    }
+
+    /// The recognition site of the enzyme.
+    pub fn recognition_site(&self) -> Vec<IupacNucleotide> {
+        self.before.iter().chain(&self.after).copied().collect()
+    }
+
+    /// Whether the recognition site reads the same on both strands.
+    ///
+    /// Sites that are not palindromic have to be searched for on both strands.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// assert!(RestrictionEnzyme::by_name("EcoRI").unwrap().is_palindromic());
+    /// assert!(!RestrictionEnzyme::by_name("BsaI_HFv2").unwrap().is_palindromic());
+    /// ```
+    pub fn is_palindromic(&self) -> bool {
+        let site = self.recognition_site();
+        site.iter()
+            .rev()
+            .map(|n| n.complement())
+            .eq(site.iter().copied())
+    }
+
+    /// The recognition site of the enzyme on the other strand, read along the top strand.
+    pub fn reverse_complement_site(&self) -> Vec<IupacNucleotide> {
+        self.recognition_site()
+            .iter()
+            .rev()
+            .map(|n| n.complement())
+            .collect()
+    }
+
+    /// The cuts of the enzyme at its site on the reverse strand, from the first to
+    /// the last one along the top strand.
+    fn reverse_cuts(&self) -> Vec<CutPositions> {
+        let site_len = self.recognition_site().len();
+        let mut cuts = self
+            .cuts()
+            .map(|cut| cut.mirrored(site_len))
+            .collect::<Vec<_>>();
+        cuts.reverse();
+        cuts
+    }
+
+    /// Whether both enzymes recognize the same site, on either strand.
+    pub fn has_same_site(&self, other: &RestrictionEnzyme) -> bool {
+        let site = self.recognition_site();
+        site == other.recognition_site() || site == other.reverse_complement_site()
+    }
+
+    /// Whether both enzymes recognize the same site and cut it at the same positions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// assert!(RestrictionEnzyme::by_name("MspI").unwrap().is_isoschizomer_of(RestrictionEnzyme::by_name("HpaII").unwrap()));
+    /// assert!(!RestrictionEnzyme::by_name("XmaI").unwrap().is_isoschizomer_of(RestrictionEnzyme::by_name("SmaI").unwrap()));
+    /// ```
+    pub fn is_isoschizomer_of(&self, other: &RestrictionEnzyme) -> bool {
+        let cuts = self.cuts().collect::<Vec<_>>();
+        (self.recognition_site() == other.recognition_site()
+            && cuts == other.cuts().collect::<Vec<_>>())
+            || (self.recognition_site() == other.reverse_complement_site()
+                && cuts == other.reverse_cuts())
+    }
+
+    /// Whether both enzymes recognize the same site, but cut it at different positions.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// // C/CCGGG and CCC/GGG
+    /// assert!(RestrictionEnzyme::by_name("XmaI").unwrap().is_neoschizomer_of(RestrictionEnzyme::by_name("SmaI").unwrap()));
+    /// ```
+    pub fn is_neoschizomer_of(&self, other: &RestrictionEnzyme) -> bool {
+        self.has_same_site(other) && !self.is_isoschizomer_of(other)
+    }
+
+    /// The number of specified nucleotides of the recognition site, i.e. not
+    /// counting `N`, e.g. 6 for BglI (`GCCNNNNNGGC`).
+    pub fn specificity(&self) -> usize {
+        self.recognition_site().iter().filter(|&&n| n != N).count()
+    }
+
+    /// Whether the enzyme cuts only one of the strands.
+    pub fn is_nicking(&self) -> bool {
+        self.cut.is_nick()
+    }
+
+    /// The type of the ends produced by the enzyme, `None` for nicking enzymes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// assert_eq!(RestrictionEnzyme::by_name("EcoRI").unwrap().overhang_type(), Some(OverhangType::FivePrime));
+    /// assert_eq!(RestrictionEnzyme::by_name("PstI").unwrap().overhang_type(), Some(OverhangType::ThreePrime));
+    /// assert_eq!(RestrictionEnzyme::by_name("EcoRV").unwrap().overhang_type(), Some(OverhangType::Blunt));
+    /// ```
+    pub fn overhang_type(&self) -> Option<OverhangType> {
+        self.cut.overhang_type()
+    }
+
+    /// The single-stranded nucleotides of the ends produced by the enzyme, read 5'
+    /// to 3' along the top strand. Nucleotides outside of the recognition site are `N`.
+    /// For enzymes cutting on both sides of their site, this is the overhang of `cut`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::{*, IupacNucleotide::*};
+    ///
+    /// assert_eq!(RestrictionEnzyme::by_name("EcoRI").unwrap().overhang(), [A, A, T, T]);
+    /// assert_eq!(RestrictionEnzyme::by_name("PstI").unwrap().overhang(), [T, G, C, A]);
+    /// assert!(RestrictionEnzyme::by_name("EcoRV").unwrap().overhang().is_empty());
+    /// assert_eq!(RestrictionEnzyme::by_name("BsaI_HFv2").unwrap().overhang(), [N, N, N, N]);
+    /// ```
+    pub fn overhang(&self) -> Vec<IupacNucleotide> {
+        let (top, bottom) = match (self.cut.top, self.cut.bottom) {
+            (Some(top), Some(bottom)) => (top, bottom),
+            _ => return Vec::new(),
+        };
+        let site = self.recognition_site();
+        (top.min(bottom)..top.max(bottom))
+            .map(|i| {
+                usize::try_from(i)
+                    .ok()
+                    .and_then(|i| site.get(i).copied())
+                    .unwrap_or(N)
+            })
+            .collect()
+    }
 }
 
 macro_rules! define_enzyme {
-    ($mode:ident; $name:ident: $before:expr, $after:expr) => {
-        RestrictionEnzyme::new(stringify!($name), &$before, &$after, CutMode::$mode)
+    ($name:ident: $before:expr, $after:expr) => {
+        RestrictionEnzyme::from_halves(stringify!($name), &$before, &$after)
+    };
+    ($name:ident: $before:expr, $after:expr; top $top:expr) => {
+        define_enzyme!($name: $before, $after).with_cut(CutPositions::top_nick($top))
+    };
+    ($name:ident: $before:expr, $after:expr; bottom $bottom:expr) => {
+        define_enzyme!($name: $before, $after).with_cut(CutPositions::bottom_nick($bottom))
+    };
+    ($name:ident: $before:expr, $after:expr; upstream $up_top:expr, $up_bottom:expr; $top:expr, $bottom:expr) => {
+        define_enzyme!($name: $before, $after; $top, $bottom)
+            .with_upstream_cut(CutPositions::new($up_top, $up_bottom))
+    };
+    ($name:ident: $before:expr, $after:expr; $top:expr, $bottom:expr) => {
+        define_enzyme!($name: $before, $after).with_cut(CutPositions::new($top, $bottom))
     };
 }
 
 lazy_static! {
-    pub static ref RestrictionEnzymes: Vec<RestrictionEnzyme> = vec![
-        define_enzyme!(M; AclI: [A,A], [C,G,T,T]),
-        define_enzyme!(M; HindIII: [A], [A,G,C,T,T]),
-        define_enzyme!(M; SspI: [A,A,T], [A,T,T]),
-        define_enzyme!(M; MluCI: [], [A,A,T,T]),
-        define_enzyme!(M; PciI: [A], [C,A,T,G,T]),
-        define_enzyme!(M; AgeI: [A], [C,C,G,G,T]),
-        define_enzyme!(M; SexAI: [A], [C,C,W,G,G,T]),
-        define_enzyme!(M; MluI: [A], [C,G,C,G,T]),
-        define_enzyme!(M; HpyCH4IV: [A], [C,G,T]),
-        define_enzyme!(M; HpyCH4III: [A,C,N], [G,T]),
-        define_enzyme!(M; AflIII: [A], [C,R,Y,G,T]),
-        define_enzyme!(M; SpeI: [A], [C,T,A,G,T]),
-        define_enzyme!(M; BglII: [A], [G,A,T,C,T]),
-        define_enzyme!(M; AfeI: [A,G,C], [G,C,T]),
-        define_enzyme!(M; AluI: [A,G], [C,T]),
-        define_enzyme!(M; StuI: [A,G,G], [C,C,T]),
-        define_enzyme!(M; ScaI: [A,G,T], [A,C,T]),
-        define_enzyme!(M; ClaI: [A,T], [C,G,A,T]),
-        define_enzyme!(M; BspDI: [A,T], [C,G,A,T]),
-        define_enzyme!(M; NsiI: [A,T,G,C,A], [T]),
-        define_enzyme!(M; AseI: [A,T], [T,A,A,T]),
-        define_enzyme!(M; SwaI: [A,T,T,T], [A,A,A,T]),
-        define_enzyme!(M; MfeI: [C], [A,A,T,T,G]),
-        define_enzyme!(M; NbBssSI: [C,A,C,G,A,G], []),
-        define_enzyme!(M; PmlI: [C,A,C], [G,T,G]),
-        define_enzyme!(M; DraIII: [C,A,C,N,N,N], [G,T,G]),
-        define_enzyme!(M; AleI_v2: [C,A,C,N,N], [N,N,G,T,G]),
-        define_enzyme!(M; PvuII: [C,A,G], [C,T,G]),
-        define_enzyme!(M; AlwNI: [C,A,G,N,N,N], [C,T,G]),
-        define_enzyme!(M; NdeI: [C,A], [T,A,T,G]),
-        define_enzyme!(M; FatI: [], [C,A,T,G]),
-        define_enzyme!(M; CviAII: [C], [A,T,G]),
-        define_enzyme!(M; NlaIII: [C,A,T,G], []),
-        define_enzyme!(M; MslI: [C,A,Y,N,N], [N,N,R,T,G]),
-        define_enzyme!(M; XcmI: [C,C,A,N,N,N,N,N], [N,N,N,N,T,G,G]),
-        define_enzyme!(M; BstXI: [C,C,A,N,N,N,N,N], [N,T,G,G]),
-        define_enzyme!(M; PflMI: [C,C,A,N,N,N,N], [N,T,G,G]),
-        define_enzyme!(M; NcoI: [C], [C,A,T,G,G]),
-        define_enzyme!(M; SmaI: [C,C,C], [G,G,G]),
-        define_enzyme!(M; TspMI: [C], [C,C,G,G,G]),
-        define_enzyme!(M; XmaI: [C], [C,C,G,G,G]),
-        define_enzyme!(M; SacII: [C,C,G,C], [G,G]),
-        define_enzyme!(M; MspI: [C], [C,G,G]),
-        define_enzyme!(M; HpaII: [C], [C,G,G]),
-        define_enzyme!(M; StyD4I: [], [C,C,N,G,G]),
-        define_enzyme!(M; ScrFI: [C,C], [N,G,G]),
-        define_enzyme!(M; BsaJI: [C], [C,N,N,G,G]),
-        define_enzyme!(M; BslI: [C,C,N,N,N,N,N], [N,N,G,G]),
-        define_enzyme!(M; BtgI: [C], [C,R,Y,G,G]),
-        define_enzyme!(M; NciI: [C,C], [S,G,G]),
-        define_enzyme!(M; AvrII: [C], [C,T,A,G,G]),
-        define_enzyme!(M; NbBbvCI: [C,C,T,C,A,G,C], []),
-        define_enzyme!(M; SbfI: [C,C,T,G,C,A], [G,G]),
-        define_enzyme!(M; Bsu36I: [C,C], [T,N,A,G,G]),
-        define_enzyme!(M; EcoNI: [C,C,T,N,N], [N,N,N,A,G,G]),
-        define_enzyme!(M; PspGI: [], [C,C,W,G,G]),
-        define_enzyme!(M; BstNI: [C,C], [W,G,G]),
-        define_enzyme!(M; StyI: [C], [C,W,W,G,G]),
-        define_enzyme!(M; PvuI: [C,G,A,T], [C,G]),
-        define_enzyme!(M; BstUI: [C,G], [C,G]),
-        define_enzyme!(M; EagI: [C], [G,G,C,C,G]),
-        define_enzyme!(M; RsrII: [C,G], [G,W,C,C,G]),
-        define_enzyme!(M; BsiEI: [C,G,R,Y], [C,G]),
-        define_enzyme!(M; BsiWI: [C], [G,T,A,C,G]),
-        define_enzyme!(M; BsmBI_v2: [C,G,T,C,T,C], []),
-        define_enzyme!(M; Hpy99I: [C,G,W,C,G], []),
-        define_enzyme!(M; MspA1I: [C,M,G], [C,K,G]),
-        define_enzyme!(M; AbaSI: [C,N,N,N,N,N,N,N,N,N,N,N], [N,N,N,N,N,N,N,N,N,G]),
-        define_enzyme!(M; SgrAI: [C,R], [C,C,G,G,Y,G]),
-        define_enzyme!(M; BfaI: [C], [T,A,G]),
-        define_enzyme!(M; XhoI: [C], [T,C,G,A,G]),
-        define_enzyme!(M; PaeR7I: [C], [T,C,G,A,G]),
-        define_enzyme!(M; PstI: [C,T,G,C,A], [G]),
-        define_enzyme!(M; DdeI: [C], [T,N,A,G]),
-        define_enzyme!(M; SfcI: [C], [T,R,Y,A,G]),
-        define_enzyme!(M; AflII: [C], [T,T,A,A,G]),
-        define_enzyme!(M; SmlI: [C], [T,Y,R,A,G]),
-        define_enzyme!(M; BsoBI: [C], [Y,C,G,R,G]),
-        define_enzyme!(M; AvaI: [C], [Y,C,G,R,G]),
-        define_enzyme!(M; XmnI: [G,A,A,N,N], [N,N,T,T,C]),
-        define_enzyme!(M; NbBsmI: [G,A,A,T,G,C], []),
-        define_enzyme!(M; EcoRI: [G], [A,A,T,T,C]),
-        define_enzyme!(M; AatII: [G,A,C,G,T], [C]),
-        define_enzyme!(M; ZraI: [G,A,C], [G,T,C]),
-        define_enzyme!(M; PflFI: [G,A,C,N], [N,N,G,T,C]),
-        define_enzyme!(M; Tth111I: [G,A,C,N], [N,N,G,T,C]),
-        define_enzyme!(M; PshAI: [G,A,C,N,N], [N,N,G,T,C]),
-        define_enzyme!(M; AhdI: [G,A,C,N,N,N], [N,N,G,T,C]),
-        define_enzyme!(M; DrdI: [G,A,C,N,N,N,N], [N,N,G,T,C]),
-        define_enzyme!(M; Eco53kI: [G,A,G], [C,T,C]),
-        define_enzyme!(M; SacI: [G,A,G,C,T], [C]),
-        define_enzyme!(M; HinfI: [G], [A,N,T,C]),
-        define_enzyme!(M; EcoRV: [G,A,T], [A,T,C]),
-        define_enzyme!(M; DpnII: [], [G,A,T,C]),
-        define_enzyme!(M; MboI: [], [G,A,T,C]),
-        define_enzyme!(M; Sau3AI: [], [G,A,T,C]),
-        define_enzyme!(M; DpnI: [G,A], [T,C]),
-        define_enzyme!(M; BsaBI: [G,A,T,N,N], [N,N,A,T,C]),
-        define_enzyme!(M; TfiI: [G], [A,W,T,C]),
-        define_enzyme!(M; NbBsrDI: [G,C,A,A,T,G], []),
-        define_enzyme!(M; NbBtsI: [G,C,A,G,T,G], []),
-        define_enzyme!(M; BstAPI: [G,C,A,N,N,N,N], [N,T,G,C]),
-        define_enzyme!(M; SphI: [G,C,A,T,G], [C]),
-        define_enzyme!(M; SrfI: [G,C,C,C], [G,G,G,C]),
-        define_enzyme!(M; NgoMIV: [G], [C,C,G,G,C]),
-        define_enzyme!(M; NaeI: [G,C,C], [G,G,C]),
-        define_enzyme!(M; BglI: [G,C,C,N,N,N,N], [N,G,G,C]),
-        define_enzyme!(M; AsiSI: [G,C,G,A,T], [C,G,C]),
-        define_enzyme!(M; HhaI: [G,C,G], [C]),
-        define_enzyme!(M; HinP1I: [G], [C,G,C]),
-        define_enzyme!(M; BssHII: [G], [C,G,C,G,C]),
-        define_enzyme!(M; NotI: [G,C], [G,G,C,C,G,C]),
-        define_enzyme!(M; Fnu4HI: [G,C], [N,G,C]),
-        define_enzyme!(M; Cac8I: [G,C,N], [N,G,C]),
-        define_enzyme!(M; MwoI: [G,C,N,N,N,N,N], [N,N,G,C]),
-        define_enzyme!(M; BmtI: [G,C,T,A,G], [C]),
-        define_enzyme!(M; NheI: [G], [C,T,A,G,C]),
-        define_enzyme!(M; BlpI: [G,C], [T,N,A,G,C]),
-        define_enzyme!(M; TseI: [G], [C,W,G,C]),
-        define_enzyme!(M; ApeKI: [G], [C,W,G,C]),
-        define_enzyme!(M; Bsp1286I: [G,D,G,C,H], [C]),
-        define_enzyme!(M; BamHI: [G], [G,A,T,C,C]),
-        define_enzyme!(M; HaeIII: [G,G], [C,C]),
-        define_enzyme!(M; FseI: [G,G,C,C,G,G], [C,C]),
-        define_enzyme!(M; SfiI: [G,G,C,C,N,N,N,N], [N,G,G,C,C]),
-        define_enzyme!(M; NarI: [G,G], [C,G,C,C]),
-        define_enzyme!(M; SfoI: [G,G,C], [G,C,C]),
-        define_enzyme!(M; KasI: [G], [G,C,G,C,C]),
-        define_enzyme!(M; PluTI: [G,G,C,G,C], [C]),
-        define_enzyme!(M; AscI: [G,G], [C,G,C,G,C,C]),
-        define_enzyme!(M; PspOMI: [G], [G,G,C,C,C]),
-        define_enzyme!(M; ApaI: [G,G,G,C,C], [C]),
-        define_enzyme!(M; Sau96I: [G], [G,N,C,C]),
-        define_enzyme!(M; NlaIV: [G,G,N], [N,C,C]),
-        define_enzyme!(M; Acc65I: [G], [G,T,A,C,C]),
-        define_enzyme!(M; KpnI: [G,G,T,A,C], [C]),
-        define_enzyme!(M; BstEII: [G], [G,T,N,A,C,C]),
-        define_enzyme!(M; AvaII: [G], [G,W,C,C]),
-        define_enzyme!(M; BanI: [G], [G,Y,R,C,C]),
-        define_enzyme!(M; BaeGI: [G,K,G,C,M], [C]),
-        define_enzyme!(M; BsaHI: [G,R], [C,G,Y,C]),
-        define_enzyme!(M; BanII: [G,R,G,C,Y], [C]),
-        define_enzyme!(M; CviQI: [G], [T,A,C]),
-        define_enzyme!(M; RsaI: [G,T], [A,C]),
-        define_enzyme!(M; BstZ17I: [G,T,A,T,A,C], []),
-        define_enzyme!(M; SalI: [G], [T,C,G,A,C]),
-        define_enzyme!(M; ApaLI: [G], [T,G,C,A,C]),
-        define_enzyme!(M; AccI: [G,T], [M,K,A,C]),
-        define_enzyme!(M; Hpy166II: [G,T,N], [N,A,C]),
-        define_enzyme!(M; Tsp45I: [], [G,T,S,A,C]),
-        define_enzyme!(M; HpaI: [G,T,T], [A,A,C]),
-        define_enzyme!(M; PmeI: [G,T,T,T], [A,A,A,C]),
-        define_enzyme!(M; HincII: [G,T,Y], [R,A,C]),
-        define_enzyme!(M; BsiHKAI: [G,W,G,C,W], [C]),
-        define_enzyme!(M; TspRI: [N,N,C,A,S,T,G,N,N], []),
-        define_enzyme!(M; ApoI_HF: [R], [A,A,T,T,Y]),
-        define_enzyme!(M; ApoI: [R], [A,A,T,T,Y]),
-        define_enzyme!(M; NspI: [R,C,A,T,G], [Y]),
-        define_enzyme!(M; BsrFI_v2: [R], [C,C,G,G,Y]),
-        define_enzyme!(M; BstYI: [R], [G,A,T,C,Y]),
-        define_enzyme!(M; HaeII: [R,G,C,G,C], [Y]),
-        define_enzyme!(M; CviKI_1: [R,G], [C,Y]),
-        define_enzyme!(M; EcoO109I: [R,G], [G,N,C,C,Y]),
-        define_enzyme!(M; PpuMI: [R,G], [G,W,C,C,Y]),
-        define_enzyme!(M; SnaBI: [T,A,C], [G,T,A]),
-        define_enzyme!(M; BspHI: [T], [C,A,T,G,A]),
-        define_enzyme!(M; BspEI: [T], [C,C,G,G,A]),
-        define_enzyme!(M; TaqI_v2: [T], [C,G,A]),
-        define_enzyme!(M; NruI: [T,C,G], [C,G,A]),
-        define_enzyme!(M; Hpy188I: [T,C,N], [G,A]),
-        define_enzyme!(M; Hpy188III: [T,C], [N,N,G,A]),
-        define_enzyme!(M; XbaI: [T], [C,T,A,G,A]),
-        define_enzyme!(M; BclI: [T], [G,A,T,C,A]),
-        define_enzyme!(M; BclI_HF: [T], [G,A,T,C,A]),
-        define_enzyme!(M; HpyCH4V: [T,G], [C,A]),
-        define_enzyme!(M; FspI: [T,G,C], [G,C,A]),
-        define_enzyme!(M; MscI: [T,G,G], [C,C,A]),
-        define_enzyme!(M; BsrGI: [T], [G,T,A,C,A]),
-        define_enzyme!(M; MseI: [T], [T,A,A]),
-        define_enzyme!(M; PacI: [T,T,A,A,T], [T,A,A]),
-        define_enzyme!(M; PsiI_v2: [T,T,A], [T,A,A]),
-        define_enzyme!(M; BstBI: [T,T], [C,G,A,A]),
-        define_enzyme!(M; DraI: [T,T,T], [A,A,A]),
-        define_enzyme!(M; PspXI: [V,C], [T,C,G,A,G,B]),
-        define_enzyme!(M; BsaWI: [W], [C,C,G,G,W]),
-        define_enzyme!(M; BsaAI: [Y,A,C], [G,T,R]),
-        define_enzyme!(M; EaeI: [Y], [G,G,C,C,R]),
-    ];
+    pub static ref RestrictionEnzymes: Vec<RestrictionEnzyme> = with_methylation_sensitivity(vec![
+        define_enzyme!(AclI: [A,A], [C,G,T,T]),
+        define_enzyme!(HindIII: [A], [A,G,C,T,T]),
+        define_enzyme!(SspI: [A,A,T], [A,T,T]),
+        define_enzyme!(MluCI: [], [A,A,T,T]),
+        define_enzyme!(PciI: [A], [C,A,T,G,T]),
+        define_enzyme!(AgeI: [A], [C,C,G,G,T]),
+        define_enzyme!(BfuAI: [A,C,C,T,G,C], []; 10, 14),
+        define_enzyme!(BspMI: [A,C,C,T,G,C], []; 10, 14),
+        define_enzyme!(SexAI: [A], [C,C,W,G,G,T]),
+        define_enzyme!(MluI: [A], [C,G,C,G,T]),
+        define_enzyme!(HpyCH4IV: [A], [C,G,T]),
+        define_enzyme!(HpyCH4III: [A,C,N], [G,T]),
+        define_enzyme!(BaeI: [A,C,N,N,N,N,G,T,A,Y,C], []; upstream -10, -15; 23, 18),
+        define_enzyme!(AflIII: [A], [C,R,Y,G,T]),
+        define_enzyme!(SpeI: [A], [C,T,A,G,T]),
+        define_enzyme!(BglII: [A], [G,A,T,C,T]),
+        define_enzyme!(AfeI: [A,G,C], [G,C,T]),
+        define_enzyme!(AluI: [A,G], [C,T]),
+        define_enzyme!(StuI: [A,G,G], [C,C,T]),
+        define_enzyme!(ScaI: [A,G,T], [A,C,T]),
+        define_enzyme!(ClaI: [A,T], [C,G,A,T]),
+        define_enzyme!(BspDI: [A,T], [C,G,A,T]),
+        define_enzyme!(NsiI: [A,T,G,C,A], [T]),
+        define_enzyme!(AseI: [A,T], [T,A,A,T]),
+        define_enzyme!(SwaI: [A,T,T,T], [A,A,A,T]),
+        define_enzyme!(CspCI: [C,A,A,N,N,N,N,N,G,T,G,G], []; upstream -11, -13; 24, 22),
+        define_enzyme!(MfeI: [C], [A,A,T,T,G]),
+        define_enzyme!(PaqCI: [C,A,C,C,T,G,C], []; 11, 15),
+        define_enzyme!(NbBssSI: [C,A,C,G,A,G], []; bottom 5),
+        define_enzyme!(PmlI: [C,A,C], [G,T,G]),
+        define_enzyme!(DraIII: [C,A,C,N,N,N], [G,T,G]),
+        define_enzyme!(AleI_v2: [C,A,C,N,N], [N,N,G,T,G]),
+        define_enzyme!(PvuII: [C,A,G], [C,T,G]),
+        define_enzyme!(AlwNI: [C,A,G,N,N,N], [C,T,G]),
+        define_enzyme!(NdeI: [C,A], [T,A,T,G]),
+        define_enzyme!(FatI: [], [C,A,T,G]),
+        define_enzyme!(CviAII: [C], [A,T,G]),
+        define_enzyme!(NlaIII: [C,A,T,G], []),
+        define_enzyme!(MslI: [C,A,Y,N,N], [N,N,R,T,G]),
+        define_enzyme!(XcmI: [C,C,A,N,N,N,N,N], [N,N,N,N,T,G,G]),
+        define_enzyme!(BstXI: [C,C,A,N,N,N,N,N], [N,T,G,G]),
+        define_enzyme!(PflMI: [C,C,A,N,N,N,N], [N,T,G,G]),
+        define_enzyme!(NcoI: [C], [C,A,T,G,G]),
+        define_enzyme!(SmaI: [C,C,C], [G,G,G]),
+        define_enzyme!(TspMI: [C], [C,C,G,G,G]),
+        define_enzyme!(XmaI: [C], [C,C,G,G,G]),
+        define_enzyme!(SacII: [C,C,G,C], [G,G]),
+        define_enzyme!(MspI: [C], [C,G,G]),
+        define_enzyme!(HpaII: [C], [C,G,G]),
+        define_enzyme!(StyD4I: [], [C,C,N,G,G]),
+        define_enzyme!(ScrFI: [C,C], [N,G,G]),
+        define_enzyme!(BsaJI: [C], [C,N,N,G,G]),
+        define_enzyme!(BslI: [C,C,N,N,N,N,N], [N,N,G,G]),
+        define_enzyme!(BtgI: [C], [C,R,Y,G,G]),
+        define_enzyme!(NciI: [C,C], [S,G,G]),
+        define_enzyme!(AvrII: [C], [C,T,A,G,G]),
+        define_enzyme!(NbBbvCI: [C,C,T,C,A,G,C], []; bottom 5),
+        define_enzyme!(SbfI: [C,C,T,G,C,A], [G,G]),
+        define_enzyme!(Bsu36I: [C,C], [T,N,A,G,G]),
+        define_enzyme!(EcoNI: [C,C,T,N,N], [N,N,N,A,G,G]),
+        define_enzyme!(PspGI: [], [C,C,W,G,G]),
+        define_enzyme!(BstNI: [C,C], [W,G,G]),
+        define_enzyme!(StyI: [C], [C,W,W,G,G]),
+        define_enzyme!(PvuI: [C,G,A,T], [C,G]),
+        define_enzyme!(BstUI: [C,G], [C,G]),
+        define_enzyme!(EagI: [C], [G,G,C,C,G]),
+        define_enzyme!(RsrII: [C,G], [G,W,C,C,G]),
+        define_enzyme!(BsiEI: [C,G,R,Y], [C,G]),
+        define_enzyme!(BsiWI: [C], [G,T,A,C,G]),
+        define_enzyme!(BsmBI_v2: [C,G,T,C,T,C], []; 7, 11),
+        define_enzyme!(Esp3I: [C,G,T,C,T,C], []; 7, 11),
+        define_enzyme!(Hpy99I: [C,G,W,C,G], []),
+        define_enzyme!(MspA1I: [C,M,G], [C,K,G]),
+        define_enzyme!(AbaSI: [C,N,N,N,N,N,N,N,N,N,N,N], [N,N,N,N,N,N,N,N,N,G]),
+        define_enzyme!(SgrAI: [C,R], [C,C,G,G,Y,G]),
+        define_enzyme!(BfaI: [C], [T,A,G]),
+        define_enzyme!(XhoI: [C], [T,C,G,A,G]),
+        define_enzyme!(PaeR7I: [C], [T,C,G,A,G]),
+        define_enzyme!(PstI: [C,T,G,C,A], [G]),
+        define_enzyme!(DdeI: [C], [T,N,A,G]),
+        define_enzyme!(SfcI: [C], [T,R,Y,A,G]),
+        define_enzyme!(AflII: [C], [T,T,A,A,G]),
+        define_enzyme!(SmlI: [C], [T,Y,R,A,G]),
+        define_enzyme!(BsoBI: [C], [Y,C,G,R,G]),
+        define_enzyme!(AvaI: [C], [Y,C,G,R,G]),
+        define_enzyme!(BbsI_HF: [G,A,A,G,A,C], []; 8, 12),
+        define_enzyme!(BbsI: [G,A,A,G,A,C], []; 8, 12),
+        define_enzyme!(XmnI: [G,A,A,N,N], [N,N,T,T,C]),
+        define_enzyme!(NbBsmI: [G,A,A,T,G,C], []; bottom 5),
+        define_enzyme!(EcoRI: [G], [A,A,T,T,C]),
+        define_enzyme!(AatII: [G,A,C,G,T], [C]),
+        define_enzyme!(ZraI: [G,A,C], [G,T,C]),
+        define_enzyme!(PflFI: [G,A,C,N], [N,N,G,T,C]),
+        define_enzyme!(Tth111I: [G,A,C,N], [N,N,G,T,C]),
+        define_enzyme!(PshAI: [G,A,C,N,N], [N,N,G,T,C]),
+        define_enzyme!(AhdI: [G,A,C,N,N,N], [N,N,G,T,C]),
+        define_enzyme!(DrdI: [G,A,C,N,N,N,N], [N,N,G,T,C]),
+        define_enzyme!(Eco53kI: [G,A,G], [C,T,C]),
+        define_enzyme!(SacI: [G,A,G,C,T], [C]),
+        define_enzyme!(HinfI: [G], [A,N,T,C]),
+        define_enzyme!(EcoRV: [G,A,T], [A,T,C]),
+        define_enzyme!(DpnII: [], [G,A,T,C]),
+        define_enzyme!(MboI: [], [G,A,T,C]),
+        define_enzyme!(Sau3AI: [], [G,A,T,C]),
+        define_enzyme!(DpnI: [G,A], [T,C]),
+        define_enzyme!(BsaBI: [G,A,T,N,N], [N,N,A,T,C]),
+        define_enzyme!(TfiI: [G], [A,W,T,C]),
+        define_enzyme!(NbBsrDI: [G,C,A,A,T,G], []; bottom 6),
+        define_enzyme!(NbBtsI: [G,C,A,G,T,G], []; bottom 6),
+        define_enzyme!(BstAPI: [G,C,A,N,N,N,N], [N,T,G,C]),
+        define_enzyme!(SphI: [G,C,A,T,G], [C]),
+        define_enzyme!(SrfI: [G,C,C,C], [G,G,G,C]),
+        define_enzyme!(NgoMIV: [G], [C,C,G,G,C]),
+        define_enzyme!(NaeI: [G,C,C], [G,G,C]),
+        define_enzyme!(BglI: [G,C,C,N,N,N,N], [N,G,G,C]),
+        define_enzyme!(AsiSI: [G,C,G,A,T], [C,G,C]),
+        define_enzyme!(HhaI: [G,C,G], [C]),
+        define_enzyme!(HinP1I: [G], [C,G,C]),
+        define_enzyme!(BssHII: [G], [C,G,C,G,C]),
+        define_enzyme!(NotI: [G,C], [G,G,C,C,G,C]),
+        define_enzyme!(Fnu4HI: [G,C], [N,G,C]),
+        define_enzyme!(Cac8I: [G,C,N], [N,G,C]),
+        define_enzyme!(MwoI: [G,C,N,N,N,N,N], [N,N,G,C]),
+        define_enzyme!(BmtI: [G,C,T,A,G], [C]),
+        define_enzyme!(NheI: [G], [C,T,A,G,C]),
+        define_enzyme!(BspQI: [G,C,T,C,T,T,C], []; 8, 11),
+        define_enzyme!(SapI: [G,C,T,C,T,T,C], []; 8, 11),
+        define_enzyme!(BlpI: [G,C], [T,N,A,G,C]),
+        define_enzyme!(TseI: [G], [C,W,G,C]),
+        define_enzyme!(ApeKI: [G], [C,W,G,C]),
+        define_enzyme!(Bsp1286I: [G,D,G,C,H], [C]),
+        define_enzyme!(BamHI: [G], [G,A,T,C,C]),
+        define_enzyme!(HaeIII: [G,G], [C,C]),
+        define_enzyme!(FseI: [G,G,C,C,G,G], [C,C]),
+        define_enzyme!(SfiI: [G,G,C,C,N,N,N,N], [N,G,G,C,C]),
+        define_enzyme!(NarI: [G,G], [C,G,C,C]),
+        define_enzyme!(SfoI: [G,G,C], [G,C,C]),
+        define_enzyme!(KasI: [G], [G,C,G,C,C]),
+        define_enzyme!(PluTI: [G,G,C,G,C], [C]),
+        define_enzyme!(AscI: [G,G], [C,G,C,G,C,C]),
+        define_enzyme!(PspOMI: [G], [G,G,C,C,C]),
+        define_enzyme!(ApaI: [G,G,G,C,C], [C]),
+        define_enzyme!(Sau96I: [G], [G,N,C,C]),
+        define_enzyme!(NlaIV: [G,G,N], [N,C,C]),
+        define_enzyme!(Acc65I: [G], [G,T,A,C,C]),
+        define_enzyme!(KpnI: [G,G,T,A,C], [C]),
+        define_enzyme!(BsaI_HFv2: [G,G,T,C,T,C], []; 7, 11),
+        define_enzyme!(BstEII: [G], [G,T,N,A,C,C]),
+        define_enzyme!(AvaII: [G], [G,W,C,C]),
+        define_enzyme!(BanI: [G], [G,Y,R,C,C]),
+        define_enzyme!(BaeGI: [G,K,G,C,M], [C]),
+        define_enzyme!(BsaHI: [G,R], [C,G,Y,C]),
+        define_enzyme!(BanII: [G,R,G,C,Y], [C]),
+        define_enzyme!(CviQI: [G], [T,A,C]),
+        define_enzyme!(RsaI: [G,T], [A,C]),
+        define_enzyme!(BstZ17I: [G,T,A], [T,A,C]),
+        define_enzyme!(SalI: [G], [T,C,G,A,C]),
+        define_enzyme!(ApaLI: [G], [T,G,C,A,C]),
+        define_enzyme!(AccI: [G,T], [M,K,A,C]),
+        define_enzyme!(Hpy166II: [G,T,N], [N,A,C]),
+        define_enzyme!(Tsp45I: [], [G,T,S,A,C]),
+        define_enzyme!(HpaI: [G,T,T], [A,A,C]),
+        define_enzyme!(PmeI: [G,T,T,T], [A,A,A,C]),
+        define_enzyme!(HincII: [G,T,Y], [R,A,C]),
+        define_enzyme!(BsiHKAI: [G,W,G,C,W], [C]),
+        define_enzyme!(TspRI: [N,N,C,A,S,T,G,N,N], []),
+        define_enzyme!(ApoI_HF: [R], [A,A,T,T,Y]),
+        define_enzyme!(ApoI: [R], [A,A,T,T,Y]),
+        define_enzyme!(NspI: [R,C,A,T,G], [Y]),
+        define_enzyme!(BsrFI_v2: [R], [C,C,G,G,Y]),
+        define_enzyme!(BstYI: [R], [G,A,T,C,Y]),
+        define_enzyme!(HaeII: [R,G,C,G,C], [Y]),
+        define_enzyme!(CviKI_1: [R,G], [C,Y]),
+        define_enzyme!(EcoO109I: [R,G], [G,N,C,C,Y]),
+        define_enzyme!(PpuMI: [R,G], [G,W,C,C,Y]),
+        define_enzyme!(SnaBI: [T,A,C], [G,T,A]),
+        define_enzyme!(BspHI: [T], [C,A,T,G,A]),
+        define_enzyme!(BspEI: [T], [C,C,G,G,A]),
+        define_enzyme!(TaqI_v2: [T], [C,G,A]),
+        define_enzyme!(NruI: [T,C,G], [C,G,A]),
+        define_enzyme!(Hpy188I: [T,C,N], [G,A]),
+        define_enzyme!(Hpy188III: [T,C], [N,N,G,A]),
+        define_enzyme!(XbaI: [T], [C,T,A,G,A]),
+        define_enzyme!(BclI: [T], [G,A,T,C,A]),
+        define_enzyme!(BclI_HF: [T], [G,A,T,C,A]),
+        define_enzyme!(HpyCH4V: [T,G], [C,A]),
+        define_enzyme!(FspI: [T,G,C], [G,C,A]),
+        define_enzyme!(MscI: [T,G,G], [C,C,A]),
+        define_enzyme!(BsrGI: [T], [G,T,A,C,A]),
+        define_enzyme!(MseI: [T], [T,A,A]),
+        define_enzyme!(PacI: [T,T,A,A,T], [T,A,A]),
+        define_enzyme!(PsiI_v2: [T,T,A], [T,A,A]),
+        define_enzyme!(BstBI: [T,T], [C,G,A,A]),
+        define_enzyme!(DraI: [T,T,T], [A,A,A]),
+        define_enzyme!(PspXI: [V,C], [T,C,G,A,G,B]),
+        define_enzyme!(BsaWI: [W], [C,C,G,G,W]),
+        define_enzyme!(BsaAI: [Y,A,C], [G,T,R]),
+        define_enzyme!(EaeI: [Y], [G,G,C,C,R]),
+    ]);
+}
+
+/// Enzymes blocked by overlapping Dam, Dcm or CpG methylation, according to NEB.
+/// Enzymes blocked by some overlapping combinations only are included, enzymes
+/// which are merely impaired are not.
+const MethylationSensitivity: &[(&str, &[Methylation])] = {
+    use Methylation::*;
+    &[
+        ("AatII", &[CpG]),
+        ("Acc65I", &[Dcm]),
+        ("AccI", &[CpG]),
+        ("AclI", &[CpG]),
+        ("AfeI", &[CpG]),
+        ("AgeI", &[CpG]),
+        ("ApaI", &[Dcm, CpG]),
+        ("ApeKI", &[CpG]),
+        ("AscI", &[CpG]),
+        ("AsiSI", &[CpG]),
+        ("AvaI", &[CpG]),
+        ("AvaII", &[Dcm, CpG]),
+        ("BanI", &[Dcm, CpG]),
+        ("BclI", &[Dam]),
+        ("BclI_HF", &[Dam]),
+        ("BglI", &[CpG]),
+        ("BsaAI", &[CpG]),
+        ("BsaBI", &[Dam, CpG]),
+        ("BsaHI", &[CpG]),
+        ("BsaI_HFv2", &[Dcm, CpG]),
+        ("BsaJI", &[CpG]),
+        ("BsaWI", &[CpG]),
+        ("BsiEI", &[CpG]),
+        ("BsiWI", &[CpG]),
+        ("BslI", &[Dcm, CpG]),
+        ("BsmBI_v2", &[CpG]),
+        ("BsoBI", &[CpG]),
+        ("BspDI", &[Dam, CpG]),
+        ("BspEI", &[Dam, CpG]),
+        ("BspHI", &[Dam]),
+        ("BsrFI_v2", &[CpG]),
+        ("BssHII", &[CpG]),
+        ("BstBI", &[CpG]),
+        ("BstUI", &[CpG]),
+        ("Cac8I", &[CpG]),
+        ("ClaI", &[Dam, CpG]),
+        ("DpnII", &[Dam]),
+        ("EaeI", &[Dcm, CpG]),
+        ("EagI", &[CpG]),
+        ("EcoO109I", &[Dcm]),
+        ("Esp3I", &[CpG]),
+        ("Fnu4HI", &[CpG]),
+        ("FseI", &[CpG]),
+        ("FspI", &[CpG]),
+        ("HaeII", &[CpG]),
+        ("HhaI", &[CpG]),
+        ("HincII", &[CpG]),
+        ("HinfI", &[CpG]),
+        ("HinP1I", &[CpG]),
+        ("HpaII", &[CpG]),
+        ("Hpy166II", &[CpG]),
+        ("Hpy188I", &[Dam, CpG]),
+        ("Hpy188III", &[Dam, CpG]),
+        ("Hpy99I", &[CpG]),
+        ("HpyCH4IV", &[CpG]),
+        ("KasI", &[CpG]),
+        ("MboI", &[Dam, CpG]),
+        ("MluI", &[CpG]),
+        ("MscI", &[Dcm]),
+        ("MspA1I", &[CpG]),
+        ("MwoI", &[CpG]),
+        ("NaeI", &[CpG]),
+        ("NarI", &[CpG]),
+        ("NciI", &[CpG]),
+        ("NgoMIV", &[CpG]),
+        ("NlaIV", &[Dcm, CpG]),
+        ("NotI", &[CpG]),
+        ("NruI", &[Dam, CpG]),
+        ("PaeR7I", &[CpG]),
+        ("PflMI", &[Dcm]),
+        ("PluTI", &[CpG]),
+        ("PmlI", &[CpG]),
+        ("PpuMI", &[Dcm]),
+        ("PspGI", &[Dcm]),
+        ("PspOMI", &[Dcm, CpG]),
+        ("PspXI", &[CpG]),
+        ("PvuI", &[CpG]),
+        ("RsrII", &[CpG]),
+        ("SacII", &[CpG]),
+        ("SalI", &[CpG]),
+        ("Sau3AI", &[CpG]),
+        ("Sau96I", &[Dcm, CpG]),
+        ("ScrFI", &[Dcm, CpG]),
+        ("SexAI", &[Dcm]),
+        ("SfiI", &[Dcm, CpG]),
+        ("SfoI", &[Dcm, CpG]),
+        ("SgrAI", &[CpG]),
+        ("SmaI", &[CpG]),
+        ("SnaBI", &[CpG]),
+        ("SrfI", &[CpG]),
+        ("StuI", &[Dcm]),
+        ("StyD4I", &[Dcm, CpG]),
+        ("TaqI_v2", &[Dam]),
+        ("TseI", &[CpG]),
+        ("XbaI", &[Dam]),
+        ("XhoI", &[CpG]),
+        ("ZraI", &[CpG]),
+    ]
+};
+
+/// Enzymes which only cut if their recognition site is methylated, according to NEB.
+/// AbaSI, which needs glucosylated 5-hydroxymethylcytosine, is not modelled.
+const MethylationDependence: &[(&str, &[Methylation])] = &[("DpnI", &[Methylation::Dam])];
+
+/// Approximate activities in NEBuffer r1.1, r2.1, r3.1 and rCutSmart, in percent,
+/// as given by NEB's activity chart for common cloning enzymes.
+const BufferActivity: &[(&str, [u8; 4])] = &[
+    ("ApaI", [25, 25, 0, 100]),
+    ("AvrII", [100, 50, 50, 100]),
+    ("BamHI", [75, 100, 100, 100]),
+    ("BglII", [10, 10, 100, 0]),
+    ("BsaI_HFv2", [100, 100, 100, 100]),
+    ("BsmBI_v2", [10, 75, 100, 10]),
+    ("ClaI", [10, 50, 50, 100]),
+    ("EcoRI", [25, 100, 50, 50]),
+    ("EcoRV", [10, 50, 100, 10]),
+    ("HindIII", [25, 100, 50, 50]),
+    ("KpnI", [100, 25, 0, 100]),
+    ("MluI", [10, 50, 100, 25]),
+    ("NcoI", [100, 100, 100, 100]),
+    ("NdeI", [75, 100, 100, 100]),
+    ("NheI", [100, 100, 10, 100]),
+    ("NotI", [0, 25, 100, 25]),
+    ("PacI", [100, 75, 10, 100]),
+    ("PstI", [75, 75, 100, 50]),
+    ("SacI", [100, 50, 10, 100]),
+    ("SalI", [0, 50, 100, 10]),
+    ("SmaI", [0, 50, 0, 100]),
+    ("SpeI", [75, 100, 25, 100]),
+    ("SphI", [100, 100, 50, 100]),
+    ("XbaI", [0, 100, 75, 100]),
+    ("XhoI", [75, 100, 100, 100]),
+    ("XmaI", [25, 50, 0, 100]),
+];
+
+fn with_methylation_sensitivity(mut enzymes: Vec<RestrictionEnzyme>) -> Vec<RestrictionEnzyme> {
+    for enzyme in enzymes.iter_mut() {
+        if let Some((_, methylations)) = MethylationSensitivity
+            .iter()
+            .find(|(name, _)| *name == enzyme.name)
+        {
+            enzyme.blocked_by = methylations.to_vec();
+        }
+        if let Some((_, methylations)) = MethylationDependence
+            .iter()
+            .find(|(name, _)| *name == enzyme.name)
+        {
+            enzyme.requires_methylation = methylations.to_vec();
+        }
+    }
+    enzymes
+}
+
+/// A recognition site found by a `RestrictionSiteScanner`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RestrictionSite {
+    /// Index of the enzyme in the list the scanner was built from
+    pub enzyme: usize,
+    /// Position of the first nucleotide of the site on the top strand
+    pub start: usize,
+    /// The strand the site is located on, `None` for palindromic sites
+    pub strand: Option<Strand>,
+    /// The methylation blocking the enzyme at this site, if any
+    pub blocked_by: Option<Methylation>,
+    /// A methylation the enzyme requires which is missing at this site, if any
+    pub unmethylated: Option<Methylation>,
+}
+
+impl RestrictionSite {
+    /// The cuts of `enzyme` at this site, relative to `start` in top strand coordinates.
+    pub fn cuts<'a>(
+        &self,
+        enzyme: &'a RestrictionEnzyme,
+    ) -> impl Iterator<Item = CutPositions> + 'a {
+        let site_len = enzyme.recognition_site().len();
+        let reverse = self.strand == Some(Strand::Reverse);
+        enzyme.cuts().map(
+            move |cut| {
+                if reverse {
+                    cut.mirrored(site_len)
+                } else {
+                    cut
+                }
+            },
+        )
+    }
+
+    /// Whether the enzyme cuts at this site, i.e. it is neither blocked by
+    /// methylation nor lacks a methylation the enzyme requires.
+    pub fn is_cut(&self) -> bool {
+        self.blocked_by.is_none() && self.unmethylated.is_none()
+    }
+
+    /// Flag the site as blocked if a nucleotide of it is methylated by one of the
+    /// methylations `enzyme` is sensitive to, and as unmethylated if none of its
+    /// nucleotides is methylated by a methylation `enzyme` requires. `methylated`
+    /// are the positions of the methylated nucleotides, see
+    /// `MethylationProfile::methylated_nucleotides`, and `len` is the length of the sequence.
+    pub fn flag_blocked(
+        &mut self,
+        enzyme: &RestrictionEnzyme,
+        methylated: &[(usize, Methylation)],
+        len: usize,
+    ) {
+        let site_len = enzyme.recognition_site().len();
+        let in_site = |pos: usize| (pos + len - self.start) % len.max(1) < site_len;
+        self.blocked_by = methylated
+            .iter()
+            .find(|&&(pos, methylation)| enzyme.is_blocked_by(methylation) && in_site(pos))
+            .map(|&(_, methylation)| methylation);
+        self.unmethylated = enzyme
+            .requires_methylation
+            .iter()
+            .find(|&&required| {
+                !methylated
+                    .iter()
+                    .any(|&(pos, methylation)| methylation == required && in_site(pos))
+            })
+            .copied();
+    }
+}
+
+/// Options for restriction site analysis.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct RestrictionOptions {
+    /// Methylation of the host the DNA was grown in
+    pub methylation: MethylationProfile,
+    /// Leave out sites blocked by methylation, or lacking a methylation their
+    /// enzyme requires, instead of flagging them
+    pub exclude_blocked: bool,
+}
+
+impl RestrictionOptions {
+    /// Take the methylation of a host into account.
+    pub fn with_methylation(mut self, methylation: MethylationProfile) -> Self {
+        self.methylation = methylation;
+        self
+    }
+
+    /// Leave out sites which aren't cut due to methylation instead of flagging them.
+    pub fn with_exclude_blocked(mut self, exclude_blocked: bool) -> Self {
+        self.exclude_blocked = exclude_blocked;
+        self
+    }
+}
+
+/// Finds the recognition sites of a set of enzymes on both strands in a single
+/// pass over a sequence, see `PatternScanner`.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+///
+/// let enzymes = RestrictionEnzymes
+///     .iter()
+///     .filter(|e| ["EcoRI", "BsaI_HFv2"].contains(&e.name.as_str()))
+///     .collect::<Vec<_>>();
+/// let scanner = RestrictionSiteScanner::new(enzymes.iter().copied());
+/// let seq = DnaSequence::from_str("GAATTCGAGACC").unwrap();
+///
+/// let sites = scanner.scan(seq.as_nucleotides(), Topology::Linear);
+/// assert_eq!(sites.len(), 2);
+/// assert_eq!(enzymes[sites[1].enzyme].name, "BsaI_HFv2");
+/// assert_eq!((sites[1].start, sites[1].strand), (6, Some(Strand::Reverse)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RestrictionSiteScanner {
+    scanner: PatternScanner,
+    /// Enzyme and strand of each pattern of the scanner
+    patterns: Vec<(usize, Option<Strand>)>,
+}
+
+impl RestrictionSiteScanner {
+    pub fn new<'a, I>(enzymes: I) -> Self
+    where
+        I: IntoIterator<Item = &'a RestrictionEnzyme>,
+    {
+        let mut patterns = Vec::new();
+        let mut sites = Vec::new();
+        for (index, enzyme) in enzymes.into_iter().enumerate() {
+            if enzyme.is_palindromic() {
+                patterns.push((index, None));
+                sites.push(enzyme.recognition_site());
+            } else {
+                patterns.push((index, Some(Strand::Forward)));
+                sites.push(enzyme.recognition_site());
+                patterns.push((index, Some(Strand::Reverse)));
+                sites.push(enzyme.reverse_complement_site());
+            }
+        }
+        Self {
+            scanner: PatternScanner::new(sites),
+            patterns,
+        }
+    }
+
+    /// Find the recognition sites of all enzymes in `seq`, a slice of nucleotides
+    /// or an iterator over them, see `PatternScanner::scan`.
+    ///
+    /// The sites are ordered by enzyme, then by strand, then by position.
+    pub fn scan<I>(&self, seq: I, topology: Topology) -> Vec<RestrictionSite>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator + Clone,
+        I::Item: ToIupac,
+    {
+        let mut matches = self.scanner.scan(seq, topology);
+        matches.sort_unstable_by_key(|m| (m.pattern, m.start));
+        matches
+            .into_iter()
+            .map(|m| {
+                let (enzyme, strand) = self.patterns[m.pattern];
+                RestrictionSite {
+                    enzyme,
+                    start: m.start,
+                    strand,
+                    blocked_by: None,
+                    unmethylated: None,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::seq::OverhangType;
+
+    use super::{
+        BufferActivity, CutPositions, MethylationDependence, MethylationSensitivity,
+        RestrictionEnzyme, RestrictionEnzymes,
+    };
+
+    #[test]
+    fn test_cut_positions_overhang() {
+        assert_eq!(
+            CutPositions::new(1, 5).overhang_type(),
+            Some(OverhangType::FivePrime)
+        );
+        assert_eq!(
+            CutPositions::new(5, 1).overhang_type(),
+            Some(OverhangType::ThreePrime)
+        );
+        assert_eq!(CutPositions::new(3, 3).overhang_len(), 0);
+        assert_eq!(CutPositions::bottom_nick(5).overhang_type(), None);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_constructor() {
+        use super::CutMode;
+        use crate::uni::IupacNucleotide::*;
+
+        let enzyme = RestrictionEnzyme::new("EcoRI", &[G], &[A, A, T, T, C], CutMode::M);
+        assert_eq!(
+            enzyme,
+            RestrictionEnzyme::from_halves("EcoRI", &[G], &[A, A, T, T, C])
+        );
+    }
+
+    #[test]
+    fn test_mirrored_cuts_have_palindromic_sites() {
+        for enzyme in RestrictionEnzymes.iter() {
+            let (before, after) = (enzyme.before.len() as isize, enzyme.after.len() as isize);
+            if enzyme.cut == CutPositions::new(before, after) {
+                assert!(
+                    enzyme.is_palindromic(),
+                    "{} is not palindromic",
+                    enzyme.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_methylation_sensitivity_enzymes_exist() {
+        for (name, _) in MethylationSensitivity {
+            assert!(
+                RestrictionEnzymes.iter().any(|e| e.name == *name),
+                "unknown enzyme {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_methylation_dependence_enzymes_exist() {
+        for (name, _) in MethylationDependence {
+            assert!(
+                RestrictionEnzymes.iter().any(|e| e.name == *name),
+                "unknown enzyme {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_buffer_activity_enzymes_exist() {
+        for (name, _) in BufferActivity {
+            assert!(
+                RestrictionEnzymes.iter().any(|e| e.name == *name),
+                "unknown enzyme {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_restriction_site_blocked() {
+        use crate::prelude::*;
+
+        let database = EnzymeDatabase::builtin()
+            .filter(|e| ["ClaI", "DpnII", "Sau3AI", "StuI"].contains(&e.name.as_str()));
+        let scan = |s: &str, profile: MethylationProfile| {
+            let seq = DnaSequence::from_str(s).unwrap();
+            database
+                .scan_methylated(seq.as_nucleotides(), Topology::Linear, &profile)
+                .into_iter()
+                .map(|site| {
+                    (
+                        database.iter().nth(site.enzyme).unwrap().name.clone(),
+                        site.blocked_by,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        // ClaI ATCGAT overlapping GATC, Sau3AI is not sensitive to Dam
+        let sites = scan("GATCGAT", MethylationProfile::ecoli());
+        assert!(sites.contains(&("ClaI".to_string(), Some(Methylation::Dam))));
+        assert!(sites.contains(&("DpnII".to_string(), Some(Methylation::Dam))));
+        assert!(sites.contains(&("Sau3AI".to_string(), None)));
+        // StuI AGGCCT overlapping CCWGG
+        assert_eq!(
+            scan("AGGCCTGG", MethylationProfile::ecoli()),
+            [("StuI".to_string(), Some(Methylation::Dcm))]
+        );
+        assert_eq!(
+            scan("AGGCCTAA", MethylationProfile::ecoli()),
+            [("StuI".to_string(), None)]
+        );
+        assert!(scan("GATCGAT", MethylationProfile::none())
+            .iter()
+            .all(|(_, blocked_by)| blocked_by.is_none()));
+    }
+
+    #[test]
+    fn test_restriction_site_sensitivities() {
+        use crate::prelude::*;
+
+        let database =
+            EnzymeDatabase::builtin().filter(|e| ["AclI", "ScrFI"].contains(&e.name.as_str()));
+        let blocked = |s: &str, profile: MethylationProfile| {
+            let seq = DnaSequence::from_str(s).unwrap();
+            database
+                .scan_methylated(seq.as_nucleotides(), Topology::Linear, &profile)
+                .into_iter()
+                .map(|site| site.blocked_by)
+                .collect::<Vec<_>>()
+        };
+        // ScrFI CCNGG as part of CCWGG
+        assert_eq!(
+            blocked("CCAGG", MethylationProfile::ecoli()),
+            [Some(Methylation::Dcm)]
+        );
+        assert_eq!(blocked("CCAGG", MethylationProfile::none()), [None]);
+        // AclI AACGTT
+        assert_eq!(
+            blocked("AACGTT", MethylationProfile::mammalian()),
+            [Some(Methylation::CpG)]
+        );
+        assert_eq!(blocked("AACGTT", MethylationProfile::ecoli()), [None]);
+    }
+
+    #[test]
+    fn test_restriction_site_requires_methylation() {
+        use crate::prelude::*;
+
+        let database = EnzymeDatabase::builtin().filter(|e| e.name == "DpnI");
+        let seq = DnaSequence::from_str("AAGATCAA").unwrap();
+        let sites = database.scan_methylated(
+            seq.as_nucleotides(),
+            Topology::Linear,
+            &MethylationProfile::none(),
+        );
+        assert_eq!(sites.len(), 1);
+        assert_eq!(sites[0].unmethylated, Some(Methylation::Dam));
+        assert!(!sites[0].is_cut());
+        let sites = database.scan_methylated(
+            seq.as_nucleotides(),
+            Topology::Linear,
+            &MethylationProfile::ecoli(),
+        );
+        assert_eq!(sites[0].unmethylated, None);
+        assert!(sites[0].is_cut());
+    }
 }
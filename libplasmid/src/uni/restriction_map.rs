@@ -0,0 +1,303 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::{
+    seq::{OverhangType, Topology},
+    traits::ToIupac,
+};
+
+use super::{EnzymeDatabase, RestrictionEnzyme, RestrictionOptions, RestrictionSite};
+
+/// Criteria for selecting enzymes from a `RestrictionMap`.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+///
+/// // Six-cutters leaving 5' overhangs, cutting once between 100 and 400
+/// let query = EnzymeQuery::default()
+///     .with_cut_count(1..=1)
+///     .with_cuts_within(100..400)
+///     .with_overhang_type(OverhangType::FivePrime)
+///     .with_specificity(6..=6);
+/// assert!(query.matches_enzyme(RestrictionEnzyme::by_name("EcoRI").unwrap()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnzymeQuery {
+    /// Number of sites cut by the enzyme, sites not cut due to methylation don't count
+    pub cut_count: Option<RangeInclusive<usize>>,
+    /// Region all cuts of the enzyme have to lie in, cut positions are the first
+    /// nucleotide after the cut like in `Annotation::needle`.
+    /// Enzymes not cutting at all don't match.
+    pub cuts_within: Option<Range<usize>>,
+    /// Type of the ends produced by the enzyme
+    pub overhang_type: Option<OverhangType>,
+    /// Number of specified nucleotides of the recognition site, see
+    /// `RestrictionEnzyme::specificity`
+    pub specificity: Option<RangeInclusive<usize>>,
+    /// Only report the first enzyme of each group of isoschizomers
+    pub collapse_isoschizomers: bool,
+}
+
+impl EnzymeQuery {
+    pub fn with_cut_count(mut self, cut_count: RangeInclusive<usize>) -> Self {
+        self.cut_count = Some(cut_count);
+        self
+    }
+
+    pub fn with_cuts_within(mut self, region: Range<usize>) -> Self {
+        self.cuts_within = Some(region);
+        self
+    }
+
+    pub fn with_overhang_type(mut self, overhang_type: OverhangType) -> Self {
+        self.overhang_type = Some(overhang_type);
+        self
+    }
+
+    pub fn with_specificity(mut self, specificity: RangeInclusive<usize>) -> Self {
+        self.specificity = Some(specificity);
+        self
+    }
+
+    pub fn with_collapsed_isoschizomers(mut self, collapse: bool) -> Self {
+        self.collapse_isoschizomers = collapse;
+        self
+    }
+
+    /// Whether the properties of `enzyme` itself match the query, regardless of
+    /// any sequence.
+    pub fn matches_enzyme(&self, enzyme: &RestrictionEnzyme) -> bool {
+        self.overhang_type
+            .is_none_or(|t| enzyme.overhang_type() == Some(t))
+            && self
+                .specificity
+                .as_ref()
+                .is_none_or(|s| s.contains(&enzyme.specificity()))
+    }
+}
+
+/// The recognition sites of the enzymes of a database in a sequence.
+///
+/// # Example
+/// ```rust
+/// use plasmid::prelude::*;
+///
+/// let database = EnzymeDatabase::builtin();
+/// let seq = DnaSequence::from_str("GAATTCAAAGGATCCAAAGAATTC").unwrap();
+/// let map = seq.restriction_map(&database, &RestrictionOptions::default());
+///
+/// assert_eq!(map.cut_count("EcoRI"), Some(2));
+/// assert!(map.unique_cutters().iter().any(|e| e.name == "BamHI"));
+/// assert!(map.non_cutters().iter().any(|e| e.name == "NotI"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RestrictionMap<'a> {
+    len: usize,
+    topology: Topology,
+    /// Every enzyme of the database with its sites, in the order of the database
+    entries: Vec<(&'a RestrictionEnzyme, Vec<RestrictionSite>)>,
+}
+
+impl<'a> RestrictionMap<'a> {
+    pub fn new<I>(
+        seq: I,
+        topology: Topology,
+        database: &'a EnzymeDatabase,
+        options: &RestrictionOptions,
+    ) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator + Clone,
+        I::Item: ToIupac,
+    {
+        let seq = seq.into_iter();
+        let mut entries = database
+            .iter()
+            .map(|enzyme| (enzyme, Vec::new()))
+            .collect::<Vec<_>>();
+        for site in database.scan_methylated(seq.clone(), topology, &options.methylation) {
+            if !options.exclude_blocked || site.is_cut() {
+                entries[site.enzyme].1.push(site);
+            }
+        }
+        Self {
+            len: seq.len(),
+            topology,
+            entries,
+        }
+    }
+
+    /// The sites of an enzyme, including sites not cut due to methylation.
+    pub fn sites(&self, enzyme: &str) -> Option<&[RestrictionSite]> {
+        self.entries
+            .iter()
+            .find(|(e, _)| e.name == enzyme)
+            .map(|(_, sites)| sites.as_slice())
+    }
+
+    /// The number of sites cut by an enzyme, not counting sites not cut due to methylation.
+    pub fn cut_count(&self, enzyme: &str) -> Option<usize> {
+        self.sites(enzyme).map(count_cut)
+    }
+
+    /// The cut positions of all sites cut by `enzyme`, as the first nucleotide
+    /// after the cut. Cuts outside of a linear sequence are `None`.
+    fn cut_positions<'s>(
+        &'s self,
+        enzyme: &'s RestrictionEnzyme,
+        sites: &'s [RestrictionSite],
+    ) -> impl Iterator<Item = Option<usize>> + 's {
+        let len = self.len as isize;
+        sites
+            .iter()
+            .filter(|site| site.is_cut())
+            .flat_map(move |site| {
+                site.cuts(enzyme)
+                    .flat_map(|cut| cut.top.into_iter().chain(cut.bottom))
+                    .map(move |pos| site.start as isize + pos)
+            })
+            .map(move |pos| match self.topology {
+                Topology::Circular => Some(pos.rem_euclid(len) as usize),
+                Topology::Linear => (0..=len).contains(&pos).then_some(pos as usize),
+            })
+    }
+
+    /// The enzymes matching `query`, in the order of the database.
+    pub fn query(&self, query: &EnzymeQuery) -> Vec<&'a RestrictionEnzyme> {
+        let mut found: Vec<&'a RestrictionEnzyme> = Vec::new();
+        for (enzyme, sites) in self.entries.iter() {
+            if !query.matches_enzyme(enzyme) {
+                continue;
+            }
+            let count = count_cut(sites);
+            if let Some(cut_count) = &query.cut_count {
+                if !cut_count.contains(&count) {
+                    continue;
+                }
+            }
+            if let Some(region) = &query.cuts_within {
+                let within = |pos: Option<usize>| {
+                    pos.is_some_and(|pos| region.start <= pos && pos <= region.end)
+                };
+                if count == 0 || !self.cut_positions(enzyme, sites).all(within) {
+                    continue;
+                }
+            }
+            if query.collapse_isoschizomers
+                && found.iter().any(|other| other.is_isoschizomer_of(enzyme))
+            {
+                continue;
+            }
+            found.push(enzyme);
+        }
+        found
+    }
+
+    /// The enzymes cutting the sequence exactly once.
+    pub fn unique_cutters(&self) -> Vec<&'a RestrictionEnzyme> {
+        self.query(&EnzymeQuery::default().with_cut_count(1..=1))
+    }
+
+    /// The enzymes not cutting the sequence.
+    pub fn non_cutters(&self) -> Vec<&'a RestrictionEnzyme> {
+        self.query(&EnzymeQuery::default().with_cut_count(0..=0))
+    }
+
+    /// The enzymes cutting the sequence only within `region`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use plasmid::prelude::*;
+    ///
+    /// let database = EnzymeDatabase::builtin().filter(|e| ["EcoRI", "BamHI"].contains(&e.name.as_str()));
+    /// let seq = DnaSequence::from_str("GAATTCAAAGGATCCAAAGAATTC").unwrap();
+    /// let map = seq.restriction_map(&database, &RestrictionOptions::default());
+    ///
+    /// let names = map.cutters_within(6..18).iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(names, ["BamHI"]);
+    /// ```
+    pub fn cutters_within(&self, region: Range<usize>) -> Vec<&'a RestrictionEnzyme> {
+        self.query(&EnzymeQuery::default().with_cuts_within(region))
+    }
+}
+
+fn count_cut(sites: &[RestrictionSite]) -> usize {
+    sites.iter().filter(|site| site.is_cut()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    fn names(enzymes: Vec<&RestrictionEnzyme>) -> Vec<&str> {
+        enzymes.into_iter().map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_restriction_map_query() {
+        let database = EnzymeDatabase::builtin();
+        // MspI/HpaII and XmaI/TspMI/SmaI sites
+        let seq = DnaSequence::from_str("AAACCCGGGAAATTTAAAGAATTCAA").unwrap();
+        let map = seq.restriction_map(&database, &RestrictionOptions::default());
+
+        let blunt = EnzymeQuery::default()
+            .with_cut_count(1..=1)
+            .with_overhang_type(OverhangType::Blunt)
+            .with_specificity(6..=6);
+        assert_eq!(names(map.query(&blunt)), ["SmaI", "DraI"]);
+
+        let collapsed = EnzymeQuery::default()
+            .with_cuts_within(3..9)
+            .with_collapsed_isoschizomers(true);
+        let found = names(map.query(&collapsed));
+        assert!(found.contains(&"MspI"));
+        assert!(!found.contains(&"HpaII"));
+        assert_eq!(
+            found.contains(&"TspMI"),
+            !found.contains(&"XmaI"),
+            "exactly one of TspMI and XmaI"
+        );
+        assert!(found.contains(&"SmaI"));
+        assert!(!found.contains(&"EcoRI"));
+    }
+
+    #[test]
+    fn test_restriction_map_circular() {
+        let database = EnzymeDatabase::builtin().filter(|e| e.name == "EcoRI");
+        let mut seq = DnaSequence::from_str("ATTCAAAAAAGA").unwrap();
+        seq.set_topology(Topology::Circular);
+        let map = seq.restriction_map(&database, &RestrictionOptions::default());
+        assert_eq!(names(map.unique_cutters()), ["EcoRI"]);
+        assert_eq!(names(map.cutters_within(3..11)), ["EcoRI"]);
+        assert!(map.cutters_within(4..11).is_empty());
+    }
+
+    #[test]
+    fn test_restriction_map_methylation() {
+        let database = EnzymeDatabase::builtin().filter(|e| e.name == "XbaI");
+        let seq = DnaSequence::from_str("TCTAGATC").unwrap();
+        let options = RestrictionOptions::default().with_methylation(MethylationProfile::ecoli());
+        let map = seq.restriction_map(&database, &options);
+        assert_eq!(map.sites("XbaI").unwrap().len(), 1);
+        assert_eq!(map.cut_count("XbaI"), Some(0));
+        assert_eq!(names(map.non_cutters()), ["XbaI"]);
+    }
+
+    #[test]
+    fn test_isoschizomer_groups() {
+        let database = EnzymeDatabase::builtin();
+        let groups = database.isoschizomer_groups();
+        let group_of = |name: &str| {
+            groups
+                .iter()
+                .find(|g| g.iter().any(|e| e.name == name))
+                .map(|g| names(g.clone()))
+                .unwrap()
+        };
+        assert_eq!(group_of("HpaII"), ["MspI", "HpaII"]);
+        assert_eq!(group_of("XmaI"), ["TspMI", "XmaI"]);
+        assert!(group_of("BsaI_HFv2").len() == 1);
+        assert!(database.collapse_isoschizomers().len() < database.len());
+        assert!(names(database.neoschizomers("SmaI")).contains(&"XmaI"));
+    }
+}
@@ -102,7 +102,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             let strand = strand.unwrap_or(Strand::Watson);
             let seq = DnaSequence::from_str(sequence)?;
             let nucleotides = seq.to_nucleotide_string();
-            let antinucleotides = seq.to_reverse_complement_string();
+            let antinucleotides = seq.to_complement_string();
             let codonstr = seq
                 .as_codons()
                 .iter()